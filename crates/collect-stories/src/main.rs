@@ -1,13 +1,21 @@
 use anyhow::{Context, Result};
 use chrono::{Datelike, Duration, Local, TimeZone, Timelike, Utc};
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use shared::article_cache::ArticleCache;
+use shared::cookies::Browser;
+use shared::raindrop_cache::RaindropCache;
 use shared::{
-    ArticleContent, ClaudeSummarizer, Config, ContentExtractor, ExtractionResult, RaindropClient,
-    ShowInfo, Story, Summary, TopicClusterer,
+    ArticleContent, Bookmark, BookmarkQuery, BookmarkSource, Config, ContentExtractor,
+    ExtractionError, ExtractionResult, LlmProvider, LlmTask, RaindropClient, RateLimiters,
+    Settings, ShowInfo, SiteRules, Story, Summarizer, Summary, SummaryCache, TopicClusterer,
+    UrlResolver,
 };
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::io::{self as stdio, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy)]
 enum Show {
@@ -64,8 +72,11 @@ fn prompt_show_selection() -> Result<Show> {
 
 #[derive(Parser)]
 #[command(name = "collect-stories")]
-#[command(about = "Collect and summarize stories from Raindrop.io for podcast briefing")]
+#[command(about = "Collect and summarize bookmarked stories for podcast briefing")]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Show to collect stories for (twit, mbw, im)
     #[arg(short, long)]
     show: Option<String>,
@@ -73,12 +84,345 @@ struct Args {
     /// Number of days to look back for bookmarks
     #[arg(short, long, default_value = "7")]
     days: i64,
+
+    /// Tag expression overriding the show's query, e.g. "#twit -#skip" or "(#im OR #ai)"
+    #[arg(short, long)]
+    query: Option<String>,
+
+    /// Where to collect bookmarks from: raindrop, netscape:PATH, json:PATH or urls:PATH
+    /// (repeat to merge several sources)
+    #[arg(long = "source", default_value = "raindrop")]
+    sources: Vec<String>,
+
+    /// Discard the local Raindrop bookmark cache and fetch everything again
+    #[arg(long)]
+    full_sync: bool,
+
+    /// Browser to read cookies from for paywalled sites (firefox, librewolf,
+    /// floorp, zen, chrome, chromium, brave, vivaldi or edge)
+    #[arg(long, global = true)]
+    browser: Option<Browser>,
+
+    /// Browser profile to read cookies from, e.g. "Profile 1"
+    /// (repeat to merge several; see `cookies profiles`)
+    #[arg(long, global = true, requires = "browser")]
+    browser_profile: Vec<String>,
+
+    /// Netscape-format cookies.txt to read cookies from instead of a browser
+    #[arg(long, global = true, conflicts_with = "browser")]
+    cookies_file: Option<PathBuf>,
+
+    /// Revalidate every cached article with the publisher instead of trusting the cache TTL
+    #[arg(long)]
+    refresh_articles: bool,
+
+    /// Summarize every article again instead of reusing cached summaries
+    #[arg(long)]
+    refresh_summaries: bool,
+
+    /// Summarize everything as one Message Batch: cheaper and clear of
+    /// per-minute rate limits, but can take minutes (for unattended runs)
+    #[arg(long)]
+    batch: bool,
+
+    /// Tag the Raindrop bookmarks used in the briefing once it has been built
+    #[arg(long)]
+    mark_used: bool,
+
+    /// Tag added by --mark-used ({slug} and {date} are replaced with the show slug and date)
+    #[arg(long, default_value = "{slug}-used-{date}")]
+    used_tag: String,
+
+    /// Also write each story's AI summary into its raindrop note
+    #[arg(long, requires = "mark_used")]
+    write_summaries: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Inspect the cookies used for paywalled sites
+    Cookies {
+        #[command(subcommand)]
+        command: CookiesCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum CookiesCommand {
+    /// Report each paywalled domain's cookies and whether its check article
+    /// comes back in full
+    Check,
+    /// List the browser profiles cookies can be read from
+    Profiles,
+}
+
+/// Render a summary as plain text for a Raindrop note
+fn summary_note(summary: &Summary) -> Option<String> {
+    match summary {
+        Summary::Success {
+            points,
+            quote,
+            speaker,
+        } => {
+            let mut lines: Vec<String> = quote
+                .iter()
+                .map(|q| shared::summarizer::quote_line(q, speaker.as_deref()))
+                .collect();
+            lines.extend(points.iter().map(|p| format!("- {}", p)));
+            Some(lines.join("\n"))
+        }
+        Summary::Insufficient | Summary::Failed(_) => None,
+    }
+}
+
+fn build_source(spec: &str, config: &Config, full_sync: bool) -> Result<Box<dyn BookmarkSource>> {
+    if spec == "raindrop" {
+        let client = RaindropClient::new(config.raindrop_token()?.to_string())?;
+
+        // A broken cache shouldn't stop the run; fall back to searching the API
+        let cache = RaindropCache::open_default().and_then(|cache| {
+            if full_sync {
+                cache.reset()?;
+            }
+            Ok(cache)
+        });
+        return match cache {
+            Ok(cache) => Ok(Box::new(client.with_cache(cache))),
+            Err(e) => {
+                eprintln!("  Warning: Bookmark cache unavailable: {}", e);
+                Ok(Box::new(client))
+            }
+        };
+    }
+    shared::bookmarks::file_source_from_spec(spec)
+}
+
+/// Cached articles not fetched for this long (or the TTL, if longer) are dropped
+const ARTICLE_CACHE_RETENTION_DAYS: i64 = 30;
+
+fn build_extractor(settings: &Settings, refresh: bool) -> Result<ContentExtractor> {
+    let extractor = configure_extractor(ContentExtractor::new(&settings.cookies)?, settings)?;
+
+    // As with bookmarks, a broken cache just means fetching everything
+    let retention = Duration::days(ARTICLE_CACHE_RETENTION_DAYS).max(settings.cache.article_ttl());
+    let cache = ArticleCache::open_default().and_then(|cache| {
+        cache.prune(Utc::now() - retention)?;
+        Ok(cache)
+    });
+    match cache {
+        Ok(cache) => {
+            let ttl = if refresh {
+                Duration::zero()
+            } else {
+                settings.cache.article_ttl()
+            };
+            Ok(extractor.with_cache(cache, ttl))
+        }
+        Err(e) => {
+            eprintln!("  Warning: Article cache unavailable: {}", e);
+            Ok(extractor)
+        }
+    }
+}
+
+/// Apply the configured per-host limits and site rules
+fn configure_extractor(
+    extractor: ContentExtractor,
+    settings: &Settings,
+) -> Result<ContentExtractor> {
+    Ok(extractor
+        .with_politeness(settings.politeness.clone())
+        .with_site_rules(SiteRules::load()?))
+}
+
+/// Cached summaries older than this are dropped
+const SUMMARY_CACHE_RETENTION_DAYS: i64 = 30;
+
+fn build_summarizer(
+    provider: Arc<dyn LlmProvider>,
+    settings: &Settings,
+    refresh: bool,
+) -> Summarizer {
+    let summarizer = Summarizer::new(provider).with_chunking(settings.summaries.clone());
+
+    // A broken cache just means paying for every summary again
+    let cache = SummaryCache::open_default().and_then(|cache| {
+        cache.prune(Utc::now() - Duration::days(SUMMARY_CACHE_RETENTION_DAYS))?;
+        Ok(cache)
+    });
+    match cache {
+        Ok(cache) => summarizer.with_cache(cache, refresh),
+        Err(e) => {
+            eprintln!("  Warning: Summary cache unavailable: {}", e);
+            summarizer
+        }
+    }
+}
+
+/// Cookies running out within this many days are flagged by `cookies check`
+const COOKIE_EXPIRY_WARNING_DAYS: i64 = 7;
+
+/// Report on the cookies for each configured paywalled domain, and whether a
+/// known article from each still comes back in full
+async fn check_cookies(settings: &Settings) -> Result<()> {
+    let mut domains: Vec<&String> = settings
+        .cookies
+        .domains
+        .iter()
+        .chain(settings.cookies.check.keys())
+        .collect();
+    domains.sort();
+    domains.dedup();
+    if domains.is_empty() {
+        anyhow::bail!(
+            "No paywalled domains configured. Add them under [cookies] in config.toml, e.g.\n\n\
+             [cookies]\n\
+             domains = [\"wsj.com\"]\n\n\
+             [cookies.check]\n\
+             \"wsj.com\" = \"https://www.wsj.com/...\""
+        );
+    }
+
+    let cookie_store = shared::cookies::load_browser_cookies(&settings.cookies)?;
+    let check = |domain: &str| {
+        settings
+            .cookies
+            .check
+            .get(domain)
+            .cloned()
+            .unwrap_or_default()
+    };
+    let reports: Vec<_> = domains
+        .iter()
+        .map(|domain| {
+            let login_cookie = check(domain).login_cookie;
+            shared::cookies::domain_cookies(&cookie_store, domain, login_cookie.as_deref())
+        })
+        .collect();
+    // No article cache, so a lapsed login can't hide behind a cached copy
+    let extractor =
+        configure_extractor(ContentExtractor::with_cookie_store(cookie_store)?, settings)?;
+
+    let now = Utc::now();
+    let mut problems = 0;
+    for (domain, cookies) in domains.iter().zip(reports) {
+        println!("\n{}", domain);
+        let check = check(domain);
+        let which = match &check.login_cookie {
+            Some(name) => format!("the {} login cookie", name),
+            None => "the first to expire".to_string(),
+        };
+        match (cookies.count, cookies.has_login, cookies.expires) {
+            (0, _, _) => {
+                problems += 1;
+                println!("  ✗ No cookies - log in to the site in your browser");
+            }
+            (count, Some(false), _) => {
+                problems += 1;
+                println!(
+                    "  ✗ {} cookies, but no {} cookie - log in to the site in your browser",
+                    count,
+                    check.login_cookie.as_deref().unwrap_or_default()
+                );
+            }
+            (count, _, Some(expires)) => {
+                let days = (expires - now).num_days();
+                let mark = if days < COOKIE_EXPIRY_WARNING_DAYS {
+                    "⚠"
+                } else {
+                    "✓"
+                };
+                println!(
+                    "  {} {} cookies, {} lasting until {} ({} days)",
+                    mark,
+                    count,
+                    which,
+                    expires.with_timezone(&Local).format("%Y-%m-%d"),
+                    days
+                );
+            }
+            (count, _, None) => println!(
+                "  ✓ {} cookies, {} ending with the browser session",
+                count, which
+            ),
+        }
+
+        let Some(url) = check.url else {
+            println!("  - No article to check (add one under [cookies.check])");
+            continue;
+        };
+        match extractor.fetch_article_content(&url).await {
+            ExtractionResult::Success(content) => {
+                println!("  ✓ Full article ({} chars): {}", content.text.len(), url);
+            }
+            ExtractionResult::Failed(error) => {
+                problems += 1;
+                println!("  ✗ {}: {}", error, url);
+            }
+        }
+    }
+
+    if problems > 0 {
+        anyhow::bail!("{} cookie problem(s) found", problems);
+    }
+    println!("\n✅ All cookies look good");
+    Ok(())
+}
+
+/// Print every browser profile with a cookie database
+fn list_profiles() -> Result<()> {
+    let profiles = shared::cookies::list_profiles();
+    if profiles.is_empty() {
+        println!("No browser profiles with cookies found");
+        return Ok(());
+    }
+
+    for profile in profiles {
+        println!(
+            "{:<10} {:<24} {}{}",
+            profile.browser.to_string(),
+            profile.name,
+            profile.path.display(),
+            if profile.is_default {
+                "  (default)"
+            } else {
+                ""
+            }
+        );
+    }
+    println!("\nPick one with --browser and --browser-profile, or under [cookies] in config.toml");
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
+
+    let mut settings = Settings::load()?;
+    if let Some(browser) = args.browser {
+        settings.cookies.browser = Some(browser);
+        settings.cookies.file = None;
+    }
+    // The configured profiles stay unless others are named
+    if !args.browser_profile.is_empty() {
+        settings.cookies.profiles = args.browser_profile;
+    }
+    if let Some(path) = args.cookies_file {
+        settings.cookies.file = Some(path);
+    }
+
+    if let Some(Command::Cookies { command }) = args.command {
+        return match command {
+            CookiesCommand::Check => check_cookies(&settings).await,
+            CookiesCommand::Profiles => list_profiles(),
+        };
+    }
+
     let config = Config::from_env()?;
+    // Both tasks draw on one budget when they call the same API
+    let limiters = RateLimiters::new(settings.llm.rate_limit.clone());
+    let summarize_llm = shared::llm::provider_for(&settings.llm, LlmTask::Summarize, &limiters)?;
+    let cluster_llm = shared::llm::provider_for(&settings.llm, LlmTask::Cluster, &limiters)?;
 
     // Determine which show to use
     let show = if let Some(slug) = args.show {
@@ -88,7 +432,12 @@ async fn main() -> Result<()> {
         prompt_show_selection()?
     };
 
-    let show_info = show.info();
+    let mut show_info = show.info();
+    settings.apply_to_show(&mut show_info);
+    if let Some(query) = args.query {
+        show_info.tag = query;
+    }
+    let query = BookmarkQuery::from_show(&show_info)?;
     println!("\n✓ Selected: {}", show_info.name);
 
     let now = Utc::now();
@@ -108,49 +457,93 @@ async fn main() -> Result<()> {
         )
         .unwrap();
 
-    println!("\n📚 Fetching bookmarks from Raindrop.io...");
-    let raindrop_client = RaindropClient::new(config.raindrop_api_token)?;
-    let bookmarks = raindrop_client
-        .fetch_bookmarks(&show_info.tag, since)
-        .await
-        .context("Failed to fetch bookmarks")?;
+    let mut fetched_bookmarks: Vec<Bookmark> = Vec::new();
+
+    for spec in &args.sources {
+        let source = build_source(spec, &config, args.full_sync)?;
+        println!("\n📚 Fetching bookmarks from {}...", source.name());
+        let fetched = source
+            .fetch_bookmarks(&query, since)
+            .await
+            .with_context(|| format!("Failed to fetch bookmarks from {}", source.name()))?;
 
-    if bookmarks.is_empty() {
+        fetched_bookmarks.extend(fetched);
+    }
+
+    if fetched_bookmarks.is_empty() {
         println!(
-            "No bookmarks found with tag {} in the past {} days.",
+            "No bookmarks found matching {} in the past {} days.",
             show_info.tag, args.days
         );
         return Ok(());
     }
 
+    // Expand short links and strip tracking so the same story saved under
+    // different links is only fetched once
+    println!("\n🔗 Resolving links...");
+    let resolver = UrlResolver::new()?;
+    let links: Vec<String> = fetched_bookmarks.iter().map(|b| b.link.clone()).collect();
+    let resolved = resolver.resolve_all(links).await;
+    let resolved_link = |bookmark: &Bookmark| -> String {
+        resolved
+            .get(&bookmark.link)
+            .cloned()
+            .unwrap_or_else(|| bookmark.link.clone())
+    };
+
+    // The same story is often saved in more than one tool. Dropped
+    // bookmarks remember which link they lost to so they can be tagged with it.
+    let mut kept_links: HashMap<String, String> = HashMap::new();
+    let mut duplicate_of: HashMap<String, String> = HashMap::new();
+    let mut duplicates: Vec<Bookmark> = Vec::new();
+    let mut bookmarks: Vec<Bookmark> = Vec::new();
+    for bookmark in fetched_bookmarks {
+        match kept_links.entry(resolved_link(&bookmark)) {
+            Entry::Occupied(kept) => {
+                if *kept.get() != bookmark.link {
+                    duplicate_of.insert(bookmark.link.clone(), kept.get().clone());
+                }
+                duplicates.push(bookmark);
+            }
+            Entry::Vacant(slot) => {
+                slot.insert(bookmark.link.clone());
+                bookmarks.push(bookmark);
+            }
+        }
+    }
+
     println!("✓ Found {} bookmarks", bookmarks.len());
 
     println!("\n🌐 Extracting article content...");
-    let extractor = ContentExtractor::new()?;
-    let urls: Vec<String> = bookmarks.iter().map(|b| b.link.clone()).collect();
+    let extractor = build_extractor(&settings, args.refresh_articles)?;
+    let urls: Vec<String> = bookmarks.iter().map(&resolved_link).collect();
     let content_results = extractor.fetch_articles_parallel(urls).await;
 
-    // Create maps for successful extractions and paywalled URLs
+    // Create maps for successful extractions and why the rest failed
     let mut content_map: HashMap<String, ArticleContent> = HashMap::new();
-    let mut paywalled_urls: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut extraction_errors: HashMap<String, ExtractionError> = HashMap::new();
 
     for (url, result) in content_results {
         match result {
             ExtractionResult::Success(content) => {
                 content_map.insert(url, content);
             }
-            ExtractionResult::Paywalled => {
-                paywalled_urls.insert(url);
-            }
-            ExtractionResult::Failed(reason) => {
-                log_error(&format!("Failed to extract: {} - {}", url, reason));
+            ExtractionResult::Failed(error) => {
+                // Paywalls are expected; only log failures worth looking into
+                if !error.is_paywall() {
+                    log_error(&format!("Failed to extract: {} - {}", url, error));
+                }
+                extraction_errors.insert(url, error);
             }
         }
     }
 
     let successful_extractions = content_map.len();
-    let paywalled_count = paywalled_urls.len();
-    let failed_count = bookmarks.len() - successful_extractions - paywalled_count;
+    let paywalled_count = extraction_errors
+        .values()
+        .filter(|e| e.is_paywall())
+        .count();
+    let failed_count = extraction_errors.len() - paywalled_count;
 
     println!(
         "✓ Extracted {}/{} articles ({} paywalled, {} failed)",
@@ -164,18 +557,27 @@ async fn main() -> Result<()> {
     let mut summary_map: HashMap<String, Summary> = HashMap::new();
 
     if !content_map.is_empty() {
-        println!("\n🤖 Summarizing articles with Claude AI...");
+        let summarizer = build_summarizer(summarize_llm, &settings, args.refresh_summaries);
+        println!(
+            "\n🤖 Summarizing articles with {}...",
+            summarizer.provider_name()
+        );
         println!("  (This may take a minute...)");
-        let summarizer = ClaudeSummarizer::new(config.anthropic_api_key.clone())?;
 
         let articles_for_summary: Vec<(String, String)> = content_map
             .iter()
             .map(|(url, content)| (url.clone(), content.text.clone()))
             .collect();
 
-        let summary_results = summarizer
-            .summarize_articles_parallel(articles_for_summary)
-            .await;
+        let summary_results = if args.batch {
+            summarizer
+                .summarize_articles_batch(articles_for_summary)
+                .await
+        } else {
+            summarizer
+                .summarize_articles_parallel(articles_for_summary)
+                .await
+        };
 
         summary_map = summary_results.into_iter().collect();
 
@@ -185,71 +587,120 @@ async fn main() -> Result<()> {
             .count();
 
         println!(
-            "✓ Successfully summarized {}/{} articles",
+            "✓ Successfully summarized {}/{} articles ({} from the summary cache)",
             successful_summaries,
-            summary_map.len()
+            summary_map.len(),
+            summarizer.cache_hits()
         );
     }
 
-    // Helper to create fallback summary from Raindrop note or excerpt fields
-    let fallback_summary = |bookmark: &shared::raindrop::Bookmark, reason: &str| -> Summary {
-        // Try note first, then excerpt
-        for field in [&bookmark.note, &bookmark.excerpt] {
-            if let Some(text) = field {
-                if !text.trim().is_empty() {
-                    return Summary::Success {
-                        points: vec![text.clone()],
-                        quote: None,
-                    };
-                }
+    // Helper to create fallback summary from the bookmark excerpt
+    // (the producer's note is kept separately on the story)
+    let fallback_summary = |bookmark: &Bookmark, reason: &str| -> Summary {
+        if let Some(text) = &bookmark.excerpt {
+            if !text.trim().is_empty() {
+                return Summary::Success {
+                    points: vec![text.clone()],
+                    quote: None,
+                    speaker: None,
+                };
             }
         }
         Summary::Failed(reason.to_string())
     };
 
-    // Create stories for ALL bookmarks
-    let stories: Vec<Story> = bookmarks
-        .iter()
-        .map(|bookmark| {
-            // Check if article was paywalled
-            if paywalled_urls.contains(&bookmark.link) {
-                return Story {
-                    title: bookmark.title.clone(),
-                    url: bookmark.link.clone(),
-                    created: bookmark.created.clone(),
-                    summary: fallback_summary(bookmark, "Paywalled - summary unavailable"),
-                };
-            }
+    // Keep the bookmarked link on the story when it isn't the one listed
+    let original_url = |bookmark: &Bookmark, url: &str| -> Option<String> {
+        (bookmark.link != url).then(|| bookmark.link.clone())
+    };
 
-            // Check if we have content
-            if let Some(article_content) = content_map.get(&bookmark.link) {
-                let created = article_content
-                    .published_date
-                    .clone()
-                    .unwrap_or_else(|| bookmark.created.clone());
-
-                let summary = summary_map
-                    .get(&bookmark.link)
-                    .cloned()
-                    .unwrap_or_else(|| fallback_summary(bookmark, "Summarization failed"));
-
-                return Story {
-                    title: bookmark.title.clone(),
-                    url: bookmark.link.clone(),
-                    created,
-                    summary,
-                };
-            }
+    // Create stories for ALL bookmarks
+    let story_for = |bookmark: &Bookmark| -> Story {
+        let link = resolved_link(bookmark);
 
-            // No content extracted - use excerpt if available
-            Story {
+        // Check if extraction failed, keeping the reason for the org file
+        if let Some(error) = extraction_errors.get(&link) {
+            return Story {
                 title: bookmark.title.clone(),
-                url: bookmark.link.clone(),
+                original_url: original_url(bookmark, &link),
+                url: link,
                 created: bookmark.created.clone(),
-                summary: fallback_summary(bookmark, "Summary not available"),
+                summary: fallback_summary(bookmark, &error.to_string()),
+                producer_note: bookmark.note.clone(),
+                highlights: bookmark.highlights.clone(),
+                metadata: Default::default(),
+                extraction_error: Some(error.clone()),
+            };
+        }
+
+        // Check if we have content
+        if let Some(article_content) = content_map.get(&link) {
+            let created = article_content
+                .published_date
+                .clone()
+                .unwrap_or_else(|| bookmark.created.clone());
+
+            let summary = summary_map
+                .get(&link)
+                .cloned()
+                .unwrap_or_else(|| fallback_summary(bookmark, "Summarization failed"));
+
+            // URL-list bookmarks often have no title beyond the link itself
+            let untitled = bookmark.title.trim().is_empty() || bookmark.title == bookmark.link;
+            let title = match &article_content.metadata.headline {
+                Some(headline) if untitled => headline.clone(),
+                _ => bookmark.title.clone(),
+            };
+
+            // Two short links can turn out to be the same article
+            let url = shared::urls::canonical_url(
+                &link,
+                article_content.metadata.canonical_url.as_deref(),
+            );
+
+            return Story {
+                title,
+                original_url: original_url(bookmark, &url),
+                url,
+                created,
+                summary,
+                producer_note: bookmark.note.clone(),
+                highlights: bookmark.highlights.clone(),
+                metadata: article_content.metadata.clone(),
+                extraction_error: None,
+            };
+        }
+
+        // No content extracted - use excerpt if available
+        Story {
+            title: bookmark.title.clone(),
+            original_url: original_url(bookmark, &link),
+            url: link,
+            created: bookmark.created.clone(),
+            summary: fallback_summary(bookmark, "Summary not available"),
+            producer_note: bookmark.note.clone(),
+            highlights: bookmark.highlights.clone(),
+            metadata: Default::default(),
+            extraction_error: None,
+        }
+    };
+
+    let mut kept_urls: HashMap<String, String> = HashMap::new();
+    let mut stories: Vec<Story> = Vec::new();
+    for bookmark in &bookmarks {
+        let story = story_for(bookmark);
+        match kept_urls.entry(story.url.clone()) {
+            Entry::Occupied(kept) => {
+                if *kept.get() != bookmark.link {
+                    duplicate_of.insert(bookmark.link.clone(), kept.get().clone());
+                }
             }
-        })
-        .collect();
+            Entry::Vacant(slot) => {
+                slot.insert(bookmark.link.clone());
+                stories.push(story);
+            }
+        }
+    }
 
     println!(
         "\n📊 Total stories: {} ({}  successfully summarized, {} failed)",
@@ -265,7 +716,7 @@ async fn main() -> Result<()> {
     );
 
     println!("\n🔗 Clustering stories by topic...");
-    let clusterer = TopicClusterer::new(config.anthropic_api_key)?;
+    let clusterer = TopicClusterer::new(cluster_llm);
     let topics = clusterer
         .cluster_stories(stories)
         .await
@@ -294,5 +745,60 @@ async fn main() -> Result<()> {
         org_filepath.display()
     );
 
+    if args.mark_used {
+        let used_tag = args
+            .used_tag
+            .replace("{slug}", &show_info.slug)
+            .replace("{date}", &show_date.format("%Y-%m-%d").to_string());
+        println!(
+            "\n🏷️  Tagging used bookmarks in Raindrop.io with {}...",
+            used_tag
+        );
+
+        let raindrop_client = RaindropClient::new(config.raindrop_token()?.to_string())?;
+        let summaries: HashMap<&str, &Summary> = topics
+            .iter()
+            .flat_map(|topic| &topic.stories)
+            .map(|story| {
+                let link = story.original_url.as_deref().unwrap_or(&story.url);
+                (link, &story.summary)
+            })
+            .collect();
+
+        let mut marked = 0;
+        for bookmark in bookmarks
+            .iter()
+            .chain(&duplicates)
+            .filter(|b| b.source == "raindrop")
+        {
+            // Duplicates share the summary of the story that kept the URL
+            let mut link = bookmark.link.as_str();
+            while let Some(kept) = duplicate_of.get(link) {
+                link = kept;
+            }
+            let Some(summary) = summaries.get(link) else {
+                continue;
+            };
+            let note = if args.write_summaries {
+                summary_note(summary)
+            } else {
+                None
+            };
+
+            match raindrop_client
+                .mark_used(bookmark, &used_tag, note.as_deref())
+                .await
+            {
+                Ok(()) => marked += 1,
+                Err(e) => log_error(&format!("Failed to tag {}: {}", bookmark.link, e)),
+            }
+
+            // Stay well under Raindrop's 120 requests/minute limit
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+
+        println!("✓ Tagged {} bookmarks", marked);
+    }
+
     Ok(())
 }
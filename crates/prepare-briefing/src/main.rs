@@ -1,7 +1,8 @@
 use anyhow::{Context, Result};
 use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDate, TimeZone, Timelike, Utc};
 use clap::Parser;
-use shared::{Story, Summary, Topic};
+use shared::summarizer::split_quote_line;
+use shared::{ExtractionError, Story, Summary, Topic};
 use std::fs::{self, OpenOptions};
 use std::io::{self, Write as _};
 use std::path::{Path, PathBuf};
@@ -251,7 +252,7 @@ fn parse_org_mode(content: &str) -> Result<(String, Vec<Topic>)> {
     let mut current_story: Option<Story> = None;
     let mut current_section: Option<String> = None;
     let mut summary_points: Vec<String> = Vec::new();
-    let mut quote: Option<String> = None;
+    let mut quote: Option<(String, Option<String>)> = None;
 
     for line in lines {
         let trimmed = line.trim();
@@ -313,6 +314,11 @@ fn parse_org_mode(content: &str) -> Result<(String, Vec<Topic>)> {
                 url: String::new(),
                 created: String::new(),
                 summary: Summary::Insufficient,
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: Default::default(),
+                extraction_error: None,
+                original_url: None,
             });
             current_section = None;
             summary_points.clear();
@@ -335,17 +341,64 @@ fn parse_org_mode(content: &str) -> Result<(String, Vec<Topic>)> {
                             story.url = trimmed.to_string();
                         }
                     }
+                    "Original URL" => {
+                        if let Some(ref mut story) = current_story {
+                            story.original_url = Some(trimmed.to_string());
+                        }
+                    }
                     "Date" => {
                         if let Some(ref mut story) = current_story {
                             story.created = trimmed.to_string();
                         }
                     }
+                    "Author" | "Publisher" | "Headline" | "Canonical URL" | "Image"
+                    | "Modified" | "Section" => {
+                        if let Some(ref mut story) = current_story {
+                            // Undo the comma escape added by generate_org_mode
+                            let value =
+                                Some(trimmed.strip_prefix(',').unwrap_or(trimmed).to_string());
+                            let metadata = &mut story.metadata;
+                            match section.as_str() {
+                                "Author" => metadata.author = value,
+                                "Publisher" => metadata.publisher = value,
+                                "Headline" => metadata.headline = value,
+                                "Canonical URL" => metadata.canonical_url = value,
+                                "Image" => metadata.image = value,
+                                "Modified" => metadata.modified_date = value,
+                                _ => metadata.section = value,
+                            }
+                        }
+                    }
+                    "Producer notes" => {
+                        if let Some(ref mut story) = current_story {
+                            // Undo the comma escape added by generate_org_mode
+                            let line = trimmed.strip_prefix(',').unwrap_or(trimmed);
+                            story.producer_note = Some(match story.producer_note.take() {
+                                Some(note) => format!("{}\n{}", note, line),
+                                None => line.to_string(),
+                            });
+                        }
+                    }
+                    "Highlights" => {
+                        if let (Some(ref mut story), Some(highlight)) =
+                            (&mut current_story, trimmed.strip_prefix("- "))
+                        {
+                            story.highlights.push(highlight.trim().to_string());
+                        }
+                    }
                     "Summary" => {
                         // Check if it's a quote line
                         if trimmed.starts_with('"') && !trimmed.starts_with("- ") {
-                            quote = Some(trimmed.to_string());
+                            quote = Some(split_quote_line(trimmed));
                         } else if let Some(point) = trimmed.strip_prefix("- ") {
                             summary_points.push(point.trim().to_string());
+                        } else if let Some(reason) =
+                            trimmed.strip_prefix("Summary not available - ")
+                        {
+                            if let Some(ref mut story) = current_story {
+                                story.extraction_error =
+                                    Some(ExtractionError::from_message(reason));
+                            }
                         }
 
                         // If we have accumulated points, create the summary
@@ -353,7 +406,8 @@ fn parse_org_mode(content: &str) -> Result<(String, Vec<Topic>)> {
                             if let Some(ref mut story) = current_story {
                                 story.summary = Summary::Success {
                                     points: summary_points.clone(),
-                                    quote: quote.clone(),
+                                    quote: quote.as_ref().map(|(q, _)| q.clone()),
+                                    speaker: quote.as_ref().and_then(|(_, s)| s.clone()),
                                 };
                             }
                         }
@@ -512,10 +566,15 @@ https://test.com
 
         let (_, topics) = parse_org_mode(content).unwrap();
 
-        if let Summary::Success { points, quote } = &topics[0].stories[0].summary {
+        if let Summary::Success {
+            points,
+            quote,
+            speaker,
+        } = &topics[0].stories[0].summary
+        {
             assert_eq!(points.len(), 2);
-            assert!(quote.is_some());
-            assert!(quote.as_ref().unwrap().contains("This is a quote"));
+            assert_eq!(quote.as_deref(), Some("This is a quote"));
+            assert_eq!(speaker.as_deref(), Some("Author Name"));
         } else {
             panic!("Expected Summary::Success");
         }
@@ -614,6 +673,158 @@ https://test.com
         assert!(result.unwrap_err().to_string().contains("No topics found"));
     }
 
+    #[test]
+    fn test_parse_org_mode_with_producer_annotations() {
+        let content = r#"#+TITLE: Test Briefing
+
+* Topic
+
+** Story
+
+*** URL
+https://test.com
+
+*** Producer notes
+Lead with this one
+,* Not a heading
+
+*** Highlights
+- The passage to read on air
+- Another passage (ask the panel)
+
+*** Summary
+- Point
+"#;
+
+        let (_, topics) = parse_org_mode(content).unwrap();
+        let story = &topics[0].stories[0];
+
+        assert_eq!(
+            story.producer_note.as_deref(),
+            Some("Lead with this one\n* Not a heading")
+        );
+        assert_eq!(
+            story.highlights,
+            vec![
+                "The passage to read on air",
+                "Another passage (ask the panel)"
+            ]
+        );
+        assert!(matches!(story.summary, Summary::Success { .. }));
+    }
+
+    #[test]
+    fn test_parse_org_mode_round_trips_generated_annotations() {
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![Story {
+                title: "Story".to_string(),
+                url: "https://example.com".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Success {
+                    points: vec!["Point".to_string()],
+                    quote: None,
+                    speaker: None,
+                },
+                producer_note: Some("First line\n*bold* second line".to_string()),
+                highlights: vec!["A passage".to_string()],
+                metadata: shared::ArticleMetadata {
+                    author: Some("Jane Doe".to_string()),
+                    publisher: Some("The Verge".to_string()),
+                    canonical_url: Some("https://example.com".to_string()),
+                    headline: Some("*Exclusive* story".to_string()),
+                    image: Some("https://example.com/lead.jpg".to_string()),
+                    modified_date: Some("2026-02-01T12:00:00+00:00".to_string()),
+                    section: Some("Tech".to_string()),
+                },
+                extraction_error: None,
+                original_url: Some("https://t.co/abc123".to_string()),
+            }],
+        }];
+        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let org = shared::briefing::BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+
+        let (_, parsed) = parse_org_mode(&org).unwrap();
+        let story = &parsed[0].stories[0];
+
+        assert_eq!(
+            story.producer_note.as_deref(),
+            Some("First line\n*bold* second line")
+        );
+        assert_eq!(story.highlights, vec!["A passage"]);
+        assert_eq!(story.url, "https://example.com");
+        assert_eq!(story.original_url.as_deref(), Some("https://t.co/abc123"));
+        assert_eq!(story.metadata, topics[0].stories[0].metadata);
+        assert_eq!(
+            story.metadata.attribution().as_deref(),
+            Some("The Verge's Jane Doe")
+        );
+    }
+
+    #[test]
+    fn test_parse_org_mode_round_trips_extraction_error() {
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![Story {
+                title: "Story".to_string(),
+                url: "https://www.wsj.com/tech/story".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Failed("Summary not available".to_string()),
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: Default::default(),
+                extraction_error: Some(ExtractionError::HttpStatus { status: 403 }),
+                original_url: None,
+            }],
+        }];
+        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let org = shared::briefing::BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+
+        let (_, parsed) = parse_org_mode(&org).unwrap();
+        let story = &parsed[0].stories[0];
+
+        assert_eq!(
+            story.extraction_error,
+            Some(ExtractionError::HttpStatus { status: 403 })
+        );
+        assert!(matches!(story.summary, Summary::Insufficient));
+    }
+
+    #[test]
+    fn test_parse_org_mode_keeps_extraction_error_with_excerpt() {
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![Story {
+                title: "Story".to_string(),
+                url: "https://www.wsj.com/tech/story".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Success {
+                    points: vec!["The bookmark excerpt".to_string()],
+                    quote: None,
+                    speaker: None,
+                },
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: Default::default(),
+                extraction_error: Some(ExtractionError::Paywall),
+                original_url: None,
+            }],
+        }];
+        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let org = shared::briefing::BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+
+        let (_, parsed) = parse_org_mode(&org).unwrap();
+        let story = &parsed[0].stories[0];
+
+        assert_eq!(story.extraction_error, Some(ExtractionError::Paywall));
+        match &story.summary {
+            Summary::Success { points, .. } => {
+                assert_eq!(points, &vec!["The bookmark excerpt".to_string()])
+            }
+            other => panic!("expected the excerpt, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_parse_org_mode_with_date() {
         let content = r#"#+TITLE: Test Briefing
@@ -0,0 +1,229 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::cache_db;
+use crate::extractor::ArticleContent;
+
+/// Bump when `ArticleContent` or its extraction changes enough to invalidate old entries
+const SCHEMA_VERSION: i64 = 2;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS articles (
+        url TEXT PRIMARY KEY,
+        fetched_at TEXT NOT NULL,
+        etag TEXT,
+        last_modified TEXT,
+        rules_hash TEXT,
+        content TEXT NOT NULL
+    );
+";
+
+/// An extracted article plus the validators needed to revalidate it
+#[derive(Debug, Clone)]
+pub struct CachedArticle {
+    pub content: ArticleContent,
+    /// When the page was last fetched or confirmed unchanged
+    pub fetched_at: DateTime<Utc>,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// [`SiteRule::fingerprint`](crate::site_rules::SiteRule::fingerprint) of
+    /// the rule the article was extracted with, if any
+    pub rules_hash: Option<String>,
+}
+
+impl CachedArticle {
+    /// Whether the entry can be served without asking the publisher
+    pub fn is_fresh(&self, ttl: chrono::Duration, now: DateTime<Utc>) -> bool {
+        now - self.fetched_at < ttl
+    }
+}
+
+/// Extracted articles keyed by URL, so reruns don't refetch every page
+pub struct ArticleCache {
+    conn: Mutex<Connection>,
+}
+
+impl ArticleCache {
+    /// Open (or create) the cache database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        // Older tables may lack columns, so they're rebuilt rather than emptied
+        let conn = cache_db::open(
+            path,
+            "article cache",
+            SCHEMA_VERSION,
+            "DROP TABLE IF EXISTS articles;",
+            SCHEMA,
+        )?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Open the cache in the default stories directory
+    pub fn open_default() -> Result<Self> {
+        let path = crate::io::get_default_stories_dir()?.join("article-cache.sqlite");
+        Self::open(&path)
+    }
+
+    pub fn get(&self, url: &str) -> Result<Option<CachedArticle>> {
+        let conn = self.conn.lock().unwrap();
+        let row = conn
+            .query_row(
+                "SELECT fetched_at, etag, last_modified, rules_hash, content
+                 FROM articles WHERE url = ?1",
+                [url],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        let Some((fetched_at, etag, last_modified, rules_hash, content)) = row else {
+            return Ok(None);
+        };
+
+        // Treat unreadable entries as missing; the next fetch replaces them
+        let (Ok(fetched_at), Ok(content)) = (
+            DateTime::parse_from_rfc3339(&fetched_at),
+            serde_json::from_str::<ArticleContent>(&content),
+        ) else {
+            return Ok(None);
+        };
+
+        Ok(Some(CachedArticle {
+            content,
+            fetched_at: fetched_at.with_timezone(&Utc),
+            etag,
+            last_modified,
+            rules_hash,
+        }))
+    }
+
+    pub fn put(&self, url: &str, article: &CachedArticle) -> Result<()> {
+        let content = serde_json::to_string(&article.content)?;
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO articles
+                 (url, fetched_at, etag, last_modified, rules_hash, content)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                url,
+                cache_db::timestamp(article.fetched_at),
+                article.etag,
+                article.last_modified,
+                article.rules_hash,
+                content
+            ],
+        )
+        .context("Failed to update article cache")?;
+        Ok(())
+    }
+
+    /// Drop entries not fetched since `before`, returning how many were removed
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute(
+            "DELETE FROM articles WHERE fetched_at < ?1",
+            [cache_db::timestamp(before)],
+        )?;
+        Ok(removed)
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM articles", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::ArticleMetadata;
+    use chrono::TimeZone;
+
+    fn article(text: &str, fetched_at: DateTime<Utc>) -> CachedArticle {
+        CachedArticle {
+            content: ArticleContent {
+                text: text.to_string(),
+                published_date: Some("2026-02-01T00:00:00+00:00".to_string()),
+                metadata: ArticleMetadata {
+                    author: Some("Jane Doe".to_string()),
+                    ..Default::default()
+                },
+            },
+            fetched_at,
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            rules_hash: Some("rules".to_string()),
+        }
+    }
+
+    fn open_temp() -> (tempfile::TempDir, ArticleCache) {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArticleCache::open(&dir.path().join("articles.sqlite")).unwrap();
+        (dir, cache)
+    }
+
+    #[test]
+    fn test_put_and_get_round_trip() {
+        let (_dir, cache) = open_temp();
+        let fetched_at = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        cache
+            .put("https://example.com/a", &article("Body", fetched_at))
+            .unwrap();
+
+        let cached = cache.get("https://example.com/a").unwrap().unwrap();
+        assert_eq!(cached.content.text, "Body");
+        assert_eq!(cached.content.metadata.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(cached.fetched_at, fetched_at);
+        assert_eq!(cached.etag.as_deref(), Some("\"abc\""));
+        assert_eq!(cached.rules_hash.as_deref(), Some("rules"));
+        assert!(cache.get("https://example.com/missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let fetched_at = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let cached = article("Body", fetched_at);
+        let ttl = chrono::Duration::hours(24);
+
+        assert!(cached.is_fresh(ttl, fetched_at + chrono::Duration::hours(23)));
+        assert!(!cached.is_fresh(ttl, fetched_at + chrono::Duration::hours(25)));
+        assert!(!cached.is_fresh(chrono::Duration::zero(), fetched_at));
+    }
+
+    #[test]
+    fn test_prune() {
+        let (_dir, cache) = open_temp();
+        let old = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let recent = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+        cache
+            .put("https://example.com/old", &article("Old", old))
+            .unwrap();
+        cache
+            .put("https://example.com/new", &article("New", recent))
+            .unwrap();
+
+        let removed = cache
+            .prune(Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap())
+            .unwrap();
+
+        assert_eq!(removed, 1);
+        assert_eq!(cache.len().unwrap(), 1);
+    }
+}
@@ -0,0 +1,575 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::query::BookmarkQuery;
+
+/// A bookmark normalized from any source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    /// Identifier within the originating source (e.g. the Raindrop `_id`)
+    pub id: String,
+    /// Name of the source the bookmark came from
+    pub source: String,
+    pub title: String,
+    pub link: String,
+    pub excerpt: Option<String>,
+    /// The producer's own note about the story
+    pub note: Option<String>,
+    /// Passages the producer highlighted in the article
+    #[serde(default)]
+    pub highlights: Vec<String>,
+    pub tags: Vec<String>,
+    /// RFC 3339 timestamp of when the bookmark was saved
+    pub created: String,
+}
+
+/// Anywhere stories can be collected from
+#[async_trait]
+pub trait BookmarkSource: Send + Sync {
+    /// Short name used in progress output
+    fn name(&self) -> &str;
+
+    /// Fetch bookmarks matching `query` that were saved after `since`
+    async fn fetch_bookmarks(
+        &self,
+        query: &BookmarkQuery,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Bookmark>>;
+}
+
+/// Compare tags ignoring case and a leading `#`
+pub fn tag_matches(bookmark_tag: &str, wanted: &str) -> bool {
+    bookmark_tag.trim_start_matches('#').to_lowercase()
+        == wanted.trim_start_matches('#').to_lowercase()
+}
+
+fn is_recent(created: &str, since: DateTime<Utc>) -> bool {
+    match DateTime::parse_from_rfc3339(created) {
+        Ok(dt) => dt.with_timezone(&Utc) > since,
+        // Keep undated bookmarks rather than silently dropping them
+        Err(_) => true,
+    }
+}
+
+fn timestamp_to_rfc3339(secs: i64) -> Option<String> {
+    Utc.timestamp_opt(secs, 0)
+        .single()
+        .map(|dt| dt.to_rfc3339())
+}
+
+fn non_empty(text: Option<String>) -> Option<String> {
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Flatten a highlighted passage and the producer's comment on it into one line
+pub fn format_highlight(text: String, note: Option<String>) -> Option<String> {
+    let text = non_empty(Some(text))?
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    match non_empty(note) {
+        Some(note) => Some(format!(
+            "{} ({})",
+            text,
+            note.split_whitespace().collect::<Vec<_>>().join(" ")
+        )),
+        None => Some(text),
+    }
+}
+
+/// Bookmarks exported from a browser as a Netscape bookmarks HTML file
+pub struct NetscapeSource {
+    path: PathBuf,
+}
+
+impl NetscapeSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn parse(html: &str) -> Vec<Bookmark> {
+        let document = Html::parse_document(html);
+        let link_selector = Selector::parse("a[href]").unwrap();
+
+        document
+            .select(&link_selector)
+            .enumerate()
+            .filter_map(|(index, link)| {
+                let attrs = link.value();
+                let href = attrs.attr("href")?.trim();
+                if !href.starts_with("http") {
+                    return None;
+                }
+
+                let tags = attrs
+                    .attr("tags")
+                    .map(|t| {
+                        t.split(',')
+                            .map(|tag| tag.trim().to_string())
+                            .filter(|tag| !tag.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                let created = attrs
+                    .attr("add_date")
+                    .and_then(|d| d.trim().parse::<i64>().ok())
+                    .and_then(timestamp_to_rfc3339)
+                    .unwrap_or_default();
+
+                let title = link.text().collect::<String>().trim().to_string();
+
+                Some(Bookmark {
+                    id: index.to_string(),
+                    source: "netscape".to_string(),
+                    title: if title.is_empty() {
+                        href.to_string()
+                    } else {
+                        title
+                    },
+                    link: href.to_string(),
+                    excerpt: None,
+                    note: non_empty(Self::description_for(link)),
+                    highlights: Vec::new(),
+                    tags,
+                    created,
+                })
+            })
+            .collect()
+    }
+
+    /// The `<DD>` following a bookmark's `<DT>` holds its description
+    fn description_for(link: ElementRef) -> Option<String> {
+        let dt = link.parent().and_then(ElementRef::wrap)?;
+        let dd = dt.next_siblings().find_map(ElementRef::wrap)?;
+        if dd.value().name() != "dd" {
+            return None;
+        }
+        Some(dd.text().collect::<String>())
+    }
+}
+
+#[async_trait]
+impl BookmarkSource for NetscapeSource {
+    fn name(&self) -> &str {
+        "Netscape bookmarks"
+    }
+
+    async fn fetch_bookmarks(
+        &self,
+        query: &BookmarkQuery,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Bookmark>> {
+        let html = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read bookmarks file: {}", self.path.display()))?;
+
+        Ok(Self::parse(&html)
+            .into_iter()
+            .filter(|b| query.matches_tags(&b.tags) && query.allows_link(&b.link))
+            .filter(|b| is_recent(&b.created, since))
+            .collect())
+    }
+}
+
+#[derive(Deserialize)]
+struct PocketExport {
+    list: HashMap<String, PocketItem>,
+}
+
+#[derive(Deserialize)]
+struct PocketItem {
+    item_id: String,
+    #[serde(default)]
+    given_url: String,
+    #[serde(default)]
+    resolved_url: Option<String>,
+    #[serde(default)]
+    given_title: Option<String>,
+    #[serde(default)]
+    resolved_title: Option<String>,
+    #[serde(default)]
+    excerpt: Option<String>,
+    #[serde(default)]
+    time_added: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OmnivoreItem {
+    id: String,
+    url: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    saved_at: Option<String>,
+    #[serde(default)]
+    highlights: Vec<OmnivoreHighlight>,
+}
+
+#[derive(Deserialize)]
+struct OmnivoreHighlight {
+    #[serde(default)]
+    quote: Option<String>,
+    #[serde(default)]
+    annotation: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonExport {
+    Pocket(PocketExport),
+    Omnivore(Vec<OmnivoreItem>),
+}
+
+/// Bookmarks exported as JSON from Pocket (API `list` format) or Omnivore
+pub struct JsonExportSource {
+    path: PathBuf,
+}
+
+impl JsonExportSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn parse(json: &str) -> Result<Vec<Bookmark>> {
+        let export: JsonExport = serde_json::from_str(json)
+            .context("Unrecognized JSON export (expected Pocket or Omnivore format)")?;
+
+        let bookmarks = match export {
+            JsonExport::Pocket(pocket) => pocket
+                .list
+                .into_values()
+                .map(|item| {
+                    let link = non_empty(item.resolved_url).unwrap_or(item.given_url);
+                    let title = non_empty(item.resolved_title)
+                        .or(non_empty(item.given_title))
+                        .unwrap_or_else(|| link.clone());
+                    Bookmark {
+                        id: item.item_id,
+                        source: "pocket".to_string(),
+                        title,
+                        link,
+                        excerpt: non_empty(item.excerpt),
+                        note: None,
+                        highlights: Vec::new(),
+                        tags: item.tags.into_keys().collect(),
+                        created: item
+                            .time_added
+                            .and_then(|t| t.parse::<i64>().ok())
+                            .and_then(timestamp_to_rfc3339)
+                            .unwrap_or_default(),
+                    }
+                })
+                .collect(),
+            JsonExport::Omnivore(items) => items
+                .into_iter()
+                .map(|item| Bookmark {
+                    id: item.id,
+                    source: "omnivore".to_string(),
+                    title: non_empty(item.title).unwrap_or_else(|| item.url.clone()),
+                    link: item.url,
+                    excerpt: non_empty(item.description),
+                    note: None,
+                    highlights: item
+                        .highlights
+                        .into_iter()
+                        .filter_map(|h| format_highlight(h.quote?, h.annotation))
+                        .collect(),
+                    tags: item.labels,
+                    created: item.saved_at.unwrap_or_default(),
+                })
+                .collect(),
+        };
+
+        Ok(bookmarks)
+    }
+}
+
+#[async_trait]
+impl BookmarkSource for JsonExportSource {
+    fn name(&self) -> &str {
+        "JSON export"
+    }
+
+    async fn fetch_bookmarks(
+        &self,
+        query: &BookmarkQuery,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Bookmark>> {
+        let json = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read export file: {}", self.path.display()))?;
+
+        Ok(Self::parse(&json)?
+            .into_iter()
+            .filter(|b| b.link.starts_with("http"))
+            .filter(|b| query.matches_tags(&b.tags) && query.allows_link(&b.link))
+            .filter(|b| is_recent(&b.created, since))
+            .collect())
+    }
+}
+
+/// A plain text file with one URL per line, optionally followed by a title.
+///
+/// The file is assumed to be curated for a single show, so every URL is
+/// returned regardless of tag or date (excluded domains still apply).
+pub struct UrlListSource {
+    path: PathBuf,
+}
+
+impl UrlListSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn parse(text: &str, created: &str) -> Vec<Bookmark> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let (link, title) = match line.split_once(char::is_whitespace) {
+                    Some((link, title)) => (link, title.trim()),
+                    None => (line, ""),
+                };
+                if !link.starts_with("http") {
+                    return None;
+                }
+                Some((link, title))
+            })
+            .enumerate()
+            .map(|(index, (link, title))| Bookmark {
+                id: index.to_string(),
+                source: "urls".to_string(),
+                title: if title.is_empty() { link } else { title }.to_string(),
+                link: link.to_string(),
+                excerpt: None,
+                note: None,
+                highlights: Vec::new(),
+                tags: Vec::new(),
+                created: created.to_string(),
+            })
+            .collect()
+    }
+
+    fn modified_time(path: &Path) -> String {
+        fs::metadata(path)
+            .and_then(|m| m.modified())
+            .map(|t| DateTime::<Utc>::from(t).to_rfc3339())
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl BookmarkSource for UrlListSource {
+    fn name(&self) -> &str {
+        "URL list"
+    }
+
+    async fn fetch_bookmarks(
+        &self,
+        query: &BookmarkQuery,
+        _since: DateTime<Utc>,
+    ) -> Result<Vec<Bookmark>> {
+        let text = fs::read_to_string(&self.path)
+            .with_context(|| format!("Failed to read URL list: {}", self.path.display()))?;
+
+        Ok(Self::parse(&text, &Self::modified_time(&self.path))
+            .into_iter()
+            .filter(|b| query.allows_link(&b.link))
+            .collect())
+    }
+}
+
+/// Build a file-based source from a `kind:path` spec such as `netscape:bookmarks.html`
+pub fn file_source_from_spec(spec: &str) -> Result<Box<dyn BookmarkSource>> {
+    let (kind, path) = spec
+        .split_once(':')
+        .with_context(|| format!("Invalid source '{}'. Expected kind:path", spec))?;
+
+    match kind {
+        "netscape" => Ok(Box::new(NetscapeSource::new(path))),
+        "json" | "pocket" | "omnivore" => Ok(Box::new(JsonExportSource::new(path))),
+        "urls" => Ok(Box::new(UrlListSource::new(path))),
+        _ => anyhow::bail!(
+            "Unknown source kind '{}'. Use netscape, json, pocket, omnivore or urls",
+            kind
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tag_matches_ignores_case_and_hash() {
+        assert!(tag_matches("TWiT", "#twit"));
+        assert!(tag_matches("#mbw", "MBW"));
+        assert!(!tag_matches("twitter", "#twit"));
+    }
+
+    #[test]
+    fn test_netscape_parse() {
+        let html = r#"<!DOCTYPE NETSCAPE-Bookmark-file-1>
+<META HTTP-EQUIV="Content-Type" CONTENT="text/html; charset=UTF-8">
+<TITLE>Bookmarks</TITLE>
+<DL><p>
+    <DT><A HREF="https://example.com/a" ADD_DATE="1769904000" TAGS="twit,apple">Story A</A>
+    <DD>Worth a segment
+    <DT><A HREF="https://example.com/b" ADD_DATE="1769904000">Story B</A>
+    <DT><A HREF="javascript:void(0)">Bookmarklet</A>
+</DL><p>"#;
+
+        let bookmarks = NetscapeSource::parse(html);
+
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].title, "Story A");
+        assert_eq!(bookmarks[0].link, "https://example.com/a");
+        assert_eq!(bookmarks[0].tags, vec!["twit", "apple"]);
+        assert_eq!(bookmarks[0].note.as_deref(), Some("Worth a segment"));
+        assert!(bookmarks[0].created.starts_with("2026-02-01"));
+        assert!(bookmarks[1].tags.is_empty());
+        assert!(bookmarks[1].note.is_none());
+    }
+
+    #[test]
+    fn test_json_export_pocket() {
+        let json = r#"{"status": 1, "list": {"42": {
+            "item_id": "42",
+            "given_url": "https://example.com/given",
+            "resolved_url": "https://example.com/resolved",
+            "given_title": "",
+            "resolved_title": "Resolved Title",
+            "excerpt": "An excerpt",
+            "time_added": "1769904000",
+            "tags": {"twit": {"item_id": "42", "tag": "twit"}}
+        }}}"#;
+
+        let bookmarks = JsonExportSource::parse(json).unwrap();
+
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].source, "pocket");
+        assert_eq!(bookmarks[0].link, "https://example.com/resolved");
+        assert_eq!(bookmarks[0].title, "Resolved Title");
+        assert_eq!(bookmarks[0].excerpt.as_deref(), Some("An excerpt"));
+        assert_eq!(bookmarks[0].tags, vec!["twit"]);
+    }
+
+    #[test]
+    fn test_json_export_omnivore() {
+        let json = r#"[{
+            "id": "abc",
+            "slug": "story",
+            "title": "Omnivore Story",
+            "description": "Description",
+            "url": "https://example.com/omnivore",
+            "labels": ["IM"],
+            "savedAt": "2026-02-01T12:00:00.000Z",
+            "highlights": [
+                {"quote": "The key\npassage", "annotation": "Read this"},
+                {"quote": null, "annotation": "Orphan note"}
+            ]
+        }]"#;
+
+        let bookmarks = JsonExportSource::parse(json).unwrap();
+
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].source, "omnivore");
+        assert_eq!(bookmarks[0].id, "abc");
+        assert_eq!(bookmarks[0].tags, vec!["IM"]);
+        assert_eq!(bookmarks[0].created, "2026-02-01T12:00:00.000Z");
+        assert_eq!(bookmarks[0].highlights, vec!["The key passage (Read this)"]);
+    }
+
+    #[test]
+    fn test_format_highlight() {
+        assert_eq!(
+            format_highlight("  Passage  ".to_string(), None).as_deref(),
+            Some("Passage")
+        );
+        assert_eq!(
+            format_highlight("Passage".to_string(), Some(" ".to_string())).as_deref(),
+            Some("Passage")
+        );
+        assert!(format_highlight(" ".to_string(), Some("Note".to_string())).is_none());
+    }
+
+    #[test]
+    fn test_json_export_unknown_format() {
+        assert!(JsonExportSource::parse(r#"{"items": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_url_list_parse() {
+        let text = "# Stories for TWiT\n\
+            https://example.com/a\n\
+            \n\
+            https://example.com/b  A titled story\n\
+            not a url\n";
+
+        let bookmarks = UrlListSource::parse(text, "2026-02-01T00:00:00+00:00");
+
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].title, "https://example.com/a");
+        assert_eq!(bookmarks[1].link, "https://example.com/b");
+        assert_eq!(bookmarks[1].title, "A titled story");
+        assert_eq!(bookmarks[1].created, "2026-02-01T00:00:00+00:00");
+    }
+
+    #[tokio::test]
+    async fn test_netscape_source_filters_by_tag_and_date() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bookmarks.html");
+        fs::write(
+            &path,
+            r#"<DL><p>
+<DT><A HREF="https://example.com/new" ADD_DATE="1769904000" TAGS="TWiT">New</A>
+<DT><A HREF="https://example.com/old" ADD_DATE="1700000000" TAGS="twit">Old</A>
+<DT><A HREF="https://example.com/other" ADD_DATE="1769904000" TAGS="mbw">Other</A>
+</DL>"#,
+        )
+        .unwrap();
+
+        let since = Utc.with_ymd_and_hms(2026, 1, 25, 0, 0, 0).unwrap();
+        let query =
+            BookmarkQuery::from_show(&crate::ShowInfo::new("TWiT", "twit", "#twit")).unwrap();
+        let bookmarks = NetscapeSource::new(&path)
+            .fetch_bookmarks(&query, since)
+            .await
+            .unwrap();
+
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].link, "https://example.com/new");
+    }
+
+    #[test]
+    fn test_file_source_from_spec() {
+        assert_eq!(
+            file_source_from_spec("netscape:/tmp/b.html")
+                .unwrap()
+                .name(),
+            "Netscape bookmarks"
+        );
+        assert_eq!(
+            file_source_from_spec("pocket:x.json").unwrap().name(),
+            "JSON export"
+        );
+        assert_eq!(
+            file_source_from_spec("urls:x.txt").unwrap().name(),
+            "URL list"
+        );
+        assert!(file_source_from_spec("raindrop").is_err());
+        assert!(file_source_from_spec("csv:x.csv").is_err());
+    }
+}
@@ -4,7 +4,7 @@ use std::fs;
 use std::path::PathBuf;
 
 use crate::clustering::Topic;
-use crate::summarizer::Summary;
+use crate::summarizer::{quote_line, Summary};
 
 pub struct BriefingGenerator;
 
@@ -107,6 +107,12 @@ impl BriefingGenerator {
         html.push_str("    li { margin: 8px 0; }\n");
         html.push_str("    hr { border: none; border-top: 1px solid #ddd; margin: 30px 0; }\n");
         html.push_str("    .error { color: #e74c3c; font-style: italic; }\n");
+        html.push_str("    .producer-notes { margin: 10px 0; padding: 8px 10px; background-color: #fef9e7; border-left: 4px solid #f1c40f; }\n");
+        html.push_str("    .highlights { margin: 10px 0; }\n");
+        html.push_str("    .highlights blockquote { margin: 6px 0 6px 10px; padding-left: 10px; border-left: 3px solid #27ae60; font-style: italic; }\n");
+        html.push_str(
+            "    .quote .speaker { color: #7f8c8d; font-size: 0.9em; white-space: nowrap; }\n",
+        );
         html.push_str("  </style>\n");
         html.push_str("</head>\n<body>\n");
 
@@ -151,21 +157,70 @@ impl BriefingGenerator {
                     story.url,
                     Self::escape_html(&story.url)
                 ));
+                if let Some(original_url) = &story.original_url {
+                    html.push_str(&format!(
+                        "      <strong>Bookmarked as:</strong> <a href=\"{}\" class=\"link\" target=\"_blank\">{}</a><br>\n",
+                        Self::escape_html(original_url),
+                        Self::escape_html(original_url)
+                    ));
+                }
+                if let Some(attribution) = story.metadata.attribution() {
+                    html.push_str(&format!(
+                        "      <strong>Source:</strong> per {}<br>\n",
+                        Self::escape_html(&attribution)
+                    ));
+                }
                 html.push_str(&format!(
                     "      <strong>Date:</strong> {}\n",
                     Self::format_date(&story.created)
                 ));
                 html.push_str("    </div>\n");
 
+                if let Some(note) = &story.producer_note {
+                    html.push_str(&format!(
+                        "    <div class=\"producer-notes\"><strong>Producer notes:</strong> {}</div>\n",
+                        Self::escape_html(note).replace('\n', "<br>")
+                    ));
+                }
+                if !story.highlights.is_empty() {
+                    html.push_str("    <div class=\"highlights\">\n");
+                    html.push_str("      <strong>Highlights:</strong>\n");
+                    for highlight in &story.highlights {
+                        html.push_str(&format!(
+                            "      <blockquote>{}</blockquote>\n",
+                            Self::escape_html(highlight)
+                        ));
+                    }
+                    html.push_str("    </div>\n");
+                }
+
+                // The excerpt may stand in for the summary, so say why either way
+                if let Some(error) = &story.extraction_error {
+                    html.push_str(&format!(
+                        "    <p class=\"error\">Summary not available - {}</p>\n",
+                        Self::escape_html(&error.to_string())
+                    ));
+                }
                 match &story.summary {
-                    Summary::Success { points, quote } => {
+                    Summary::Success {
+                        points,
+                        quote,
+                        speaker,
+                    } => {
                         html.push_str("    <details class=\"article\" open>\n");
                         html.push_str("      <summary></summary>\n");
-                        if let Some(q) = quote {
-                            html.push_str(&format!(
-                                "      <p><em>{}</em></p>\n",
+                        match (quote, speaker) {
+                            (Some(q), Some(speaker)) => html.push_str(&format!(
+                                "      <p class=\"quote\"><em>\u{201c}{}\u{201d}</em> <span class=\"speaker\">\u{2014} {}</span></p>\n",
+                                Self::escape_html(q),
+                                Self::escape_html(speaker)
+                            )),
+                            // Older summaries have the attribution inside the quote
+                            (Some(q), None) => html.push_str(&format!(
+                                "      <p class=\"quote\"><em>{}</em></p>\n",
                                 Self::escape_html(q)
-                            ));
+                            )),
+                            _ => {}
                         }
                         html.push_str("      <ul>\n");
                         for point in points.iter() {
@@ -178,7 +233,9 @@ impl BriefingGenerator {
                         html.push_str("    </details>\n");
                     }
                     Summary::Insufficient | Summary::Failed(_) => {
-                        html.push_str("    <p class=\"error\">Summary not available</p>\n");
+                        if story.extraction_error.is_none() {
+                            html.push_str("    <p class=\"error\">Summary not available</p>\n");
+                        }
                     }
                 }
 
@@ -288,19 +345,69 @@ impl BriefingGenerator {
 
                 // URL
                 org.push_str(&format!("*** URL\n{}\n\n", story.url));
+                if let Some(original_url) = &story.original_url {
+                    org.push_str(&format!("*** Original URL\n{}\n\n", original_url));
+                }
 
                 // Date
                 if !story.created.is_empty() {
                     org.push_str(&format!("*** Date\n{}\n\n", story.created));
                 }
 
+                // Byline, for crediting the story on air, then the rest of
+                // the page's metadata so prepare-briefing gets all of it back
+                let metadata = &story.metadata;
+                for (name, value) in [
+                    ("Author", &metadata.author),
+                    ("Publisher", &metadata.publisher),
+                    ("Headline", &metadata.headline),
+                    ("Canonical URL", &metadata.canonical_url),
+                    ("Image", &metadata.image),
+                    ("Modified", &metadata.modified_date),
+                    ("Section", &metadata.section),
+                ] {
+                    if let Some(value) = value {
+                        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+                        org.push_str(&format!(
+                            "*** {}\n{}\n\n",
+                            name,
+                            Self::escape_org_line(&value)
+                        ));
+                    }
+                }
+
+                // Producer annotations
+                if let Some(note) = &story.producer_note {
+                    org.push_str("*** Producer notes\n");
+                    for line in note.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                        org.push_str(&format!("{}\n", Self::escape_org_line(line)));
+                    }
+                    org.push('\n');
+                }
+                if !story.highlights.is_empty() {
+                    org.push_str("*** Highlights\n");
+                    for highlight in &story.highlights {
+                        org.push_str(&format!("- {}\n", highlight));
+                    }
+                    org.push('\n');
+                }
+
                 // Summary
                 org.push_str("*** Summary\n");
+                // Say why, so the producer knows whether to open the link,
+                // even when the excerpt stands in for the summary
+                if let Some(error) = &story.extraction_error {
+                    org.push_str(&format!("Summary not available - {}\n", error));
+                }
                 match &story.summary {
-                    Summary::Success { points, quote } => {
-                        // Add quote first if it exists (quote already includes quotes and attribution)
+                    Summary::Success {
+                        points,
+                        quote,
+                        speaker,
+                    } => {
+                        // Add quote first if it exists
                         if let Some(q) = quote {
-                            org.push_str(&format!("{}\n\n", q));
+                            org.push_str(&format!("{}\n\n", quote_line(q, speaker.as_deref())));
                         }
                         // Add bullet points
                         for point in points {
@@ -308,7 +415,9 @@ impl BriefingGenerator {
                         }
                     }
                     Summary::Insufficient | Summary::Failed(_) => {
-                        org.push_str("Summary not available\n");
+                        if story.extraction_error.is_none() {
+                            org.push_str("Summary not available\n");
+                        }
                     }
                 }
                 org.push('\n');
@@ -323,6 +432,15 @@ impl BriefingGenerator {
         org
     }
 
+    /// Keep free text from being read back as an org heading or keyword
+    fn escape_org_line(line: &str) -> String {
+        if line.starts_with('*') || line.starts_with("#+") || line.starts_with(',') {
+            format!(",{}", line)
+        } else {
+            line.to_string()
+        }
+    }
+
     pub fn save_org_mode(content: &str, show_slug: &str, date: DateTime<Utc>) -> Result<PathBuf> {
         let filename = format!("{}-{}.org", show_slug, date.format("%Y-%m-%d"));
 
@@ -493,7 +611,13 @@ mod tests {
                 summary: Summary::Success {
                     points: vec!["Point 1".to_string()],
                     quote: None,
+                    speaker: None,
                 },
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: Default::default(),
+                extraction_error: None,
+                original_url: None,
             }],
         }];
 
@@ -507,6 +631,37 @@ mod tests {
         assert!(html.contains("Point 1"));
     }
 
+    #[test]
+    fn test_generate_html_quote_attribution() {
+        use crate::summarizer::Summary;
+
+        let topics = vec![Topic {
+            title: "Tech News".to_string(),
+            stories: vec![Story {
+                title: "Test Article".to_string(),
+                url: "https://example.com".to_string(),
+                created: "2026-02-01T00:00:00Z".to_string(),
+                summary: Summary::Success {
+                    points: vec!["Point 1".to_string()],
+                    quote: Some("Ship it & see".to_string()),
+                    speaker: Some("Jane Doe".to_string()),
+                },
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: Default::default(),
+                extraction_error: None,
+                original_url: None,
+            }],
+        }];
+
+        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+
+        assert!(html.contains(
+            "<p class=\"quote\"><em>\u{201c}Ship it &amp; see\u{201d}</em> <span class=\"speaker\">\u{2014} Jane Doe</span></p>"
+        ));
+    }
+
     #[test]
     fn test_generate_html_escapes_special_chars() {
         use crate::summarizer::Summary;
@@ -520,7 +675,13 @@ mod tests {
                 summary: Summary::Success {
                     points: vec!["Point \"quoted\"".to_string()],
                     quote: None,
+                    speaker: None,
                 },
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: Default::default(),
+                extraction_error: None,
+                original_url: None,
             }],
         }];
 
@@ -546,12 +707,22 @@ mod tests {
                     url: "https://a.com".to_string(),
                     created: "2026-02-01".to_string(),
                     summary: Summary::Insufficient,
+                    producer_note: None,
+                    highlights: Vec::new(),
+                    metadata: Default::default(),
+                    extraction_error: None,
+                    original_url: None,
                 },
                 Story {
                     title: "Article 2".to_string(),
                     url: "https://b.com".to_string(),
                     created: "2026-02-01".to_string(),
                     summary: Summary::Insufficient,
+                    producer_note: None,
+                    highlights: Vec::new(),
+                    metadata: Default::default(),
+                    extraction_error: None,
+                    original_url: None,
                 },
             ],
         }];
@@ -578,8 +749,14 @@ mod tests {
                 created: "2026-02-01".to_string(),
                 summary: Summary::Success {
                     points: vec!["Point A".to_string(), "Point B".to_string()],
-                    quote: Some("\"A quote\" - Author".to_string()),
+                    quote: Some("A quote".to_string()),
+                    speaker: Some("Author".to_string()),
                 },
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: Default::default(),
+                extraction_error: None,
+                original_url: None,
             }],
         }];
 
@@ -593,7 +770,100 @@ mod tests {
         assert!(org.contains("*** Summary"));
         assert!(org.contains("- Point A"));
         assert!(org.contains("- Point B"));
-        assert!(org.contains("\"A quote\" - Author"));
+        assert!(org.contains("\"A quote\" -- Author"));
+    }
+
+    #[test]
+    fn test_generate_with_producer_annotations() {
+        use crate::summarizer::Summary;
+
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![Story {
+                title: "Story Title".to_string(),
+                url: "https://example.com".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Insufficient,
+                producer_note: Some("Lead story\n* starts with a star".to_string()),
+                highlights: vec!["Read <this> on air".to_string()],
+                metadata: Default::default(),
+                extraction_error: None,
+                original_url: None,
+            }],
+        }];
+
+        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+
+        assert!(org.contains("*** Producer notes\nLead story\n,* starts with a star\n"));
+        assert!(org.contains("*** Highlights\n- Read <this> on air\n"));
+        assert!(
+            html.contains("<strong>Producer notes:</strong> Lead story<br>* starts with a star")
+        );
+        assert!(html.contains("<blockquote>Read &lt;this&gt; on air</blockquote>"));
+    }
+
+    #[test]
+    fn test_generate_with_byline() {
+        use crate::metadata::ArticleMetadata;
+        use crate::summarizer::Summary;
+
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![Story {
+                title: "Story Title".to_string(),
+                url: "https://example.com".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Insufficient,
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: ArticleMetadata {
+                    author: Some("Jane Doe".to_string()),
+                    publisher: Some("The Verge".to_string()),
+                    ..Default::default()
+                },
+                extraction_error: None,
+                original_url: None,
+            }],
+        }];
+
+        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+
+        assert!(org.contains("*** Author\nJane Doe\n\n*** Publisher\nThe Verge\n"));
+        assert!(html.contains("<strong>Source:</strong> per The Verge&#39;s Jane Doe<br>"));
+    }
+
+    #[test]
+    fn test_generate_with_extraction_error() {
+        use crate::extractor::ExtractionError;
+        use crate::summarizer::Summary;
+
+        let topics = vec![Topic {
+            title: "Tech".to_string(),
+            stories: vec![Story {
+                title: "Story Title".to_string(),
+                url: "https://example.com".to_string(),
+                created: "2026-02-01".to_string(),
+                summary: Summary::Failed("Paywalled".to_string()),
+                producer_note: None,
+                highlights: Vec::new(),
+                metadata: Default::default(),
+                extraction_error: Some(ExtractionError::Paywall),
+                original_url: None,
+            }],
+        }];
+
+        let date = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let org = BriefingGenerator::generate_org_mode(&topics, "TWiT", date);
+        let html = BriefingGenerator::generate(&topics, "TWiT", date);
+
+        assert!(org.contains(
+            "*** Summary\nSummary not available - Paywalled - subscription wall on the page\n"
+        ));
+        assert!(html.contains("Summary not available - Paywalled"));
     }
 
     #[test]
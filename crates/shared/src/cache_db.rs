@@ -0,0 +1,33 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::Connection;
+use std::path::Path;
+
+/// Open (or create) the SQLite cache `name` at `path`. Databases written
+/// before `version` get `upgrade` run on them first, then `schema` creates
+/// whatever is missing.
+pub fn open(
+    path: &Path,
+    name: &str,
+    version: i64,
+    upgrade: &str,
+    schema: &str,
+) -> Result<Connection> {
+    let conn = Connection::open(path)
+        .with_context(|| format!("Failed to open {}: {}", name, path.display()))?;
+
+    let current: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if current < version {
+        conn.execute_batch(&format!("{} PRAGMA user_version = {};", upgrade, version))
+            .with_context(|| format!("Failed to upgrade {}", name))?;
+    }
+    conn.execute_batch(schema)
+        .with_context(|| format!("Failed to initialize {}", name))?;
+
+    Ok(conn)
+}
+
+/// One fixed-width UTC format so SQLite can compare timestamps as text
+pub fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
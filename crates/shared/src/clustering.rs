@@ -1,15 +1,34 @@
 use anyhow::{Context, Result};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
+use crate::extractor::ExtractionError;
+use crate::llm::{self, LlmProvider};
+use crate::metadata::ArticleMetadata;
 use crate::summarizer::Summary;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Story {
     pub title: String,
+    /// Canonical article URL, with redirects followed and tracking stripped
     pub url: String,
+    /// The link as bookmarked, when it differs from `url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_url: Option<String>,
     pub created: String,
     pub summary: Summary,
+    /// The producer's own note about the story
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub producer_note: Option<String>,
+    /// Passages the producer highlighted for the host to read
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub highlights: Vec<String>,
+    /// Byline, publisher and other details from the article page
+    #[serde(default, skip_serializing_if = "ArticleMetadata::is_empty")]
+    pub metadata: ArticleMetadata,
+    /// Why the article text couldn't be fetched, if it couldn't
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extraction_error: Option<ExtractionError>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,29 +37,6 @@ pub struct Topic {
     pub stories: Vec<Story>,
 }
 
-#[derive(Serialize)]
-struct ClaudeRequest {
-    model: String,
-    max_tokens: u32,
-    messages: Vec<Message>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: String,
-}
-
-#[derive(Deserialize)]
-struct ClaudeResponse {
-    content: Vec<Content>,
-}
-
-#[derive(Deserialize)]
-struct Content {
-    text: String,
-}
-
 #[derive(Deserialize)]
 struct ClusteringResult {
     topics: Vec<TopicCluster>,
@@ -52,19 +48,14 @@ struct TopicCluster {
     article_indices: Vec<usize>,
 }
 
+/// Groups stories into topics with whichever model is configured for the task
 pub struct TopicClusterer {
-    client: Client,
-    api_key: String,
+    provider: Arc<dyn LlmProvider>,
 }
 
 impl TopicClusterer {
-    pub fn new(api_key: String) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(60))
-            .build()
-            .context("Failed to create HTTP client")?;
-
-        Ok(Self { client, api_key })
+    pub fn new(provider: Arc<dyn LlmProvider>) -> Self {
+        Self { provider }
     }
 
     pub async fn cluster_stories(&self, stories: Vec<Story>) -> Result<Vec<Topic>> {
@@ -79,15 +70,11 @@ impl TopicClusterer {
             }]);
         }
 
-        // Retry logic with exponential backoff for rate limits
+        // Retry logic with exponential backoff for other errors
         for attempt in 0..5 {
             match self.try_cluster_with_ai(&stories).await {
                 Ok(topics) => return Ok(topics),
                 Err(e) => {
-                    let error_msg = e.to_string();
-                    let is_rate_limit =
-                        error_msg.contains("rate_limit") || error_msg.contains("429");
-
                     if attempt == 4 {
                         eprintln!(
                             "Clustering failed after {} attempts: {}, using chronological fallback",
@@ -97,24 +84,25 @@ impl TopicClusterer {
                         return Ok(self.fallback_chronological(stories));
                     }
 
-                    // Longer backoff for rate limits
-                    let backoff = if is_rate_limit {
-                        std::time::Duration::from_secs(15 * (attempt + 1) as u64)
-                    } else {
-                        std::time::Duration::from_millis(1000 * (2_u64.pow(attempt as u32)))
-                    };
-
-                    if is_rate_limit {
-                        eprintln!("Rate limit hit during clustering, waiting {:?} before retry {} of 5...", backoff, attempt + 2);
-                    } else {
+                    // The rate limiter already holds the retry until the
+                    // API's Retry-After has passed
+                    if llm::is_rate_limit(&e) {
                         eprintln!(
-                            "Clustering error (attempt {} of 5): {}, retrying after {:?}...",
-                            attempt + 1,
-                            e,
-                            backoff
+                            "Rate limit hit during clustering, retry {} of 5 when the API allows...",
+                            attempt + 2
                         );
+                        continue;
                     }
 
+                    let backoff =
+                        std::time::Duration::from_millis(1000 * (2_u64.pow(attempt as u32)));
+                    eprintln!(
+                        "Clustering error (attempt {} of 5): {}, retrying after {:?}...",
+                        attempt + 1,
+                        e,
+                        backoff
+                    );
+
                     tokio::time::sleep(backoff).await;
                 }
             }
@@ -175,62 +163,11 @@ Important: Every article index from 0 to {} must appear in exactly one topic."#,
             stories.len() - 1
         );
 
-        let request = ClaudeRequest {
-            model: "claude-3-5-haiku-20241022".to_string(),
-            max_tokens: 2048,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-        };
-
-        let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send request to Claude API")?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| String::from("unknown error"));
-            anyhow::bail!(
-                "Claude API error (status {}): {}",
-                status.as_u16(),
-                error_text
-            );
-        }
-
-        let claude_response = response
-            .json::<ClaudeResponse>()
-            .await
-            .context("Failed to parse Claude API response")?;
-
-        let response_text = claude_response
-            .content
-            .first()
-            .map(|c| c.text.as_str())
-            .unwrap_or("");
-
-        let json_text = if let Some(start) = response_text.find('{') {
-            if let Some(end) = response_text.rfind('}') {
-                &response_text[start..=end]
-            } else {
-                response_text
-            }
-        } else {
-            response_text
-        };
+        let response_text = self.provider.complete(&prompt).await?;
 
         let clustering_result: ClusteringResult =
-            serde_json::from_str(json_text).context("Failed to parse clustering JSON response")?;
+            serde_json::from_value(llm::extract_json_object(&response_text)?)
+                .context("Failed to parse clustering JSON response")?;
 
         let mut topics = Vec::new();
         for cluster in clustering_result.topics {
@@ -262,3 +199,68 @@ Important: Every article index from 0 to {} must appear in exactly one topic."#,
         }]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::FakeProvider;
+
+    fn story(title: &str) -> Story {
+        Story {
+            title: title.to_string(),
+            url: format!("https://example.com/{}", title.to_lowercase()),
+            original_url: None,
+            created: "2026-02-01T09:30:00Z".to_string(),
+            summary: Summary::Success {
+                points: vec![format!("{} news", title)],
+                quote: None,
+                speaker: None,
+            },
+            producer_note: None,
+            highlights: Vec::new(),
+            metadata: ArticleMetadata::default(),
+            extraction_error: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cluster_stories() {
+        let provider = Arc::new(FakeProvider::replying(
+            r#"Here are the topics:
+            {"topics": [
+                {"title": "Apple", "article_indices": [0, 2]},
+                {"title": "Google", "article_indices": [1, 9]}
+            ]}"#,
+        ));
+        let clusterer = TopicClusterer::new(provider.clone());
+
+        let topics = clusterer
+            .cluster_stories(vec![story("iPhone"), story("Gemini"), story("Mac")])
+            .await
+            .unwrap();
+
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[0].title, "Apple");
+        let titles: Vec<_> = topics[0].stories.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, vec!["iPhone", "Mac"]);
+        // Out-of-range indices are dropped
+        assert_eq!(topics[1].stories.len(), 1);
+
+        let prompt = &provider.prompts()[0];
+        assert!(prompt.contains("1: Gemini - Gemini news"));
+        assert!(prompt.contains("from 0 to 2"));
+    }
+
+    #[tokio::test]
+    async fn test_single_story_skips_model() {
+        let provider = Arc::new(FakeProvider::replying("unused"));
+        let clusterer = TopicClusterer::new(provider.clone());
+
+        let topics = clusterer
+            .cluster_stories(vec![story("iPhone")])
+            .await
+            .unwrap();
+        assert_eq!(topics[0].title, "News");
+        assert!(provider.prompts().is_empty());
+    }
+}
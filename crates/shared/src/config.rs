@@ -1,10 +1,17 @@
 use anyhow::{Context, Result};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
 use std::env;
+use std::path::{Path, PathBuf};
+
+use crate::cookies::Browser;
+use crate::llm::{LlmTask, ProviderKind};
+use crate::models::ShowInfo;
 
 #[derive(Debug, Clone)]
 pub struct Config {
-    pub raindrop_api_token: String,
-    pub anthropic_api_key: String,
+    /// Only needed when bookmarks are collected from Raindrop.io
+    pub raindrop_api_token: Option<String>,
 }
 
 impl Config {
@@ -12,26 +19,21 @@ impl Config {
         // Try to load .env from multiple locations
         Self::try_load_dotenv();
 
-        let raindrop_api_token = env::var("RAINDROP_API_TOKEN").context(
+        // The LLM key is read by the configured provider; see `llm::provider_for`
+        let raindrop_api_token = env::var("RAINDROP_API_TOKEN").ok();
+
+        Ok(Self { raindrop_api_token })
+    }
+
+    /// The Raindrop.io token, or an explanation of how to set it up
+    pub fn raindrop_token(&self) -> Result<&str> {
+        self.raindrop_api_token.as_deref().context(
             "RAINDROP_API_TOKEN not found.\n\n\
                 To fix this, create ~/.config/podcast-briefing/.env with:\n  \
                 RAINDROP_API_TOKEN=your_token_here\n  \
                 ANTHROPIC_API_KEY=your_key_here\n\n\
                 Get your Raindrop.io API token from: https://app.raindrop.io/settings/integrations",
-        )?;
-
-        let anthropic_api_key = env::var("ANTHROPIC_API_KEY").context(
-            "ANTHROPIC_API_KEY not found.\n\n\
-                To fix this, create ~/.config/podcast-briefing/.env with:\n  \
-                RAINDROP_API_TOKEN=your_token_here\n  \
-                ANTHROPIC_API_KEY=your_key_here\n\n\
-                Get your Anthropic API key from: https://console.anthropic.com/settings/keys",
-        )?;
-
-        Ok(Self {
-            raindrop_api_token,
-            anthropic_api_key,
-        })
+        )
     }
 
     fn try_load_dotenv() {
@@ -59,3 +61,483 @@ impl Config {
         // If none found, that's okay - environment variables might be set system-wide
     }
 }
+
+/// Optional settings from ~/.config/podcast-briefing/config.toml
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Settings {
+    /// Per-show overrides keyed by show slug
+    #[serde(default)]
+    pub shows: HashMap<String, ShowSettings>,
+    #[serde(default)]
+    pub cache: CacheSettings,
+    #[serde(default)]
+    pub politeness: PolitenessSettings,
+    #[serde(default)]
+    pub cookies: CookieSettings,
+    #[serde(default)]
+    pub llm: LlmSettings,
+    #[serde(default)]
+    pub summaries: SummarySettings,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ShowSettings {
+    /// Tag expression replacing the show's default tag
+    pub query: Option<String>,
+    #[serde(default)]
+    pub collections: Vec<i64>,
+    #[serde(default)]
+    pub exclude_domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheSettings {
+    /// Serve fetched articles from the cache for this long before revalidating
+    #[serde(default = "CacheSettings::default_article_ttl_hours")]
+    pub article_ttl_hours: u64,
+}
+
+impl CacheSettings {
+    fn default_article_ttl_hours() -> u64 {
+        24
+    }
+
+    pub fn article_ttl(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.article_ttl_hours as i64)
+    }
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            article_ttl_hours: Self::default_article_ttl_hours(),
+        }
+    }
+}
+
+/// How hard article fetching may hit each publisher
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolitenessSettings {
+    /// Requests in flight across all hosts
+    pub max_concurrent: usize,
+    /// Requests in flight to any one host
+    pub host_concurrency: usize,
+    /// Minimum gap between requests to the same host
+    pub host_delay_ms: u64,
+    /// Longest `Retry-After` we'll wait out before retrying
+    pub max_retry_after_secs: u64,
+    /// Skip pages robots.txt disallows
+    pub respect_robots: bool,
+    /// Product token matched against robots.txt `User-agent` lines
+    pub robots_user_agent: String,
+    /// Overrides keyed by domain; subdomains match too
+    pub domains: HashMap<String, DomainPoliteness>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DomainPoliteness {
+    pub concurrency: Option<usize>,
+    pub delay_ms: Option<u64>,
+    pub respect_robots: Option<bool>,
+}
+
+impl Default for PolitenessSettings {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 10,
+            host_concurrency: 2,
+            host_delay_ms: 1000,
+            max_retry_after_secs: 60,
+            respect_robots: false,
+            robots_user_agent: "collect-stories".to_string(),
+            domains: HashMap::new(),
+        }
+    }
+}
+
+/// Which browser's cookies to fetch paywalled articles with
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CookieSettings {
+    /// Unset tries the Firefox family, then each Chromium-family browser in turn
+    pub browser: Option<Browser>,
+    /// Profiles to merge cookies from, by name (e.g. "Profile 1" or
+    /// "default-release") or path; the browser's default profile if empty
+    #[serde(alias = "profile", deserialize_with = "one_or_many")]
+    pub profiles: Vec<String>,
+    /// Chromium "Safe Storage" password, for when the Secret Service isn't running
+    pub keyring_password: Option<String>,
+    /// Netscape-format `cookies.txt` to read instead of a browser
+    pub file: Option<PathBuf>,
+    /// Only load cookies for these domains (subdomains match too); all if empty
+    pub domains: Vec<String>,
+    /// What `cookies check` looks at per domain; a plain string is the
+    /// article URL
+    #[serde(deserialize_with = "cookie_checks")]
+    pub check: HashMap<String, CookieCheck>,
+}
+
+/// How `cookies check` tells whether one site's login is still good
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CookieCheck {
+    /// A subscriber-only article that should come back in full
+    pub url: Option<String>,
+    /// The cookie holding the login, whose expiry is the one reported
+    pub login_cookie: Option<String>,
+}
+
+fn cookie_checks<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<HashMap<String, CookieCheck>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum UrlOrCheck {
+        Url(String),
+        Check(CookieCheck),
+    }
+
+    let checks = HashMap::<String, UrlOrCheck>::deserialize(deserializer)?;
+    Ok(checks
+        .into_iter()
+        .map(|(domain, check)| {
+            let check = match check {
+                UrlOrCheck::Url(url) => CookieCheck {
+                    url: Some(url),
+                    login_cookie: None,
+                },
+                UrlOrCheck::Check(check) => check,
+            };
+            (domain, check)
+        })
+        .collect())
+}
+
+/// A single string or a list of them
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+/// How many LLM calls run at once. The limit starts low and rises while the
+/// API reports plenty of budget left, up to `max_concurrent`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitSettings {
+    pub start_concurrent: usize,
+    pub max_concurrent: usize,
+}
+
+impl Default for RateLimitSettings {
+    fn default() -> Self {
+        Self {
+            start_concurrent: 2,
+            max_concurrent: 8,
+        }
+    }
+}
+
+/// How articles too long for one prompt are summarized: split into
+/// overlapping chunks, noted one by one, then merged into one summary
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SummarySettings {
+    /// Articles up to this long are summarized in a single call
+    pub long_article_chars: usize,
+    /// Target size of each chunk of a longer article
+    pub chunk_chars: usize,
+    /// Text each chunk repeats from the end of the one before
+    pub chunk_overlap_chars: usize,
+    /// Articles that would need more chunks get bigger ones instead
+    pub max_chunks: usize,
+    /// Only this much of an article is summarized; the rest is dropped
+    pub max_article_chars: usize,
+}
+
+impl Default for SummarySettings {
+    fn default() -> Self {
+        Self {
+            long_article_chars: 10_000,
+            chunk_chars: 6_000,
+            chunk_overlap_chars: 500,
+            max_chunks: 8,
+            max_article_chars: 100_000,
+        }
+    }
+}
+
+/// The model used for summarizing and clustering. Top-level keys apply to both
+/// tasks; `[llm.summarize]` and `[llm.cluster]` override them per task.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LlmSettings {
+    pub provider: Option<ProviderKind>,
+    pub model: Option<String>,
+    pub base_url: Option<String>,
+    pub api_key_env: Option<String>,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    pub timeout_secs: Option<u64>,
+    pub summarize: LlmTaskSettings,
+    pub cluster: LlmTaskSettings,
+    pub rate_limit: RateLimitSettings,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LlmTaskSettings {
+    pub provider: Option<ProviderKind>,
+    pub model: Option<String>,
+    /// API root, e.g. `http://localhost:11434/v1` for a local Ollama
+    pub base_url: Option<String>,
+    /// Environment variable holding the API key
+    pub api_key_env: Option<String>,
+    pub max_tokens: Option<u32>,
+    pub temperature: Option<f32>,
+    pub timeout_secs: Option<u64>,
+}
+
+impl LlmSettings {
+    /// The task's own settings, falling back to the top-level ones field by field
+    pub fn for_task(&self, task: LlmTask) -> LlmTaskSettings {
+        let task = match task {
+            LlmTask::Summarize => &self.summarize,
+            LlmTask::Cluster => &self.cluster,
+        };
+
+        LlmTaskSettings {
+            provider: task.provider.or(self.provider),
+            model: task.model.clone().or_else(|| self.model.clone()),
+            base_url: task.base_url.clone().or_else(|| self.base_url.clone()),
+            api_key_env: task
+                .api_key_env
+                .clone()
+                .or_else(|| self.api_key_env.clone()),
+            max_tokens: task.max_tokens.or(self.max_tokens),
+            temperature: task.temperature.or(self.temperature),
+            timeout_secs: task.timeout_secs.or(self.timeout_secs),
+        }
+    }
+}
+
+impl Settings {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("podcast-briefing").join("config.toml"))
+    }
+
+    /// Load the settings file if there is one, otherwise use defaults
+    pub fn load() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read settings: {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse settings: {}", path.display()))
+    }
+
+    /// Apply any configured overrides to a show
+    pub fn apply_to_show(&self, show: &mut ShowInfo) {
+        if let Some(settings) = self.shows.get(&show.slug) {
+            if let Some(query) = &settings.query {
+                show.tag = query.clone();
+            }
+            show.collections = settings.collections.clone();
+            show.exclude_domains = settings.exclude_domains.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_apply_to_show() {
+        let settings: Settings = toml::from_str(
+            r##"
+            [shows.twit]
+            query = "#twit -#skip"
+            collections = [123, 456]
+            exclude_domains = ["youtube.com"]
+            "##,
+        )
+        .unwrap();
+
+        let mut twit = ShowInfo::new("This Week in Tech", "twit", "#twit");
+        settings.apply_to_show(&mut twit);
+        assert_eq!(twit.tag, "#twit -#skip");
+        assert_eq!(twit.collections, vec![123, 456]);
+        assert_eq!(twit.exclude_domains, vec!["youtube.com"]);
+
+        let mut mbw = ShowInfo::new("MacBreak Weekly", "mbw", "#mbw");
+        settings.apply_to_show(&mut mbw);
+        assert_eq!(mbw.tag, "#mbw");
+    }
+
+    #[test]
+    fn test_settings_empty_file() {
+        let settings: Settings = toml::from_str("").unwrap();
+        assert!(settings.shows.is_empty());
+        assert_eq!(settings.cache.article_ttl(), chrono::Duration::hours(24));
+    }
+
+    #[test]
+    fn test_settings_politeness() {
+        let settings: Settings = toml::from_str(
+            r#"
+            [politeness]
+            host_delay_ms = 250
+            respect_robots = true
+
+            [politeness.domains."theverge.com"]
+            concurrency = 1
+            delay_ms = 5000
+            "#,
+        )
+        .unwrap();
+
+        let politeness = &settings.politeness;
+        assert_eq!(politeness.host_delay_ms, 250);
+        assert_eq!(politeness.host_concurrency, 2);
+        assert!(politeness.respect_robots);
+        assert_eq!(politeness.domains["theverge.com"].concurrency, Some(1));
+        assert_eq!(politeness.domains["theverge.com"].respect_robots, None);
+    }
+
+    #[test]
+    fn test_settings_cookies() {
+        let settings: Settings = toml::from_str(
+            r#"
+            [cookies]
+            browser = "brave"
+            profiles = ["Profile 1"]
+            domains = ["nytimes.com", "wsj.com"]
+
+            [cookies.check]
+            "wsj.com" = "https://www.wsj.com/tech/some-story"
+            "nytimes.com" = { login_cookie = "NYT-S" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(settings.cookies.browser, Some(Browser::Brave));
+        assert_eq!(settings.cookies.profiles, vec!["Profile 1"]);
+        assert!(settings.cookies.keyring_password.is_none());
+        assert_eq!(settings.cookies.domains, vec!["nytimes.com", "wsj.com"]);
+        assert_eq!(
+            settings.cookies.check["wsj.com"].url.as_deref(),
+            Some("https://www.wsj.com/tech/some-story")
+        );
+        assert_eq!(
+            settings.cookies.check["nytimes.com"],
+            CookieCheck {
+                url: None,
+                login_cookie: Some("NYT-S".to_string()),
+            }
+        );
+
+        assert!(toml::from_str::<Settings>("[cookies]\nbrowser = \"safari\"\n").is_err());
+
+        let settings: Settings = toml::from_str("[cookies]\nprofile = \"Work\"\n").unwrap();
+        assert_eq!(settings.cookies.profiles, vec!["Work"]);
+        let settings: Settings = toml::from_str("[cookies]\nprofiles = \"Work\"\n").unwrap();
+        assert_eq!(settings.cookies.profiles, vec!["Work"]);
+    }
+
+    #[test]
+    fn test_settings_llm() {
+        let settings: Settings = toml::from_str(
+            r#"
+            [llm]
+            provider = "openai"
+            base_url = "http://localhost:11434/v1"
+            model = "llama3.1:8b"
+            temperature = 0.2
+
+            [llm.cluster]
+            model = "qwen2.5:14b"
+            max_tokens = 4096
+
+            [llm.rate_limit]
+            max_concurrent = 4
+            "#,
+        )
+        .unwrap();
+
+        let summarize = settings.llm.for_task(LlmTask::Summarize);
+        assert_eq!(summarize.provider, Some(ProviderKind::OpenAi));
+        assert_eq!(summarize.model.as_deref(), Some("llama3.1:8b"));
+        assert_eq!(summarize.max_tokens, None);
+
+        let cluster = settings.llm.for_task(LlmTask::Cluster);
+        assert_eq!(cluster.model.as_deref(), Some("qwen2.5:14b"));
+        assert_eq!(cluster.max_tokens, Some(4096));
+        assert_eq!(cluster.temperature, Some(0.2));
+        assert_eq!(
+            cluster.base_url.as_deref(),
+            Some("http://localhost:11434/v1")
+        );
+
+        assert_eq!(settings.llm.rate_limit.max_concurrent, 4);
+        assert_eq!(settings.llm.rate_limit.start_concurrent, 2);
+
+        assert!(toml::from_str::<Settings>("[llm.summarize]\nmodle = \"x\"\n").is_err());
+        assert!(toml::from_str::<Settings>("[llm]\nmodle = \"x\"\n").is_err());
+        assert!(toml::from_str::<Settings>("[llm]\nprovider = \"gemini\"\n").is_err());
+    }
+
+    #[test]
+    fn test_settings_summaries() {
+        let settings: Settings =
+            toml::from_str("[summaries]\nlong_article_chars = 20000\nmax_chunks = 4\n").unwrap();
+        assert_eq!(settings.summaries.long_article_chars, 20_000);
+        assert_eq!(settings.summaries.max_chunks, 4);
+        assert_eq!(settings.summaries.chunk_chars, 6_000);
+        assert_eq!(settings.summaries.max_article_chars, 100_000);
+        assert!(toml::from_str::<Settings>("[summaries]\nchunk_size = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_settings_cache_ttl() {
+        let settings: Settings = toml::from_str("[cache]\narticle_ttl_hours = 6\n").unwrap();
+        assert_eq!(settings.cache.article_ttl(), chrono::Duration::hours(6));
+    }
+
+    #[test]
+    fn test_settings_rejects_unknown_show_keys() {
+        let result: Result<Settings, _> = toml::from_str(
+            r#"
+            [shows.twit]
+            querry = "typo"
+            "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_settings_load_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[shows.im]\nquery = \"#im OR #ai\"\n").unwrap();
+
+        let settings = Settings::load_from(&path).unwrap();
+        assert_eq!(settings.shows["im"].query.as_deref(), Some("#im OR #ai"));
+    }
+}
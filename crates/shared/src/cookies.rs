@@ -1,106 +1,646 @@
+use aes::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
 use anyhow::{Context, Result};
 use cookie_store::CookieStore;
+use reqwest::header::HeaderValue;
 use rusqlite::Connection;
-use std::path::PathBuf;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::str::FromStr;
+use std::sync::RwLock;
 use url::Url;
 
-pub fn load_browser_cookies() -> Result<CookieStore> {
+use crate::config::CookieSettings;
+
+/// Browsers whose cookie databases can be read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Browser {
+    Firefox,
+    LibreWolf,
+    Floorp,
+    Zen,
+    Chrome,
+    Chromium,
+    Brave,
+    Vivaldi,
+    Edge,
+}
+
+impl Browser {
+    /// Tried in this order when no browser is configured
+    pub const ALL: [Browser; 9] = [
+        Browser::Firefox,
+        Browser::LibreWolf,
+        Browser::Floorp,
+        Browser::Zen,
+        Browser::Chrome,
+        Browser::Chromium,
+        Browser::Brave,
+        Browser::Vivaldi,
+        Browser::Edge,
+    ];
+
+    /// Firefox and its forks, which share its profile and cookie formats
+    pub fn is_firefox_family(self) -> bool {
+        self.chromium_dir().is_none()
+    }
+
+    /// Directories under the home directory holding a Firefox-family
+    /// browser's `profiles.ini`: native, then Flatpak and Snap installs
+    fn firefox_roots(self) -> &'static [&'static str] {
+        match self {
+            Browser::Firefox => &[
+                ".mozilla/firefox",
+                ".var/app/org.mozilla.firefox/.mozilla/firefox",
+                "snap/firefox/common/.mozilla/firefox",
+            ],
+            Browser::LibreWolf => &[
+                ".librewolf",
+                ".var/app/io.gitlab.librewolf-community/.librewolf",
+            ],
+            Browser::Floorp => &[".floorp", ".var/app/one.ablaze.floorp/.floorp"],
+            Browser::Zen => &[".zen", ".var/app/app.zen_browser.zen/.zen"],
+            _ => &[],
+        }
+    }
+
+    /// Profile root under `~/.config` for Chromium-family browsers
+    fn chromium_dir(self) -> Option<&'static str> {
+        match self {
+            Browser::Firefox | Browser::LibreWolf | Browser::Floorp | Browser::Zen => None,
+            Browser::Chrome => Some("google-chrome"),
+            Browser::Chromium => Some("chromium"),
+            Browser::Brave => Some("BraveSoftware/Brave-Browser"),
+            Browser::Vivaldi => Some("vivaldi"),
+            Browser::Edge => Some("microsoft-edge"),
+        }
+    }
+
+    /// `application` attribute the browser stores its "Safe Storage"
+    /// password under in the Secret Service
+    fn keyring_applications(self) -> &'static [&'static str] {
+        match self {
+            Browser::Chrome => &["chrome"],
+            Browser::Chromium => &["chromium"],
+            Browser::Brave => &["brave"],
+            Browser::Vivaldi => &["vivaldi", "chrome"],
+            Browser::Edge => &["microsoft-edge", "chromium"],
+            _ => &[],
+        }
+    }
+}
+
+impl fmt::Display for Browser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Browser::Firefox => "Firefox",
+            Browser::LibreWolf => "LibreWolf",
+            Browser::Floorp => "Floorp",
+            Browser::Zen => "Zen",
+            Browser::Chrome => "Chrome",
+            Browser::Chromium => "Chromium",
+            Browser::Brave => "Brave",
+            Browser::Vivaldi => "Vivaldi",
+            Browser::Edge => "Edge",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Browser {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Browser::ALL
+            .into_iter()
+            .find(|browser| browser.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Unknown browser: {}. Use firefox, librewolf, floorp, zen, chrome, chromium, brave, vivaldi or edge",
+                    s
+                )
+            })
+    }
+}
+
+/// Load cookies from the configured `cookies.txt` file or browser, or from
+/// the first browser with a cookie database when neither is configured. Only
+/// cookies for the configured `domains` are kept, if any are configured.
+pub fn load_browser_cookies(settings: &CookieSettings) -> Result<CookieStore> {
     let mut cookie_store = CookieStore::default();
 
-    if let Some(firefox_path) = find_firefox_cookies() {
-        match load_firefox_cookies_from_db(&firefox_path, &mut cookie_store) {
-            Ok(count) if count > 0 => {
-                eprintln!("✓ Loaded {} cookies from Firefox", count);
-            }
-            Ok(_) => {
-                eprintln!("  Note: Found Firefox cookies but loaded 0");
-            }
-            Err(e) => {
-                eprintln!("  Warning: Could not load Firefox cookies: {}", e);
+    let Some((source, result)) = read_configured_cookies(settings) else {
+        match settings.browser {
+            Some(browser) => eprintln!(
+                "  Warning: No {} cookies found (paywalled sites may not work)",
+                browser
+            ),
+            None => eprintln!("  Note: No browser cookies found (paywalled sites may not work)"),
+        }
+        return Ok(cookie_store);
+    };
+
+    match result {
+        Ok(cookies) if !cookies.is_empty() => {
+            let found = cookies.len();
+            let count = insert_cookies(&mut cookie_store, cookies, &settings.domains);
+            if count < found && !settings.domains.is_empty() {
+                eprintln!(
+                    "✓ Loaded {} cookies from {} (skipped {} for other domains)",
+                    count,
+                    source,
+                    found - count
+                );
+            } else {
+                eprintln!("✓ Loaded {} cookies from {}", count, source);
             }
         }
-    } else {
-        eprintln!("  Note: No Firefox cookies found (paywalled sites may not work)");
+        Ok(_) => {
+            eprintln!("  Note: Found {} cookies but loaded 0", source);
+        }
+        Err(e) => {
+            eprintln!("  Warning: Could not load {} cookies: {}", source, e);
+        }
     }
 
     Ok(cookie_store)
 }
 
-fn find_firefox_cookies() -> Option<PathBuf> {
-    let home = dirs::home_dir()?;
-    let firefox_dir = home.join(".mozilla/firefox");
+/// Cookies from the configured source, named for messages; `None` if there
+/// was nothing to read
+fn read_configured_cookies(
+    settings: &CookieSettings,
+) -> Option<(String, Result<Vec<BrowserCookie>>)> {
+    if let Some(path) = &settings.file {
+        return Some((path.display().to_string(), load_cookies_txt(path)));
+    }
+
+    let browsers = match settings.browser {
+        Some(browser) => vec![browser],
+        None => Browser::ALL.to_vec(),
+    };
 
-    if !firefox_dir.exists() {
+    let (Some(home), Some(config_dir)) = (dirs::home_dir(), dirs::config_dir()) else {
+        return None;
+    };
+
+    let (profiles, missing) = select_profiles(&browsers, &settings.profiles, &home, &config_dir);
+    for spec in missing {
+        match settings.browser {
+            Some(browser) => eprintln!("  Warning: No {} profile named '{}'", browser, spec),
+            None => eprintln!("  Warning: No browser profile named '{}'", spec),
+        }
+    }
+    if profiles.is_empty() {
         return None;
     }
 
-    // Look for profiles.ini to find the default profile
-    let profiles_ini = firefox_dir.join("profiles.ini");
-    if profiles_ini.exists() {
-        if let Ok(content) = std::fs::read_to_string(&profiles_ini) {
-            let mut current_path: Option<String> = None;
-            let mut is_default = false;
+    let mut by_browser: Vec<(Browser, Vec<&str>)> = Vec::new();
+    for profile in &profiles {
+        match by_browser
+            .iter_mut()
+            .find(|(browser, _)| *browser == profile.browser)
+        {
+            Some((_, names)) => names.push(&profile.name),
+            None => by_browser.push((profile.browser, vec![&profile.name])),
+        }
+    }
+    let source = by_browser
+        .iter()
+        .map(|(browser, names)| format!("{} ({})", browser, names.join(", ")))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let result = load_profiles(&profiles, settings.keyring_password.as_deref());
+    Some((source, result))
+}
 
-            for line in content.lines() {
-                if line.starts_with("Path=") {
-                    current_path = Some(line.trim_start_matches("Path=").to_string());
-                }
-                if line == "Default=1" {
-                    is_default = true;
-                }
-                if line.starts_with('[') && line != "[General]" {
-                    if is_default {
-                        if let Some(path) = current_path {
-                            let profile_dir = firefox_dir.join(&path);
-                            let cookies_path = profile_dir.join("cookies.sqlite");
-                            if cookies_path.exists() {
-                                return Some(cookies_path);
-                            }
-                        }
-                    }
-                    current_path = None;
-                    is_default = false;
-                }
-            }
+/// Cookies from every profile, merged; a profile that can't be read is
+/// skipped as long as another one can
+fn load_profiles(
+    profiles: &[BrowserProfile],
+    keyring_password: Option<&str>,
+) -> Result<Vec<BrowserCookie>> {
+    let mut cookies = Vec::new();
+    let mut last_error = None;
 
-            // Check last section
-            if is_default {
-                if let Some(path) = current_path {
-                    let profile_dir = firefox_dir.join(&path);
-                    let cookies_path = profile_dir.join("cookies.sqlite");
-                    if cookies_path.exists() {
-                        return Some(cookies_path);
-                    }
-                }
+    for profile in profiles {
+        let Some(db_path) = profile.cookie_db() else {
+            continue;
+        };
+        let result = if profile.browser.is_firefox_family() {
+            load_firefox_cookies_from_db(&db_path)
+        } else {
+            load_chromium_cookies_from_db(profile.browser, &db_path, keyring_password)
+        };
+        match result {
+            Ok(found) => cookies.extend(found),
+            Err(e) if profiles.len() > 1 => {
+                eprintln!(
+                    "  Warning: Could not load cookies from {} profile {}: {}",
+                    profile.browser, profile.name, e
+                );
+                last_error = Some(e);
             }
+            Err(e) => return Err(e),
         }
     }
 
-    // Fallback: find any profile with cookies.sqlite
-    if let Ok(entries) = std::fs::read_dir(&firefox_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                let cookies_path = path.join("cookies.sqlite");
-                if cookies_path.exists() {
-                    return Some(cookies_path);
-                }
+    match last_error {
+        Some(e) if cookies.is_empty() => Err(e),
+        _ => Ok(cookies),
+    }
+}
+
+/// Read a Netscape-format `cookies.txt`, as exported by browser extensions
+/// and `yt-dlp --cookies-from-browser`
+fn load_cookies_txt(path: &Path) -> Result<Vec<BrowserCookie>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read cookies file: {}", path.display()))?;
+    Ok(parse_cookies_txt(&content, chrono::Utc::now().timestamp()))
+}
+
+/// One cookie per line: domain, include-subdomains flag, path, secure flag,
+/// expiry (0 for session cookies), name and value, separated by tabs. An
+/// `#HttpOnly_` prefix on the domain marks HttpOnly cookies; other `#`
+/// lines are comments. Expired and malformed lines are skipped.
+fn parse_cookies_txt(content: &str, now: i64) -> Vec<BrowserCookie> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_end_matches('\r');
+            let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+                Some(line) => (line, true),
+                None => (line, false),
+            };
+            if line.trim().is_empty() || line.starts_with('#') {
+                return None;
             }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [domain, include_subdomains, path, secure, expires, name, value] = fields[..]
+            else {
+                return None;
+            };
+            let expires: i64 = expires.trim().parse::<f64>().ok()? as i64;
+            if name.is_empty() || (expires != 0 && expires <= now) {
+                return None;
+            }
+
+            let domain = domain.trim_start_matches('.');
+            let host = if include_subdomains.eq_ignore_ascii_case("TRUE") {
+                format!(".{}", domain)
+            } else {
+                domain.to_string()
+            };
+            Some(BrowserCookie {
+                host,
+                path: path.to_string(),
+                secure: secure.eq_ignore_ascii_case("TRUE"),
+                http_only,
+                expires: (expires != 0).then_some(expires),
+                name: name.to_string(),
+                value: value.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// What the cookie store holds for one site
+#[derive(Debug, Clone, PartialEq)]
+pub struct DomainCookies {
+    /// Unexpired cookies for the domain or its subdomains
+    pub count: usize,
+    /// Whether the named login cookie is among them; `None` if no name was given
+    pub has_login: Option<bool>,
+    /// When the login cookie runs out, else the first of them to; `None` if
+    /// that ends with the browser session
+    pub expires: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Summarize the cookies a site would be sent, to tell whether a login is
+/// still there
+pub fn domain_cookies(
+    cookie_store: &CookieStore,
+    domain: &str,
+    login_cookie: Option<&str>,
+) -> DomainCookies {
+    let domains = [domain.to_string()];
+    let cookies: Vec<_> = cookie_store
+        .iter_unexpired()
+        .filter(|cookie| {
+            cookie
+                .domain
+                .as_cow()
+                .is_some_and(|host| domain_allowed(&host, &domains))
+        })
+        .collect();
+
+    let expiry = |cookie: &&cookie_store::Cookie| match &cookie.expires {
+        cookie_store::CookieExpiration::AtUtc(at) => {
+            chrono::DateTime::from_timestamp(at.unix_timestamp(), 0)
         }
+        cookie_store::CookieExpiration::SessionEnd => None,
+    };
+    let login = login_cookie.and_then(|name| cookies.iter().find(|cookie| cookie.name() == name));
+    let expires = match login {
+        Some(login) => expiry(login),
+        None => cookies.iter().filter_map(expiry).min(),
+    };
+
+    DomainCookies {
+        count: cookies.len(),
+        has_login: login_cookie.map(|_| login.is_some()),
+        expires,
     }
+}
 
-    None
+/// Whether cookies for `host` may be used. An empty allowlist allows every
+/// domain; otherwise the host must be a listed domain or a subdomain of one.
+pub fn domain_allowed(host: &str, domains: &[String]) -> bool {
+    let host = host.trim_start_matches('.').to_lowercase();
+    domains.is_empty()
+        || domains.iter().any(|domain| {
+            let domain = domain.trim().trim_start_matches('.').to_lowercase();
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
 }
 
-fn load_firefox_cookies_from_db(
-    db_path: &PathBuf,
+/// Add the allowed cookies to the store, returning how many went in
+fn insert_cookies(
     cookie_store: &mut CookieStore,
-) -> Result<usize> {
-    // Firefox locks the database, so we need to copy it first
-    let temp_path = std::env::temp_dir().join("collect-stories-firefox-cookies.db");
+    cookies: Vec<BrowserCookie>,
+    domains: &[String],
+) -> usize {
+    let now = chrono::Utc::now().timestamp();
+    cookies
+        .into_iter()
+        .filter(|cookie| domain_allowed(&cookie.host, domains))
+        .filter(|cookie| cookie.insert_into(cookie_store, now))
+        .count()
+}
 
-    // Copy the database to avoid locking issues
-    std::fs::copy(db_path, &temp_path).context("Failed to copy Firefox cookies database")?;
+/// A browser profile with a cookie database
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserProfile {
+    pub browser: Browser,
+    /// Name from the browser's profile manager, else the directory name
+    pub name: String,
+    pub path: PathBuf,
+    /// The profile the browser opens unless told otherwise
+    pub is_default: bool,
+}
+
+impl BrowserProfile {
+    fn new(browser: Browser, path: PathBuf) -> Self {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Self {
+            browser,
+            name,
+            path,
+            is_default: false,
+        }
+    }
+
+    /// The profile's cookie database, if it has one
+    pub fn cookie_db(&self) -> Option<PathBuf> {
+        let candidates = if self.browser.is_firefox_family() {
+            vec![self.path.join("cookies.sqlite")]
+        } else {
+            // Newer versions keep cookies under Network/
+            vec![
+                self.path.join("Network").join("Cookies"),
+                self.path.join("Cookies"),
+            ]
+        };
+        candidates.into_iter().find(|path| path.exists())
+    }
+
+    /// Whether a configured profile picks this one, by its name, its
+    /// directory name or its full path
+    fn matches(&self, spec: &str) -> bool {
+        self.name.eq_ignore_ascii_case(spec)
+            || self.path.file_name().is_some_and(|dir| dir == spec)
+            || self.path == Path::new(spec)
+    }
+}
+
+/// Every profile with a cookie database, across all supported browsers
+pub fn list_profiles() -> Vec<BrowserProfile> {
+    let (Some(home), Some(config_dir)) = (dirs::home_dir(), dirs::config_dir()) else {
+        return Vec::new();
+    };
+    Browser::ALL
+        .into_iter()
+        .flat_map(|browser| discover_profiles(browser, &home, &config_dir))
+        .collect()
+}
+
+/// The profiles to read from `browsers`: every one matching `specs` in any
+/// of them, or the first browser's default profile (else its first one) when
+/// no profiles are configured. Also returns the specs that matched nothing.
+fn select_profiles(
+    browsers: &[Browser],
+    specs: &[String],
+    home: &Path,
+    config_dir: &Path,
+) -> (Vec<BrowserProfile>, Vec<String>) {
+    let discovered: Vec<Vec<BrowserProfile>> = browsers
+        .iter()
+        .map(|&browser| discover_profiles(browser, home, config_dir))
+        .collect();
+
+    if specs.is_empty() {
+        let default = discovered.iter().find_map(|profiles| {
+            profiles
+                .iter()
+                .find(|profile| profile.is_default)
+                .or(profiles.first())
+        });
+        return (default.cloned().into_iter().collect(), Vec::new());
+    }
+
+    let mut selected: Vec<BrowserProfile> = Vec::new();
+    let mut missing = Vec::new();
+    for spec in specs {
+        let mut found: Vec<BrowserProfile> = discovered
+            .iter()
+            .flatten()
+            .filter(|profile| profile.matches(spec))
+            .cloned()
+            .collect();
+        // A path to a profile no browser lists
+        if found.is_empty() && Path::new(spec).is_absolute() {
+            found.extend(
+                browsers
+                    .iter()
+                    .map(|&browser| BrowserProfile::new(browser, PathBuf::from(spec)))
+                    .find(|profile| profile.cookie_db().is_some()),
+            );
+        }
+        if found.is_empty() {
+            missing.push(spec.clone());
+        }
+        for profile in found {
+            if !selected.contains(&profile) {
+                selected.push(profile);
+            }
+        }
+    }
+    (selected, missing)
+}
+
+fn discover_profiles(browser: Browser, home: &Path, config_dir: &Path) -> Vec<BrowserProfile> {
+    let mut profiles: Vec<BrowserProfile> = match browser.chromium_dir() {
+        Some(dir) => discover_chromium_profiles(browser, &config_dir.join(dir)),
+        None => browser
+            .firefox_roots()
+            .iter()
+            .flat_map(|root| discover_firefox_profiles(browser, &home.join(root)))
+            .collect(),
+    };
+    profiles.retain(|profile| profile.cookie_db().is_some());
+    profiles
+}
+
+/// Profiles listed in a Firefox-family `profiles.ini`, then any profile
+/// directories it doesn't mention
+fn discover_firefox_profiles(browser: Browser, root: &Path) -> Vec<BrowserProfile> {
+    let mut profiles = std::fs::read_to_string(root.join("profiles.ini"))
+        .map(|content| parse_profiles_ini(browser, root, &content))
+        .unwrap_or_default();
+
+    if let Ok(entries) = std::fs::read_dir(root) {
+        let mut unlisted: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.join("cookies.sqlite").exists())
+            .filter(|path| !profiles.iter().any(|profile| profile.path == *path))
+            .collect();
+        unlisted.sort();
+        profiles.extend(
+            unlisted
+                .into_iter()
+                .map(|path| BrowserProfile::new(browser, path)),
+        );
+    }
+
+    profiles
+}
+
+/// Parse `profiles.ini`. `[Install…]` sections name the profile each
+/// install actually opens, which takes precedence over the older
+/// `Default=1` flag on a `[Profile…]` section.
+fn parse_profiles_ini(browser: Browser, root: &Path, content: &str) -> Vec<BrowserProfile> {
+    let mut sections: Vec<(String, HashMap<String, String>)> = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((header.to_string(), HashMap::new()));
+        } else if let (Some((key, value)), Some((_, keys))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            keys.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+
+    let resolve = |path: &str, relative: bool| {
+        if relative {
+            root.join(path)
+        } else {
+            PathBuf::from(path)
+        }
+    };
+    let install_defaults: Vec<PathBuf> = sections
+        .iter()
+        .filter(|(header, _)| header.starts_with("Install"))
+        .filter_map(|(_, keys)| keys.get("Default"))
+        .map(|path| resolve(path, !Path::new(path).is_absolute()))
+        .collect();
+
+    sections
+        .iter()
+        .filter(|(header, _)| header.starts_with("Profile"))
+        .filter_map(|(_, keys)| {
+            let relative = keys.get("IsRelative").is_none_or(|v| v == "1");
+            let path = resolve(keys.get("Path")?, relative);
+            let is_default = if install_defaults.is_empty() {
+                keys.get("Default").is_some_and(|v| v == "1")
+            } else {
+                install_defaults.contains(&path)
+            };
+            let mut profile = BrowserProfile::new(browser, path);
+            if let Some(name) = keys.get("Name") {
+                profile.name = name.clone();
+            }
+            profile.is_default = is_default;
+            Some(profile)
+        })
+        .collect()
+}
+
+/// Chromium keeps each profile in its own directory ("Default", "Profile 1",
+/// …) and their display names in `Local State`
+fn discover_chromium_profiles(browser: Browser, root: &Path) -> Vec<BrowserProfile> {
+    let local_state: serde_json::Value = std::fs::read_to_string(root.join("Local State"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default();
+
+    let Ok(entries) = std::fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<BrowserProfile> = entries
+        .flatten()
+        .map(|entry| BrowserProfile::new(browser, entry.path()))
+        .filter(|profile| profile.cookie_db().is_some())
+        .map(|mut profile| {
+            let dir = profile.name.clone();
+            if let Some(name) = local_state["profile"]["info_cache"][&dir]["name"].as_str() {
+                profile.name = name.to_string();
+            }
+            profile.is_default = dir == "Default";
+            profile
+        })
+        .collect();
+    profiles.sort_by_key(|profile| (!profile.is_default, profile.path.clone()));
+    profiles
+}
+
+/// Copy a live SQLite database where the browser's lock can't get in the
+/// way. The `-wal` file comes too: recent logins are often only there.
+fn copy_database(db_path: &Path) -> Result<(tempfile::TempDir, PathBuf)> {
+    let dir = tempfile::Builder::new()
+        .prefix("collect-stories-cookies")
+        .tempdir()?;
+    let file_name = db_path
+        .file_name()
+        .context("Cookie database path has no file name")?;
+    let copy = dir.path().join(file_name);
+    std::fs::copy(db_path, &copy)?;
+
+    let with_suffix = |path: &Path| {
+        let mut path = path.as_os_str().to_owned();
+        path.push("-wal");
+        PathBuf::from(path)
+    };
+    let wal = with_suffix(db_path);
+    if wal.exists() {
+        std::fs::copy(&wal, with_suffix(&copy))?;
+    }
+
+    Ok((dir, copy))
+}
+
+fn load_firefox_cookies_from_db(db_path: &Path) -> Result<Vec<BrowserCookie>> {
+    // Firefox locks the database, so we need to copy it first
+    let (_temp_dir, temp_path) =
+        copy_database(db_path).context("Failed to copy Firefox cookies database")?;
 
     let conn = Connection::open(&temp_path).context("Failed to open Firefox cookies database")?;
 
@@ -113,49 +653,734 @@ fn load_firefox_cookies_from_db(
          WHERE expiry > ? AND name != '' AND value != ''",
     )?;
 
-    let mut count = 0;
-    let rows = stmt.query_map([now], |row| {
-        Ok((
-            row.get::<_, String>(0)?, // host
-            row.get::<_, String>(1)?, // path
-            row.get::<_, i64>(2)?,    // isSecure
-            row.get::<_, i64>(3)?,    // expiry
-            row.get::<_, String>(4)?, // name
-            row.get::<_, String>(5)?, // value
-            row.get::<_, i64>(6)?,    // isHttpOnly
-        ))
-    })?;
-
-    for (host, path, is_secure, _expires, name, value, _is_httponly) in rows.flatten() {
-        // Build a Set-Cookie header string
-        let cookie_str = format!(
-            "{}={}; Domain={}; Path={}{}",
-            name,
-            value,
-            host,
-            path,
-            if is_secure != 0 { "; Secure" } else { "" }
+    let cookies = stmt
+        .query_map([now], |row| {
+            Ok(BrowserCookie {
+                host: row.get(0)?,
+                path: row.get(1)?,
+                secure: row.get::<_, i64>(2)? != 0,
+                expires: Some(row.get(3)?),
+                name: row.get(4)?,
+                value: row.get(5)?,
+                http_only: row.get::<_, i64>(6)? != 0,
+            })
+        })?
+        .flatten()
+        .collect();
+
+    Ok(cookies)
+}
+
+/// A cookie as a browser stores it
+#[derive(Debug, Clone)]
+struct BrowserCookie {
+    /// A leading dot marks a domain cookie; without one the cookie is host-only
+    host: String,
+    path: String,
+    secure: bool,
+    http_only: bool,
+    /// Unix seconds; `None` for session cookies
+    expires: Option<i64>,
+    name: String,
+    value: String,
+}
+
+/// Browsers cap cookie lifetimes at 400 days; longer expiries are clamped
+/// to that rather than overflowing the store's date arithmetic
+const MAX_COOKIE_AGE_SECS: i64 = 400 * 24 * 60 * 60;
+
+impl BrowserCookie {
+    /// The cookie as the `Set-Cookie` header that would have created it
+    fn set_cookie_header(&self, now: i64) -> String {
+        let mut header = format!("{}={}; Path={}", self.name, self.value, self.path);
+        if self.host.starts_with('.') {
+            header.push_str(&format!("; Domain={}", self.host.trim_start_matches('.')));
+        }
+        if self.secure {
+            header.push_str("; Secure");
+        }
+        if self.http_only {
+            header.push_str("; HttpOnly");
+        }
+        if let Some(expires) = self.expires {
+            let max_age = (expires - now).clamp(0, MAX_COOKIE_AGE_SECS);
+            header.push_str(&format!("; Max-Age={}", max_age));
+        }
+        header
+    }
+
+    /// Add the cookie as though the host had just set it; false if it
+    /// doesn't parse
+    fn insert_into(&self, cookie_store: &mut CookieStore, now: i64) -> bool {
+        let scheme = if self.secure { "https" } else { "http" };
+        let host = self.host.trim_start_matches('.');
+        let Ok(url) = Url::parse(&format!("{}://{}{}", scheme, host, self.path)) else {
+            return false;
+        };
+        let Ok(cookie) = cookie_store::RawCookie::parse(self.set_cookie_header(now)) else {
+            return false;
+        };
+        cookie_store.insert_raw(&cookie, &url).is_ok()
+    }
+}
+
+/// Browser cookies as the HTTP client's cookie jar. Unlike reqwest's own
+/// jar it can be built from an existing store, so every cookie keeps its
+/// domain, path, host-only and Secure attributes.
+#[derive(Debug, Default)]
+pub struct CookieJar {
+    store: RwLock<CookieStore>,
+}
+
+impl CookieJar {
+    pub fn new(store: CookieStore) -> Self {
+        Self {
+            store: RwLock::new(store),
+        }
+    }
+}
+
+impl reqwest::cookie::CookieStore for CookieJar {
+    fn set_cookies(&self, cookie_headers: &mut dyn Iterator<Item = &HeaderValue>, url: &Url) {
+        let cookies = cookie_headers
+            .filter_map(|header| header.to_str().ok())
+            .filter_map(|header| cookie_store::RawCookie::parse(header.to_string()).ok());
+        if let Ok(mut store) = self.store.write() {
+            store.store_response_cookies(cookies, url);
+        }
+    }
+
+    fn cookies(&self, url: &Url) -> Option<HeaderValue> {
+        let store = self.store.read().ok()?;
+        let header = store
+            .get_request_values(url)
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        if header.is_empty() {
+            return None;
+        }
+        HeaderValue::from_str(&header).ok()
+    }
+}
+
+/// Seconds between 1601-01-01, Chromium's epoch, and 1970-01-01
+const CHROMIUM_EPOCH_OFFSET_SECS: i64 = 11_644_473_600;
+
+/// Databases from this schema version on prefix each decrypted value with a
+/// SHA-256 of the cookie's host
+const CHROMIUM_HOST_HASH_VERSION: i64 = 24;
+
+/// Keys for the two encryption schemes Chromium uses on Linux: `v10` with a
+/// hard-coded password, and `v11` with one kept in the Secret Service
+struct ChromiumKeys {
+    v10: [u8; 16],
+    v11: Vec<[u8; 16]>,
+    host_hash: bool,
+}
+
+/// Derive an AES key from a "Safe Storage" password the way Chromium does
+fn chromium_key(password: &str) -> [u8; 16] {
+    let mut key = [0u8; 16];
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(password.as_bytes(), b"saltysalt", 1, &mut key);
+    key
+}
+
+/// The browser's "Safe Storage" password from the Secret Service, via
+/// libsecret's `secret-tool`
+fn keyring_password(browser: Browser) -> Option<String> {
+    browser
+        .keyring_applications()
+        .iter()
+        .find_map(|application| {
+            let output = Command::new("secret-tool")
+                .args(["lookup", "application", application])
+                .output()
+                .ok()?;
+            let password = String::from_utf8(output.stdout).ok()?;
+            let password = password.trim_end_matches('\n');
+            (output.status.success() && !password.is_empty()).then(|| password.to_string())
+        })
+}
+
+fn decrypt_chromium_value(encrypted: &[u8], keys: &ChromiumKeys) -> Option<String> {
+    let (version, ciphertext) = encrypted.split_at_checked(3)?;
+    let candidates: Vec<[u8; 16]> = match version {
+        b"v10" => vec![keys.v10],
+        b"v11" => keys.v11.clone(),
+        _ => return None,
+    };
+
+    candidates.into_iter().find_map(|key| {
+        let mut buffer = ciphertext.to_vec();
+        let plaintext = cbc::Decryptor::<aes::Aes128>::new(&key.into(), &[b' '; 16].into())
+            .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+            .ok()?;
+        let plaintext = if keys.host_hash {
+            plaintext.get(32..)?
+        } else {
+            plaintext
+        };
+        String::from_utf8(plaintext.to_vec()).ok()
+    })
+}
+
+fn load_chromium_cookies_from_db(
+    browser: Browser,
+    db_path: &Path,
+    password: Option<&str>,
+) -> Result<Vec<BrowserCookie>> {
+    // Chromium locks the database too while it's running
+    let (_temp_dir, temp_path) = copy_database(db_path)
+        .with_context(|| format!("Failed to copy {} cookies database", browser))?;
+
+    read_chromium_cookies(browser, &temp_path, password)
+}
+
+fn read_chromium_cookies(
+    browser: Browser,
+    db_path: &Path,
+    password: Option<&str>,
+) -> Result<Vec<BrowserCookie>> {
+    let conn = Connection::open(db_path)
+        .with_context(|| format!("Failed to open {} cookies database", browser))?;
+
+    let version: i64 = conn
+        .query_row("SELECT value FROM meta WHERE key = 'version'", [], |row| {
+            row.get::<_, String>(0)
+        })
+        .ok()
+        .and_then(|version| version.parse().ok())
+        .unwrap_or(0);
+
+    // Expiry is in microseconds since 1601; 0 marks a session cookie
+    let now = (chrono::Utc::now().timestamp() + CHROMIUM_EPOCH_OFFSET_SECS) * 1_000_000;
+
+    let mut stmt = conn.prepare(
+        "SELECT host_key, path, is_secure, expires_utc, name, value, encrypted_value, is_httponly
+         FROM cookies
+         WHERE (expires_utc = 0 OR expires_utc > ?) AND name != ''",
+    )?;
+    let rows: Vec<_> = stmt
+        .query_map([now], |row| {
+            let expires_utc: i64 = row.get(3)?;
+            let cookie = BrowserCookie {
+                host: row.get(0)?,
+                path: row.get(1)?,
+                secure: row.get::<_, i64>(2)? != 0,
+                expires: (expires_utc != 0)
+                    .then(|| expires_utc / 1_000_000 - CHROMIUM_EPOCH_OFFSET_SECS),
+                name: row.get(4)?,
+                value: row.get(5)?,
+                http_only: row.get::<_, i64>(7)? != 0,
+            };
+            Ok((cookie, row.get::<_, Vec<u8>>(6)?))
+        })?
+        .flatten()
+        .collect();
+
+    // Only ask the keyring when there's something it's needed for; an
+    // empty password is what Chromium uses when no keyring was available
+    let needs_keyring = rows
+        .iter()
+        .any(|(_, encrypted_value)| encrypted_value.starts_with(b"v11"));
+    let mut v11 = Vec::new();
+    if needs_keyring {
+        match password
+            .map(str::to_string)
+            .or_else(|| keyring_password(browser))
+        {
+            Some(password) => v11.push(chromium_key(&password)),
+            None => eprintln!(
+                "  Note: No {} Safe Storage password in the Secret Service; set keyring_password under [cookies]",
+                browser
+            ),
+        }
+        v11.push(chromium_key(""));
+    }
+    let keys = ChromiumKeys {
+        v10: chromium_key("peanuts"),
+        v11,
+        host_hash: version >= CHROMIUM_HOST_HASH_VERSION,
+    };
+
+    let mut cookies = Vec::new();
+    let mut undecryptable = 0;
+    for (mut cookie, encrypted_value) in rows {
+        if !encrypted_value.is_empty() {
+            match decrypt_chromium_value(&encrypted_value, &keys) {
+                Some(value) => cookie.value = value,
+                None => {
+                    undecryptable += 1;
+                    continue;
+                }
+            }
+        }
+        if !cookie.value.is_empty() {
+            cookies.push(cookie);
+        }
+    }
+
+    if undecryptable > 0 {
+        eprintln!(
+            "  Warning: Could not decrypt {} {} cookies (wrong keyring password?)",
+            undecryptable, browser
         );
+    }
+
+    Ok(cookies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aes::cipher::BlockEncryptMut;
+
+    fn encrypt(version: &str, password: &str, host_hash: bool, value: &str) -> Vec<u8> {
+        let mut plaintext = if host_hash { vec![0u8; 32] } else { Vec::new() };
+        plaintext.extend_from_slice(value.as_bytes());
+        let len = plaintext.len();
+        plaintext.resize(len + 16, 0);
+
+        let key = chromium_key(password);
+        let ciphertext = cbc::Encryptor::<aes::Aes128>::new(&key.into(), &[b' '; 16].into())
+            .encrypt_padded_mut::<Pkcs7>(&mut plaintext, len)
+            .unwrap();
 
-        // Parse and insert into cookie store
-        let url_str = format!(
-            "{}://{}{}",
-            if is_secure != 0 { "https" } else { "http" },
-            host.trim_start_matches('.'),
-            path
+        let mut encrypted = version.as_bytes().to_vec();
+        encrypted.extend_from_slice(ciphertext);
+        encrypted
+    }
+
+    fn chromium_db(path: &Path, version: i64, rows: &[(&str, &str, i64, Vec<u8>)]) {
+        let conn = Connection::open(path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE meta (key TEXT PRIMARY KEY, value TEXT);
+             CREATE TABLE cookies (host_key TEXT, path TEXT, is_secure INTEGER,
+                 expires_utc INTEGER, name TEXT, value TEXT, encrypted_value BLOB,
+                 is_httponly INTEGER);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO meta (key, value) VALUES ('version', ?)",
+            [version.to_string()],
+        )
+        .unwrap();
+        for (host, name, expires_utc, encrypted_value) in rows {
+            conn.execute(
+                "INSERT INTO cookies VALUES (?, '/', 1, ?, ?, '', ?, 1)",
+                rusqlite::params![host, expires_utc, name, encrypted_value],
+            )
+            .unwrap();
+        }
+    }
+
+    fn cookie_value(store: &CookieStore, url: &str, name: &str) -> Option<String> {
+        let url = Url::parse(url).unwrap();
+        store
+            .matches(&url)
+            .into_iter()
+            .find(|cookie| cookie.name() == name)
+            .map(|cookie| cookie.value().to_string())
+    }
+
+    #[test]
+    fn test_browser_from_str() {
+        assert_eq!("brave".parse::<Browser>().unwrap(), Browser::Brave);
+        assert_eq!("Edge".parse::<Browser>().unwrap(), Browser::Edge);
+        assert!("safari".parse::<Browser>().is_err());
+    }
+
+    #[test]
+    fn test_decrypt_chromium_value() {
+        let keys = ChromiumKeys {
+            v10: chromium_key("peanuts"),
+            v11: vec![chromium_key("hunter2"), chromium_key("")],
+            host_hash: false,
+        };
+
+        let v10 = encrypt("v10", "peanuts", false, "session=abc");
+        assert_eq!(
+            decrypt_chromium_value(&v10, &keys).as_deref(),
+            Some("session=abc")
+        );
+        let v11 = encrypt("v11", "hunter2", false, "token");
+        assert_eq!(
+            decrypt_chromium_value(&v11, &keys).as_deref(),
+            Some("token")
+        );
+        let no_keyring = encrypt("v11", "", false, "fallback");
+        assert_eq!(
+            decrypt_chromium_value(&no_keyring, &keys).as_deref(),
+            Some("fallback")
         );
 
-        if let Ok(url) = Url::parse(&url_str) {
-            if let Ok(cookie) = cookie_store::RawCookie::parse(&cookie_str) {
-                let cookie = cookie.into_owned();
-                cookie_store.insert_raw(&cookie, &url).ok();
-                count += 1;
-            }
+        let wrong = encrypt("v11", "not it", false, "secret");
+        assert_eq!(decrypt_chromium_value(&wrong, &keys), None);
+        assert_eq!(decrypt_chromium_value(b"v9", &keys), None);
+    }
+
+    #[test]
+    fn test_read_chromium_cookies() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Cookies");
+        let far_future =
+            (chrono::Utc::now().timestamp() + CHROMIUM_EPOCH_OFFSET_SECS + 86_400) * 1_000_000;
+        chromium_db(
+            &path,
+            24,
+            &[
+                (
+                    ".nytimes.com",
+                    "NYT-S",
+                    far_future,
+                    encrypt("v11", "brave-secret", true, "subscriber"),
+                ),
+                (
+                    "www.wsj.com",
+                    "session",
+                    0,
+                    encrypt("v10", "peanuts", true, "abc123"),
+                ),
+                (
+                    ".ft.com",
+                    "expired",
+                    1,
+                    encrypt("v10", "peanuts", true, "old"),
+                ),
+            ],
+        );
+
+        let cookies = read_chromium_cookies(Browser::Brave, &path, Some("brave-secret")).unwrap();
+        let mut store = CookieStore::default();
+        let count = insert_cookies(&mut store, cookies, &[]);
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            cookie_value(&store, "https://www.nytimes.com/", "NYT-S").as_deref(),
+            Some("subscriber")
+        );
+        assert_eq!(
+            cookie_value(&store, "https://www.wsj.com/", "session").as_deref(),
+            Some("abc123")
+        );
+        assert_eq!(cookie_value(&store, "https://www.ft.com/", "expired"), None);
+    }
+
+    fn cookie(host: &str, path: &str, secure: bool, name: &str) -> BrowserCookie {
+        BrowserCookie {
+            host: host.to_string(),
+            path: path.to_string(),
+            secure,
+            http_only: false,
+            expires: None,
+            name: name.to_string(),
+            value: "1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_cookie_attributes_kept() {
+        let mut store = CookieStore::default();
+        let cookies = vec![
+            cookie(".nytimes.com", "/", true, "domain_wide"),
+            cookie("www.nytimes.com", "/", false, "host_only"),
+            cookie("www.nytimes.com", "/section", false, "scoped"),
+        ];
+        assert_eq!(insert_cookies(&mut store, cookies, &[]), 3);
+
+        let names = |url: &str| -> Vec<String> {
+            let mut names: Vec<String> = store
+                .get_request_values(&Url::parse(url).unwrap())
+                .map(|(name, _)| name.to_string())
+                .collect();
+            names.sort();
+            names
+        };
+        assert_eq!(
+            names("https://www.nytimes.com/section/tech"),
+            ["domain_wide", "host_only", "scoped"]
+        );
+        // Secure cookies stay off plain HTTP, paths and hosts are respected
+        assert_eq!(names("http://www.nytimes.com/"), ["host_only"]);
+        assert_eq!(names("https://cooking.nytimes.com/"), ["domain_wide"]);
+    }
+
+    #[test]
+    fn test_expiry_becomes_max_age() {
+        let mut session = cookie(".wsj.com", "/", true, "session");
+        assert_eq!(
+            session.set_cookie_header(1_000),
+            "session=1; Path=/; Domain=wsj.com; Secure"
+        );
+
+        session.http_only = true;
+        session.expires = Some(4_600);
+        assert_eq!(
+            session.set_cookie_header(1_000),
+            "session=1; Path=/; Domain=wsj.com; Secure; HttpOnly; Max-Age=3600"
+        );
+    }
+
+    const PROFILES_INI: &str = "[Profile1]
+Name=default
+IsRelative=1
+Path=abcd1234.default
+Default=1
+
+[Profile0]
+Name=default-release
+IsRelative=1
+Path=efgh5678.default-release
+
+[Profile2]
+Name=Work
+IsRelative=0
+Path=/data/firefox/work
+
+[General]
+StartWithLastProfile=1
+Version=2
+
+[Install4F96D1932A9F858E]
+Default=efgh5678.default-release
+Locked=1
+";
+
+    #[test]
+    fn test_parse_profiles_ini() {
+        let root = Path::new("/home/leo/.mozilla/firefox");
+        let profiles = parse_profiles_ini(Browser::Firefox, root, PROFILES_INI);
+
+        assert_eq!(profiles.len(), 3);
+        assert_eq!(profiles[0].name, "default");
+        assert_eq!(profiles[0].path, root.join("abcd1234.default"));
+        // The install's default wins over the stale Default=1
+        assert!(!profiles[0].is_default);
+        assert!(profiles[1].is_default);
+        assert_eq!(profiles[2].path, Path::new("/data/firefox/work"));
+
+        let legacy = PROFILES_INI.split("[Install").next().unwrap();
+        let profiles = parse_profiles_ini(Browser::Firefox, root, legacy);
+        assert!(profiles[0].is_default);
+        assert!(profiles[1].matches("default-release"));
+        assert!(profiles[1].matches("efgh5678.default-release"));
+        assert!(!profiles[1].matches("default"));
+    }
+
+    #[test]
+    fn test_discover_profiles() {
+        let home = tempfile::tempdir().unwrap();
+        let config_dir = home.path().join(".config");
+
+        let librewolf = home
+            .path()
+            .join(".var/app/io.gitlab.librewolf-community/.librewolf");
+        for dir in ["abcd1234.default", "efgh5678.default-release", "zzzz.old"] {
+            std::fs::create_dir_all(librewolf.join(dir)).unwrap();
         }
+        std::fs::write(librewolf.join("profiles.ini"), PROFILES_INI).unwrap();
+        // abcd1234.default has no cookie database, so isn't a candidate
+        std::fs::write(
+            librewolf.join("efgh5678.default-release/cookies.sqlite"),
+            "",
+        )
+        .unwrap();
+        std::fs::write(librewolf.join("zzzz.old/cookies.sqlite"), "").unwrap();
+
+        let profiles = discover_profiles(Browser::LibreWolf, home.path(), &config_dir);
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["default-release", "zzzz.old"]);
+        assert!(profiles[0].is_default);
+
+        let brave = config_dir.join("BraveSoftware/Brave-Browser");
+        for dir in ["Default/Network", "Profile 1"] {
+            std::fs::create_dir_all(brave.join(dir)).unwrap();
+        }
+        std::fs::write(brave.join("Default/Network/Cookies"), "").unwrap();
+        std::fs::write(brave.join("Profile 1/Cookies"), "").unwrap();
+        std::fs::write(
+            brave.join("Local State"),
+            r#"{"profile":{"info_cache":{"Profile 1":{"name":"Subscriptions"}}}}"#,
+        )
+        .unwrap();
+
+        let profiles = discover_profiles(Browser::Brave, home.path(), &config_dir);
+        let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Default", "Subscriptions"]);
+        assert!(profiles[0].is_default);
+        assert_eq!(
+            profiles[0].cookie_db(),
+            Some(brave.join("Default/Network/Cookies"))
+        );
+    }
+
+    #[test]
+    fn test_select_profiles_across_browsers() {
+        let home = tempfile::tempdir().unwrap();
+        let config_dir = home.path().join(".config");
+
+        let librewolf = home.path().join(".librewolf");
+        std::fs::create_dir_all(librewolf.join("efgh5678.default-release")).unwrap();
+        std::fs::write(librewolf.join("profiles.ini"), PROFILES_INI).unwrap();
+        std::fs::write(
+            librewolf.join("efgh5678.default-release/cookies.sqlite"),
+            "",
+        )
+        .unwrap();
+
+        let brave = config_dir.join("BraveSoftware/Brave-Browser");
+        std::fs::create_dir_all(brave.join("Profile 1")).unwrap();
+        std::fs::write(brave.join("Profile 1/Cookies"), "").unwrap();
+
+        let browsers = [Browser::LibreWolf, Browser::Brave];
+        let specs = ["default-release", "Profile 1", "Work"].map(String::from);
+        let (profiles, missing) = select_profiles(&browsers, &specs, home.path(), &config_dir);
+        let names: Vec<(Browser, &str)> = profiles
+            .iter()
+            .map(|p| (p.browser, p.name.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                (Browser::LibreWolf, "default-release"),
+                (Browser::Brave, "Profile 1")
+            ]
+        );
+        assert_eq!(missing, ["Work"]);
+
+        // Without specs, only the first browser's default profile is read
+        let (profiles, missing) = select_profiles(&browsers, &[], home.path(), &config_dir);
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].browser, Browser::LibreWolf);
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_firefox_cookies_read_from_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cookies.sqlite");
+
+        // Keep the connection open so the insert stays in the -wal file,
+        // as it does while Firefox is running
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA wal_autocheckpoint = 0;
+             CREATE TABLE moz_cookies (host TEXT, path TEXT, isSecure INTEGER, expiry INTEGER,
+                 name TEXT, value TEXT, isHttpOnly INTEGER);",
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO moz_cookies VALUES ('.wsj.com', '/', 1, ?, 'login', 'fresh', 1)",
+            [chrono::Utc::now().timestamp() + 3_600],
+        )
+        .unwrap();
+        assert!(dir.path().join("cookies.sqlite-wal").exists());
+
+        let cookies = load_firefox_cookies_from_db(&path).unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].value, "fresh");
+    }
+
+    #[test]
+    fn test_parse_cookies_txt() {
+        let content = "# Netscape HTTP Cookie File\n\
+            # https://curl.se/docs/http-cookies.html\n\
+            \n\
+            .wsj.com\tTRUE\t/\tTRUE\t2000000000\twsjregion\tna,us\r\n\
+            #HttpOnly_www.wsj.com\tFALSE\t/\tTRUE\t0\tsession\tabc\n\
+            .ft.com\tTRUE\t/\tFALSE\t1000\texpired\told\n\
+            broken line\n";
+
+        let cookies = parse_cookies_txt(content, 1_500_000_000);
+        assert_eq!(cookies.len(), 2);
+
+        assert_eq!(cookies[0].host, ".wsj.com");
+        assert_eq!(cookies[0].value, "na,us");
+        assert_eq!(cookies[0].expires, Some(2_000_000_000));
+        assert!(cookies[0].secure && !cookies[0].http_only);
+
+        assert_eq!(cookies[1].host, "www.wsj.com");
+        assert_eq!(cookies[1].name, "session");
+        assert_eq!(cookies[1].expires, None);
+        assert!(cookies[1].http_only);
+    }
+
+    #[test]
+    fn test_domain_cookies() {
+        let now = chrono::Utc::now().timestamp();
+        let mut store = CookieStore::default();
+        let mut login = cookie(".wsj.com", "/", true, "login");
+        login.expires = Some(now + 86_400);
+        let mut region = cookie("www.wsj.com", "/", true, "region");
+        region.expires = Some(now + 3_600);
+        insert_cookies(
+            &mut store,
+            vec![login, region, cookie(".nytimes.com", "/", true, "nyt")],
+            &[],
+        );
+
+        // Without a login cookie name, the first cookie to run out
+        let wsj = domain_cookies(&store, "wsj.com", None);
+        assert_eq!(wsj.count, 2);
+        assert_eq!(wsj.has_login, None);
+        assert_eq!(wsj.expires.map(|at| at.timestamp()), Some(now + 3_600));
+
+        let wsj = domain_cookies(&store, "wsj.com", Some("login"));
+        assert_eq!(wsj.has_login, Some(true));
+        assert_eq!(wsj.expires.map(|at| at.timestamp()), Some(now + 86_400));
+
+        let wsj = domain_cookies(&store, "wsj.com", Some("session"));
+        assert_eq!(wsj.has_login, Some(false));
+
+        let nyt = domain_cookies(&store, "nytimes.com", None);
+        assert_eq!(nyt.count, 1);
+        assert_eq!(nyt.expires, None);
+
+        assert_eq!(domain_cookies(&store, "ft.com", None).count, 0);
+    }
+
+    #[test]
+    fn test_domain_allowlist() {
+        let domains = vec!["nytimes.com".to_string(), ".WSJ.com".to_string()];
+        assert!(domain_allowed(".nytimes.com", &domains));
+        assert!(domain_allowed("www.wsj.com", &domains));
+        assert!(!domain_allowed("google.com", &domains));
+        assert!(!domain_allowed("notnytimes.com", &domains));
+        assert!(domain_allowed("google.com", &[]));
+
+        let mut store = CookieStore::default();
+        let cookies = vec![
+            cookie(".nytimes.com", "/", true, "kept"),
+            cookie(".google.com", "/", true, "dropped"),
+        ];
+        assert_eq!(insert_cookies(&mut store, cookies, &domains), 1);
+        assert_eq!(store.iter_any().count(), 1);
     }
 
-    // Clean up temp file
-    std::fs::remove_file(&temp_path).ok();
+    #[test]
+    fn test_cookie_jar_headers() {
+        use reqwest::cookie::CookieStore as _;
 
-    Ok(count)
+        let mut store = CookieStore::default();
+        insert_cookies(
+            &mut store,
+            vec![cookie(".theatlantic.com", "/", true, "subscriber")],
+            &[],
+        );
+        let jar = CookieJar::new(store);
+
+        let url = Url::parse("https://www.theatlantic.com/a").unwrap();
+        let set_cookie = HeaderValue::from_static("seen=yes; Path=/");
+        jar.set_cookies(&mut std::iter::once(&set_cookie), &url);
+
+        let header = jar.cookies(&url).unwrap();
+        let mut sent: Vec<&str> = header.to_str().unwrap().split("; ").collect();
+        sent.sort();
+        assert_eq!(sent, ["seen=yes", "subscriber=1"]);
+        assert!(jar
+            .cookies(&Url::parse("https://example.com/").unwrap())
+            .is_none());
+    }
 }
@@ -1,46 +1,244 @@
+pub mod social;
+
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use chrono::Utc;
+use cookie_store::CookieStore;
 use futures::stream::{self, StreamExt};
 use reqwest::Client;
 use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::sync::Arc;
-use tokio::sync::Semaphore;
 
-#[derive(Debug, Clone)]
+use crate::article_cache::{ArticleCache, CachedArticle};
+use crate::config::{CookieSettings, PolitenessSettings};
+use crate::cookies::CookieJar;
+use crate::metadata::{self, ArticleMetadata};
+use crate::pdf;
+use crate::politeness::Politeness;
+use crate::site_rules::{SiteRule, SiteRules};
+use crate::urls;
+use crate::video::{self, VideoEndpoints, VideoLink};
+use social::{SocialEndpoints, SocialLink};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArticleContent {
     pub text: String,
     pub published_date: Option<String>,
+    #[serde(default)]
+    pub metadata: ArticleMetadata,
 }
 
 #[derive(Debug, Clone)]
 pub enum ExtractionResult {
     Success(ArticleContent),
-    Paywalled,
-    Failed(String),
+    Failed(ExtractionError),
+}
+
+/// Why an article's text couldn't be extracted
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ExtractionError {
+    /// The server answered with a non-success status
+    HttpStatus {
+        status: u16,
+    },
+    Timeout,
+    DnsFailure,
+    /// Connection refused, TLS failure, dropped response and the like
+    Connection {
+        message: String,
+    },
+    /// Too little text to summarize
+    TooShort {
+        chars: usize,
+    },
+    /// The page renders its article with JavaScript
+    JavaScriptRequired,
+    /// A subscription or registration wall served with a 200
+    Paywall,
+    /// The link points at something other than a web page
+    NotHtml {
+        content_type: String,
+    },
+    /// robots.txt asks crawlers to stay away from the page
+    RobotsDisallowed,
+    Other {
+        message: String,
+    },
 }
 
+/// Pages with less main text than this may be a paywall teaser
+const SOFT_PAYWALL_MAX_CHARS: usize = 2000;
+
+/// Phrases subscription walls show in place of the article
+const PAYWALL_PHRASES: &[&str] = &[
+    "subscribe to continue reading",
+    "subscribe to keep reading",
+    "subscribe to read",
+    "to continue reading, please",
+    "this article is for subscribers",
+    "this content is for subscribers",
+    "exclusive to subscribers",
+    "available to subscribers",
+    "already a subscriber?",
+    "already a member? sign in",
+    "create a free account to continue",
+    "register to continue reading",
+    "you've reached your free article limit",
+    "you have reached your free article limit",
+];
+
+const JAVASCRIPT_PHRASES: &[&str] = &[
+    "enable javascript",
+    "javascript is disabled",
+    "javascript is required",
+    "requires javascript",
+    "turn on javascript",
+];
+
+impl ExtractionError {
+    /// Failures that may go away if we try again shortly
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ExtractionError::Timeout | ExtractionError::Connection { .. } => true,
+            ExtractionError::HttpStatus { status } => {
+                matches!(status, 408 | 429 | 500..=599)
+            }
+            _ => false,
+        }
+    }
+
+    /// The article is behind a login or subscription
+    pub fn is_paywall(&self) -> bool {
+        matches!(
+            self,
+            ExtractionError::Paywall | ExtractionError::HttpStatus { status: 401..=403 }
+        )
+    }
+
+    pub(crate) fn from_request(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return ExtractionError::Timeout;
+        }
+
+        // reqwest doesn't expose resolver errors directly; look down the source chain
+        let mut source: Option<&dyn std::error::Error> = Some(&error);
+        while let Some(err) = source {
+            let message = err.to_string();
+            if message.contains("dns error") || message.contains("failed to lookup address") {
+                return ExtractionError::DnsFailure;
+            }
+            source = err.source();
+        }
+
+        ExtractionError::Connection {
+            message: error.to_string(),
+        }
+    }
+
+    /// Recover an error from its message, as written to the org file; a
+    /// message that isn't one of ours comes back as `Other`
+    pub fn from_message(message: &str) -> Self {
+        let message = message.trim();
+        if let Some(message) = message.strip_prefix("Connection failed: ") {
+            return ExtractionError::Connection {
+                message: message.to_string(),
+            };
+        }
+        if let Some(content_type) = message
+            .strip_prefix("Not a web page (")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            return ExtractionError::NotHtml {
+                content_type: content_type.to_string(),
+            };
+        }
+
+        // The rest carry at most one number: a status code or a length
+        let number = message
+            .split(|c: char| !c.is_ascii_digit())
+            .find_map(|digits| digits.parse::<usize>().ok());
+        let mut candidates = vec![
+            ExtractionError::Timeout,
+            ExtractionError::DnsFailure,
+            ExtractionError::JavaScriptRequired,
+            ExtractionError::Paywall,
+            ExtractionError::RobotsDisallowed,
+        ];
+        if let Some(number) = number {
+            candidates.push(ExtractionError::TooShort { chars: number });
+            if let Ok(status) = u16::try_from(number) {
+                candidates.push(ExtractionError::HttpStatus { status });
+            }
+        }
+        candidates
+            .into_iter()
+            .find(|candidate| candidate.to_string() == message)
+            .unwrap_or_else(|| ExtractionError::Other {
+                message: message.to_string(),
+            })
+    }
+}
+
+impl fmt::Display for ExtractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractionError::HttpStatus { status } => match status {
+                401 => write!(f, "Access denied (401 Unauthorized) - requires login"),
+                403 => write!(
+                    f,
+                    "Access forbidden (403 Forbidden) - may be paywalled or blocking bots"
+                ),
+                404 => write!(f, "Page not found (404) - article may have been removed"),
+                429 => write!(f, "Rate limited (429) - too many requests"),
+                500..=599 => write!(f, "Server error ({}) - website is having issues", status),
+                _ => write!(f, "HTTP error: {}", status),
+            },
+            ExtractionError::Timeout => write!(f, "Timed out waiting for the page"),
+            ExtractionError::DnsFailure => write!(f, "DNS lookup failed - domain may be gone"),
+            ExtractionError::Connection { message } => write!(f, "Connection failed: {}", message),
+            ExtractionError::TooShort { chars } => write!(
+                f,
+                "Content too short ({} chars) - may be paywalled or blocked",
+                chars
+            ),
+            ExtractionError::JavaScriptRequired => {
+                write!(f, "No text content extracted - page requires JavaScript")
+            }
+            ExtractionError::Paywall => write!(f, "Paywalled - subscription wall on the page"),
+            ExtractionError::NotHtml { content_type } => {
+                write!(f, "Not a web page ({})", content_type)
+            }
+            ExtractionError::RobotsDisallowed => write!(f, "Disallowed by the site's robots.txt"),
+            ExtractionError::Other { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ExtractionError {}
+
 pub struct ContentExtractor {
     client: Client,
-    semaphore: Arc<Semaphore>,
+    politeness: Politeness,
+    site_rules: SiteRules,
+    social: SocialEndpoints,
+    video: VideoEndpoints,
+    cache: Option<ArticleCache>,
+    /// How long a cached article is served before it's revalidated
+    cache_ttl: chrono::Duration,
 }
 
 impl ContentExtractor {
-    pub fn new() -> Result<Self> {
-        // Create reqwest cookie jar
-        let cookie_jar = Arc::new(reqwest::cookie::Jar::default());
-
-        // Load Firefox cookies for accessing paywalled sites
-        if let Ok(browser_cookies) = crate::cookies::load_browser_cookies() {
-            for cookie in browser_cookies.iter_any() {
-                if let Some(domain) = cookie.domain() {
-                    let url_str = format!("https://{}", domain);
-                    if let Ok(url) = url::Url::parse(&url_str) {
-                        let cookie_str = format!("{}={}", cookie.name(), cookie.value());
-                        cookie_jar.add_cookie_str(&cookie_str, &url);
-                    }
-                }
-            }
-        }
+    pub fn new(cookies: &CookieSettings) -> Result<Self> {
+        // Load browser cookies for accessing paywalled sites
+        let browser_cookies = crate::cookies::load_browser_cookies(cookies).unwrap_or_default();
+        Self::with_cookie_store(browser_cookies)
+    }
+
+    /// An extractor that sends the given cookies
+    pub fn with_cookie_store(cookies: CookieStore) -> Result<Self> {
+        let cookie_jar = Arc::new(CookieJar::new(cookies));
 
         let client = Client::builder()
             .timeout(std::time::Duration::from_secs(30))
@@ -49,93 +247,376 @@ impl ContentExtractor {
             .build()
             .context("Failed to create HTTP client")?;
 
-        let semaphore = Arc::new(Semaphore::new(10));
+        Ok(Self::from_client(client))
+    }
+
+    fn from_client(client: Client) -> Self {
+        Self {
+            client,
+            politeness: Politeness::new(PolitenessSettings::default()),
+            site_rules: SiteRules::default(),
+            social: SocialEndpoints::default(),
+            video: VideoEndpoints::default(),
+            cache: None,
+            cache_ttl: chrono::Duration::zero(),
+        }
+    }
+
+    /// Replace the default per-host limits and robots.txt policy
+    pub fn with_politeness(mut self, settings: PolitenessSettings) -> Self {
+        self.politeness = Politeness::new(settings);
+        self
+    }
+
+    /// Consult per-site selectors and headers before generic extraction
+    pub fn with_site_rules(mut self, rules: SiteRules) -> Self {
+        self.site_rules = rules;
+        self
+    }
+
+    /// Send X, Mastodon and Bluesky API requests somewhere other than the public endpoints
+    pub fn with_social_endpoints(mut self, endpoints: SocialEndpoints) -> Self {
+        self.social = endpoints;
+        self
+    }
 
-        Ok(Self { client, semaphore })
+    /// Fetch YouTube and Vimeo details from somewhere other than the public sites
+    pub fn with_video_endpoints(mut self, endpoints: VideoEndpoints) -> Self {
+        self.video = endpoints;
+        self
+    }
+
+    /// Serve articles fetched within `ttl` from `cache`, and revalidate older
+    /// ones with conditional requests instead of downloading them again
+    pub fn with_cache(mut self, cache: ArticleCache, ttl: chrono::Duration) -> Self {
+        self.cache = Some(cache);
+        self.cache_ttl = ttl;
+        self
     }
 
     pub async fn fetch_article_content(&self, url: &str) -> ExtractionResult {
-        let _permit = match self.semaphore.acquire().await {
-            Ok(p) => p,
-            Err(e) => return ExtractionResult::Failed(e.to_string()),
-        };
+        // An entry extracted under different site rules (or none) is refetched
+        // in full, so fixing a site's rule takes effect on the next run
+        let rules_hash = self.site_rules.rule_for(url).map(SiteRule::fingerprint);
+        let cached = self
+            .cached(url)
+            .filter(|cached| cached.rules_hash == rules_hash);
+        if let Some(cached) = &cached {
+            if cached.is_fresh(self.cache_ttl, Utc::now()) {
+                return ExtractionResult::Success(cached.content.clone());
+            }
+        }
 
-        for attempt in 0..3 {
-            match self.try_fetch_article(url).await {
-                Ok(content) => return ExtractionResult::Success(content),
-                Err(e) => {
-                    let error_msg = e.to_string();
-                    // Don't retry 403 errors - they're paywalls
-                    if error_msg.contains("403") {
-                        return ExtractionResult::Paywalled;
-                    }
-                    if attempt == 2 {
+        if !self.politeness.allowed_by_robots(&self.client, url).await {
+            return ExtractionResult::Failed(ExtractionError::RobotsDisallowed);
+        }
+
+        let mut attempt = 0;
+        loop {
+            match self.try_fetch_article(url, cached.as_ref()).await {
+                Ok(article) => {
+                    self.store(url, &article);
+                    return ExtractionResult::Success(article.content);
+                }
+                // Paywalls, missing pages and the like won't change on a retry
+                Err(e) if !e.is_retryable() || attempt == 2 => {
+                    if !e.is_paywall() {
                         eprintln!("Failed to fetch {}: {}", url, e);
-                        return ExtractionResult::Failed(error_msg);
                     }
+                    return ExtractionResult::Failed(e);
+                }
+                Err(_) => {
                     let backoff = std::time::Duration::from_millis(500 * (2_u64.pow(attempt)));
                     tokio::time::sleep(backoff).await;
+                    attempt += 1;
                 }
             }
         }
+    }
 
-        ExtractionResult::Failed("Max retries exceeded".to_string())
+    fn cached(&self, url: &str) -> Option<CachedArticle> {
+        let cache = self.cache.as_ref()?;
+        cache.get(url).unwrap_or_else(|e| {
+            eprintln!("  Warning: Article cache lookup failed for {}: {}", url, e);
+            None
+        })
     }
 
-    async fn try_fetch_article(&self, url: &str) -> Result<ArticleContent> {
-        let response = self
-            .client
-            .get(url)
+    fn store(&self, url: &str, article: &CachedArticle) {
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put(url, article) {
+                eprintln!("  Warning: Failed to cache {}: {}", url, e);
+            }
+        }
+    }
+
+    /// Fetch and extract an article. With a `cached` copy the request is
+    /// conditional, and a 304 hands back the cached content. Every request,
+    /// API calls included, waits its turn on its own host.
+    async fn try_fetch_article(
+        &self,
+        url: &str,
+        cached: Option<&CachedArticle>,
+    ) -> Result<CachedArticle, ExtractionError> {
+        // Posts come from the platforms' APIs; their pages are login walls or JS shells
+        if let Some(link) = SocialLink::parse(url) {
+            match social::fetch_post(&self.client, &self.politeness, &self.social, &link).await {
+                Ok(post) => {
+                    return Ok(CachedArticle {
+                        content: post.into_article(url),
+                        fetched_at: Utc::now(),
+                        etag: None,
+                        last_modified: None,
+                        rules_hash: None,
+                    })
+                }
+                // Other sites use Mastodon-like /@user/123 paths too, so
+                // fall back to reading the page
+                Err(e) if matches!(link, SocialLink::Mastodon { .. }) && !e.is_retryable() => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        // A video page scrape is just player chrome; use the captions instead
+        if let Some(link) = VideoLink::parse(url) {
+            let video =
+                video::fetch_video(&self.client, &self.politeness, &self.video, &link).await?;
+            return Ok(CachedArticle {
+                content: video.into_article(url),
+                fetched_at: Utc::now(),
+                etag: None,
+                last_modified: None,
+                rules_hash: None,
+            });
+        }
+
+        let rule = self.site_rules.rule_for(url);
+        let rules_hash = rule.map(SiteRule::fingerprint);
+
+        // Held until the body is read
+        let host = Politeness::host_key(url).unwrap_or_default();
+        let _permit = self.politeness.acquire(&host).await;
+
+        let mut request = self.client.get(url);
+        if let Some(rule) = rule {
+            for (name, value) in &rule.headers {
+                request = request.header(name, value);
+            }
+        }
+        if let Some(cached) = cached {
+            if let Some(etag) = &cached.etag {
+                request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &cached.last_modified {
+                request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = request
             .send()
             .await
-            .context("Failed to send HTTP request")?;
+            .map_err(ExtractionError::from_request)?;
 
         let status = response.status();
+        // Where any redirects ended up
+        let final_url = response.url().to_string();
 
-        // Provide specific error messages for common HTTP status codes
-        match status.as_u16() {
-            401 => anyhow::bail!("Access denied (401 Unauthorized) - requires login"),
-            403 => anyhow::bail!(
-                "Access forbidden (403 Forbidden) - may be paywalled or blocking bots"
-            ),
-            404 => anyhow::bail!("Page not found (404) - article may have been removed"),
-            429 => anyhow::bail!("Rate limited (429) - too many requests"),
-            500..=599 => anyhow::bail!("Server error ({}) - website is having issues", status),
-            _ if !status.is_success() => anyhow::bail!("HTTP error: {}", status),
-            _ => {}
+        // Let the host set the pace of our retries
+        self.politeness.honor_retry_after(&host, &response).await;
+
+        if let (reqwest::StatusCode::NOT_MODIFIED, Some(cached)) = (status, cached) {
+            return Ok(CachedArticle {
+                fetched_at: Utc::now(),
+                ..cached.clone()
+            });
+        }
+        if !status.is_success() {
+            return Err(ExtractionError::HttpStatus {
+                status: status.as_u16(),
+            });
+        }
+
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                .map(String::from)
+        };
+        let etag = header(reqwest::header::ETAG);
+        let last_modified = header(reqwest::header::LAST_MODIFIED);
+        let content_type = header(reqwest::header::CONTENT_TYPE).map(|v| v.to_lowercase());
+
+        if pdf::is_pdf(content_type.as_deref(), url) {
+            let mut content = Self::read_pdf(response).await?;
+            content.metadata.canonical_url = Some(urls::canonical_url(&final_url, None));
+            return Ok(CachedArticle {
+                content,
+                fetched_at: Utc::now(),
+                etag,
+                last_modified,
+                rules_hash,
+            });
+        }
+        if let Some(content_type) = content_type {
+            if !content_type.contains("html") && !content_type.starts_with("text/") {
+                return Err(ExtractionError::NotHtml { content_type });
+            }
         }
 
         let html = response
             .text()
             .await
-            .context("Failed to read response body")?;
+            .map_err(ExtractionError::from_request)?;
+
+        // Site rules strip their elements first, and their selectors win over
+        // anything the generic extraction finds
+        let (html, site) = match rule {
+            Some(rule) => {
+                let html = rule.strip(&html);
+                let site = rule.extract(&html);
+                (html, site)
+            }
+            None => (html, Default::default()),
+        };
+
+        // Extract publication date, byline and publisher from JSON-LD and meta tags
+        let published_date = site
+            .published_date
+            .or_else(|| self.extract_published_date(&html));
+        let mut metadata = ArticleMetadata::from_html(&html);
+        if site.title.is_some() {
+            metadata.headline = site.title;
+        }
+        if site.author.is_some() {
+            metadata.author = site.author;
+        }
+        metadata.canonical_url = Some(urls::canonical_url(
+            &final_url,
+            metadata.canonical_url.as_deref(),
+        ));
 
-        // Extract publication date from HTML meta tags
-        let published_date = self.extract_published_date(&html);
+        // Convert the main article to text, or the whole page if none stands out
+        let text = site
+            .body
+            .or_else(|| crate::readability::extract_main_content(&html))
+            .map(|content| html2text::from_read(content.as_bytes(), 100))
+            .unwrap_or_else(|| html2text::from_read(html.as_bytes(), 100));
 
-        // Convert HTML to text
-        let text = html2text::from_read(html.as_bytes(), 100);
+        if Self::looks_paywalled(&html, &text) {
+            return Err(ExtractionError::Paywall);
+        }
 
-        if text.trim().is_empty() {
-            anyhow::bail!("No text content extracted - may require JavaScript or login");
+        if text.trim().is_empty() || Self::needs_javascript(&text) {
+            return Err(ExtractionError::JavaScriptRequired);
         }
 
         if text.len() < 100 {
-            anyhow::bail!(
-                "Content too short ({} chars) - may be paywalled or blocked",
-                text.len()
-            );
+            return Err(ExtractionError::TooShort { chars: text.len() });
         }
 
-        Ok(ArticleContent {
-            text,
-            published_date,
+        Ok(CachedArticle {
+            content: ArticleContent {
+                text,
+                published_date,
+                metadata,
+            },
+            fetched_at: Utc::now(),
+            etag,
+            last_modified,
+            rules_hash,
         })
     }
 
+    /// Extract a PDF's text and document info. Court filings and papers
+    /// often run to hundreds of pages, so oversized files are refused.
+    async fn read_pdf(response: reqwest::Response) -> Result<ArticleContent, ExtractionError> {
+        let too_large = |bytes: u64| ExtractionError::Other {
+            message: format!("PDF too large ({} MB)", bytes / (1024 * 1024)),
+        };
+        if let Some(length) = response.content_length() {
+            if length > pdf::MAX_PDF_BYTES as u64 {
+                return Err(too_large(length));
+            }
+        }
+
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(ExtractionError::from_request)?;
+        if bytes.len() > pdf::MAX_PDF_BYTES {
+            return Err(too_large(bytes.len() as u64));
+        }
+
+        // Parsing is CPU-bound, and the PDF crates panic on some malformed files
+        let content = tokio::task::spawn_blocking(move || pdf::extract(&bytes))
+            .await
+            .map_err(|_| ExtractionError::Other {
+                message: "PDF could not be parsed".to_string(),
+            })?
+            .map_err(|e| ExtractionError::Other {
+                message: format!("{:#}", e),
+            })?;
+
+        // Scanned documents have pages but no text layer
+        if content.text.len() < 100 {
+            return Err(ExtractionError::TooShort {
+                chars: content.text.len(),
+            });
+        }
+
+        Ok(content)
+    }
+
+    /// Detect subscription walls served with a 200: a teaser paragraph plus a
+    /// "subscribe to continue" box. Long articles are never flagged, since
+    /// pages often keep the wall markup even when our cookies unlock them.
+    fn looks_paywalled(html: &str, text: &str) -> bool {
+        if text.len() > SOFT_PAYWALL_MAX_CHARS {
+            return false;
+        }
+
+        let document = Html::parse_document(html);
+
+        // schema.org markup Google asks paywalled publishers to include
+        let json_ld = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+        let declared = document.select(&json_ld).any(|script| {
+            let json = script.text().collect::<String>().replace(' ', "");
+            json.contains("\"isAccessibleForFree\":false")
+                || json.contains("\"isAccessibleForFree\":\"false\"")
+                || json.contains("\"isAccessibleForFree\":\"False\"")
+        });
+
+        let wall = Selector::parse(
+            r#"[class*="paywall"], [id*="paywall"], [class*="regwall"], [class*="subscriber-only"], [class*="piano-"]"#,
+        )
+        .unwrap();
+        // Wall markup alone is weak evidence, so only trust it for teaser-length text
+        let marked =
+            text.len() < SOFT_PAYWALL_MAX_CHARS / 2 && document.select(&wall).next().is_some();
+
+        let lower = text.to_lowercase();
+        let worded = PAYWALL_PHRASES.iter().any(|phrase| lower.contains(phrase));
+
+        declared || marked || worded
+    }
+
+    fn needs_javascript(text: &str) -> bool {
+        let lower = text.to_lowercase();
+        text.len() < SOFT_PAYWALL_MAX_CHARS
+            && JAVASCRIPT_PHRASES
+                .iter()
+                .any(|phrase| lower.contains(phrase))
+    }
+
     fn extract_published_date(&self, html: &str) -> Option<String> {
         let document = Html::parse_document(html);
 
+        // News sites often only publish the date in JSON-LD
+        if let Some(date) = metadata::json_ld_published_date(&document) {
+            return Some(date);
+        }
+
         // Try various meta tag selectors for publication date
         let meta_selectors = vec![
             r#"meta[property="article:published_time"]"#,
@@ -172,25 +653,16 @@ impl ContentExtractor {
     }
 
     fn format_date(&self, date_str: &str) -> Option<String> {
-        // Try parsing ISO 8601 / RFC 3339 format first — keep it as-is
-        if let Ok(dt) = date_str.parse::<DateTime<Utc>>() {
-            return Some(dt.to_rfc3339());
-        }
-
-        // If it's just a date without time, normalize to RFC 3339 at midnight UTC
-        if let Ok(naive_date) = chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
-            let datetime = naive_date.and_hms_opt(0, 0, 0)?;
-            let dt: DateTime<Utc> = DateTime::from_naive_utc_and_offset(datetime, Utc);
-            return Some(dt.to_rfc3339());
-        }
-
-        None
+        metadata::normalize_date(date_str)
     }
 
     pub async fn fetch_articles_parallel(
         &self,
         urls: Vec<String>,
     ) -> Vec<(String, ExtractionResult)> {
+        // Politeness caps what's actually in flight; queue everything so a
+        // backlog for one host doesn't hold up the others
+        let queued = urls.len().max(1);
         stream::iter(urls)
             .map(|url| {
                 let url_clone = url.clone();
@@ -199,7 +671,7 @@ impl ContentExtractor {
                     (url_clone, result)
                 }
             })
-            .buffer_unordered(10)
+            .buffer_unordered(queued)
             .collect()
             .await
     }
@@ -212,10 +684,7 @@ mod tests {
     // Helper to create extractor for testing format_date
     fn test_format_date(date_str: &str) -> Option<String> {
         // Create a minimal extractor just for testing the format_date function
-        let extractor = ContentExtractor {
-            client: reqwest::Client::new(),
-            semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
-        };
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
         extractor.format_date(date_str)
     }
 
@@ -255,10 +724,7 @@ mod tests {
 
     #[test]
     fn test_extract_published_date_og_tag() {
-        let extractor = ContentExtractor {
-            client: reqwest::Client::new(),
-            semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
-        };
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
 
         let html = r#"
             <html>
@@ -274,10 +740,7 @@ mod tests {
 
     #[test]
     fn test_extract_published_date_time_tag() {
-        let extractor = ContentExtractor {
-            client: reqwest::Client::new(),
-            semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
-        };
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
 
         let html = r#"
             <html>
@@ -291,12 +754,30 @@ mod tests {
         assert!(result.is_some());
     }
 
+    #[test]
+    fn test_extract_published_date_prefers_json_ld() {
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
+
+        let html = r#"
+            <html>
+            <head>
+                <script type="application/ld+json">
+                {"@type": "NewsArticle", "datePublished": "2026-01-30T08:00:00Z"}
+                </script>
+            </head>
+            <body>
+                <time datetime="2026-02-01T12:00:00Z">Updated February 1, 2026</time>
+            </body>
+            </html>
+        "#;
+
+        let result = extractor.extract_published_date(html);
+        assert_eq!(result.as_deref(), Some("2026-01-30T08:00:00+00:00"));
+    }
+
     #[test]
     fn test_extract_published_date_no_date() {
-        let extractor = ContentExtractor {
-            client: reqwest::Client::new(),
-            semaphore: std::sync::Arc::new(tokio::sync::Semaphore::new(1)),
-        };
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
 
         let html = r#"
             <html>
@@ -308,11 +789,614 @@ mod tests {
         assert!(result.is_none());
     }
 
+    async fn fetch_with(
+        response: wiremock::ResponseTemplate,
+    ) -> Result<ArticleContent, ExtractionError> {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(response)
+            .mount(&server)
+            .await;
+
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
+        extractor
+            .try_fetch_article(&format!("{}/story", server.uri()), None)
+            .await
+            .map(|article| article.content)
+    }
+
+    fn html_page(body: &str) -> wiremock::ResponseTemplate {
+        wiremock::ResponseTemplate::new(200).set_body_raw(
+            format!("<html><body>{}</body></html>", body),
+            "text/html; charset=utf-8",
+        )
+    }
+
+    #[tokio::test]
+    async fn test_http_status_errors() {
+        let forbidden = fetch_with(wiremock::ResponseTemplate::new(403)).await;
+        assert_eq!(
+            forbidden.unwrap_err(),
+            ExtractionError::HttpStatus { status: 403 }
+        );
+
+        let unavailable = fetch_with(wiremock::ResponseTemplate::new(503))
+            .await
+            .unwrap_err();
+        assert!(unavailable.is_retryable());
+        assert!(!unavailable.is_paywall());
+    }
+
+    #[tokio::test]
+    async fn test_soft_paywall_detected() {
+        let page = html_page(
+            r#"<article><p>The first paragraph of an exclusive story about chip supply chains.</p>
+            <div class="paywall"><p>Subscribe to continue reading. Already a subscriber? Sign in.</p></div>
+            </article>"#,
+        );
+
+        let error = fetch_with(page).await.unwrap_err();
+        assert_eq!(error, ExtractionError::Paywall);
+        assert!(error.is_paywall());
+        assert!(!error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_paywall_declared_in_json_ld() {
+        let page = html_page(
+            r#"<script type="application/ld+json">{"@type": "NewsArticle", "isAccessibleForFree": false}</script>
+            <p>A teaser paragraph that is long enough to pass the length check on its own, just about, maybe.</p>"#,
+        );
+
+        assert_eq!(
+            fetch_with(page).await.unwrap_err(),
+            ExtractionError::Paywall
+        );
+    }
+
+    #[tokio::test]
+    async fn test_long_article_not_flagged_as_paywalled() {
+        let paragraph =
+            "<p>Plenty of real article text, with commas, clauses and detail, goes here.</p>";
+        let page = html_page(&format!(
+            r#"<article>{}</article><div class="paywall-prompt"></div>"#,
+            paragraph.repeat(40)
+        ));
+
+        assert!(fetch_with(page).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_javascript_required() {
+        let page = html_page("<noscript>Please enable JavaScript to view this site.</noscript>");
+        assert_eq!(
+            fetch_with(page).await.unwrap_err(),
+            ExtractionError::JavaScriptRequired
+        );
+
+        let empty = html_page("<div id=\"root\"></div>");
+        assert_eq!(
+            fetch_with(empty).await.unwrap_err(),
+            ExtractionError::JavaScriptRequired
+        );
+    }
+
+    #[tokio::test]
+    async fn test_too_short() {
+        let page = html_page("<p>Just a line.</p>");
+        assert!(matches!(
+            fetch_with(page).await.unwrap_err(),
+            ExtractionError::TooShort { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_non_html_content_type() {
+        let response =
+            wiremock::ResponseTemplate::new(200).set_body_raw(vec![0u8; 16], "application/zip");
+
+        assert_eq!(
+            fetch_with(response).await.unwrap_err(),
+            ExtractionError::NotHtml {
+                content_type: "application/zip".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a DNS resolver"]
+    async fn test_dns_failure() {
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
+
+        let error = extractor
+            .try_fetch_article("http://does-not-exist.invalid/story", None)
+            .await
+            .unwrap_err();
+        assert_eq!(error, ExtractionError::DnsFailure);
+    }
+
+    #[tokio::test]
+    async fn test_connection_refused() {
+        // A port that was just free is closed, with no network needed
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
+
+        let error = extractor
+            .try_fetch_article(&format!("http://127.0.0.1:{}/story", port), None)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, ExtractionError::Connection { .. }));
+    }
+
+    fn cached_extractor(ttl: chrono::Duration) -> (tempfile::TempDir, ContentExtractor) {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ArticleCache::open(&dir.path().join("articles.sqlite")).unwrap();
+        let extractor =
+            ContentExtractor::from_client(reqwest::Client::new()).with_cache(cache, ttl);
+        (dir, extractor)
+    }
+
+    fn cached_article(text: &str, age: chrono::Duration) -> CachedArticle {
+        CachedArticle {
+            content: ArticleContent {
+                text: text.to_string(),
+                published_date: None,
+                metadata: ArticleMetadata::default(),
+            },
+            fetched_at: Utc::now() - age,
+            etag: Some("\"v1\"".to_string()),
+            last_modified: Some("Sun, 01 Feb 2026 12:00:00 GMT".to_string()),
+            rules_hash: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fresh_cache_entry_skips_network() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let (_dir, extractor) = cached_extractor(chrono::Duration::hours(24));
+        let url = format!("{}/story", server.uri());
+        extractor
+            .cache
+            .as_ref()
+            .unwrap()
+            .put(
+                &url,
+                &cached_article("Cached text", chrono::Duration::hours(1)),
+            )
+            .unwrap();
+
+        let result = extractor.fetch_article_content(&url).await;
+        assert!(matches!(result, ExtractionResult::Success(c) if c.text == "Cached text"));
+    }
+
+    #[tokio::test]
+    async fn test_stale_cache_entry_revalidated_with_conditional_get() {
+        use wiremock::matchers::{header, header_exists, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("If-None-Match", "\"v1\""))
+            .and(header_exists("If-Modified-Since"))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let (_dir, extractor) = cached_extractor(chrono::Duration::hours(24));
+        let url = format!("{}/story", server.uri());
+        let cache = extractor.cache.as_ref().unwrap();
+        cache
+            .put(
+                &url,
+                &cached_article("Cached text", chrono::Duration::hours(48)),
+            )
+            .unwrap();
+
+        let result = extractor.fetch_article_content(&url).await;
+        assert!(matches!(result, ExtractionResult::Success(c) if c.text == "Cached text"));
+
+        // The 304 makes the entry fresh again
+        let refreshed = cache.get(&url).unwrap().unwrap();
+        assert!(refreshed.is_fresh(chrono::Duration::hours(1), Utc::now()));
+    }
+
+    #[tokio::test]
+    async fn test_fetched_article_cached_with_validators() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let body = format!(
+            "<html><body><article>{}</article></body></html>",
+            "<p>Enough article text to pass the length checks, with a comma or two.</p>".repeat(5)
+        );
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v2\"")
+                    .set_body_raw(body, "text/html"),
+            )
+            .mount(&server)
+            .await;
+
+        let (_dir, extractor) = cached_extractor(chrono::Duration::hours(24));
+        let url = format!("{}/story", server.uri());
+
+        let result = extractor.fetch_article_content(&url).await;
+        assert!(matches!(result, ExtractionResult::Success(_)));
+
+        let cached = extractor
+            .cache
+            .as_ref()
+            .unwrap()
+            .get(&url)
+            .unwrap()
+            .unwrap();
+        assert_eq!(cached.etag.as_deref(), Some("\"v2\""));
+        assert!(cached.content.text.contains("Enough article text"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_honored_on_429() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .respond_with(html_page(&format!(
+                "<article>{}</article>",
+                "<p>Article text served once the rate limit has passed, at last.</p>".repeat(5)
+            )))
+            .mount(&server)
+            .await;
+
+        let extractor = ContentExtractor::from_client(reqwest::Client::new()).with_politeness(
+            PolitenessSettings {
+                host_delay_ms: 0,
+                ..Default::default()
+            },
+        );
+        let start = std::time::Instant::now();
+
+        let result = extractor
+            .fetch_article_content(&format!("{}/story", server.uri()))
+            .await;
+
+        assert!(matches!(result, ExtractionResult::Success(_)));
+        assert!(start.elapsed() >= std::time::Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_robots_disallowed() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /story\n"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/story"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let extractor = ContentExtractor::from_client(reqwest::Client::new()).with_politeness(
+            PolitenessSettings {
+                respect_robots: true,
+                ..Default::default()
+            },
+        );
+
+        let result = extractor
+            .fetch_article_content(&format!("{}/story", server.uri()))
+            .await;
+        assert!(matches!(
+            result,
+            ExtractionResult::Failed(ExtractionError::RobotsDisallowed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cache_entry_refetched_when_site_rule_changes() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(html_page(&format!(
+                r#"<div class="story">{}</div>"#,
+                "<p>Text the site rule picks out, spread over a few short blocks.</p>".repeat(3)
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let host = url::Url::parse(&server.uri())
+            .unwrap()
+            .host_str()
+            .unwrap()
+            .to_string();
+        let rules = SiteRules::parse(&format!("[\"{}\"]\nbody = \".story\"\n", host)).unwrap();
+        let (_dir, extractor) = cached_extractor(chrono::Duration::hours(24));
+        let extractor = extractor.with_site_rules(rules);
+        let url = format!("{}/story", server.uri());
+
+        // Fresh, but extracted before the rule existed
+        let cache = extractor.cache.as_ref().unwrap();
+        cache
+            .put(
+                &url,
+                &cached_article("Broken extraction", chrono::Duration::hours(1)),
+            )
+            .unwrap();
+
+        let result = extractor.fetch_article_content(&url).await;
+        assert!(matches!(result, ExtractionResult::Success(c) if c.text.contains("site rule")));
+        // Stored under the new rule, so the next run uses it without fetching
+        let result = extractor.fetch_article_content(&url).await;
+        assert!(matches!(result, ExtractionResult::Success(c) if c.text.contains("site rule")));
+    }
+
+    #[tokio::test]
+    async fn test_site_rule_selectors_and_headers() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(header("Referer", "https://www.google.com/"))
+            .respond_with(html_page(&format!(
+                r#"<h1 class="headline">Real headline</h1>
+                <span class="byline">Jane Doe</span>
+                <div class="story">{}<div class="promo">Buy our merch</div></div>
+                <article>{}</article>"#,
+                "<p>Text the generic extraction would miss, spread over short blocks.</p>"
+                    .repeat(3),
+                "<p>A long related-stories teaser that wins on text density alone.</p>".repeat(6)
+            )))
+            .mount(&server)
+            .await;
+
+        let host = url::Url::parse(&server.uri())
+            .unwrap()
+            .host_str()
+            .unwrap()
+            .to_string();
+        let rules = SiteRules::parse(&format!(
+            r#"["{}"]
+            body = ".story"
+            title = ".headline"
+            author = ".byline"
+            remove = [".promo"]
+            headers = {{ Referer = "https://www.google.com/" }}"#,
+            host
+        ))
+        .unwrap();
+        let extractor =
+            ContentExtractor::from_client(reqwest::Client::new()).with_site_rules(rules);
+
+        let result = extractor
+            .fetch_article_content(&format!("{}/story", server.uri()))
+            .await;
+
+        let ExtractionResult::Success(content) = result else {
+            panic!("expected success, got {:?}", result);
+        };
+        assert!(content.text.contains("generic extraction would miss"));
+        assert!(!content.text.contains("related-stories teaser"));
+        assert!(!content.text.contains("merch"));
+        assert_eq!(content.metadata.headline.as_deref(), Some("Real headline"));
+        assert_eq!(content.metadata.author.as_deref(), Some("Jane Doe"));
+    }
+
+    #[tokio::test]
+    async fn test_pdf_extracted() {
+        let body = "The court granted the motion to dismiss in part, finding the plaintiffs";
+        let pdf = crate::pdf::tests::build_pdf(
+            &[
+                body,
+                "had not shown the outputs were substantially similar to their works.",
+            ],
+            &[
+                ("Title", "Order on Motion to Dismiss"),
+                ("CreationDate", "D:20260201120000Z"),
+            ],
+        );
+        let response = wiremock::ResponseTemplate::new(200).set_body_raw(pdf, "application/pdf");
+
+        let content = fetch_with(response).await.unwrap();
+
+        assert!(content.text.contains("motion to dismiss"));
+        assert_eq!(
+            content.metadata.headline.as_deref(),
+            Some("Order on Motion to Dismiss")
+        );
+        assert_eq!(
+            content.published_date.as_deref(),
+            Some("2026-02-01T12:00:00+00:00")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_corrupt_pdf() {
+        let response = wiremock::ResponseTemplate::new(200)
+            .set_body_raw(b"%PDF-1.5 truncated".to_vec(), "application/pdf");
+
+        assert!(matches!(
+            fetch_with(response).await,
+            Err(ExtractionError::Other { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_social_post_uses_api() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/xrpc/app.bsky.feed.getPostThread"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "thread": {"post": {
+                    "author": {"handle": "jay.bsky.team"},
+                    "record": {"text": "Short post", "createdAt": "2026-02-01T12:00:00Z"}
+                }}
+            })))
+            .mount(&server)
+            .await;
+
+        let extractor = ContentExtractor::from_client(reqwest::Client::new())
+            .with_social_endpoints(SocialEndpoints {
+                bluesky_appview: server.uri(),
+                ..Default::default()
+            });
+
+        let result = extractor
+            .fetch_article_content("https://bsky.app/profile/jay.bsky.team/post/3abc")
+            .await;
+
+        // Posts are short, but that's no reason to drop them
+        let ExtractionResult::Success(content) = result else {
+            panic!("expected success, got {:?}", result);
+        };
+        assert_eq!(content.text, "@jay.bsky.team on Bluesky:\n\nShort post");
+        assert_eq!(content.metadata.publisher.as_deref(), Some("Bluesky"));
+    }
+
+    #[tokio::test]
+    async fn test_mastodon_lookalike_falls_back_to_page() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/@writer/2026"))
+            .respond_with(html_page(&format!(
+                "<article>{}</article>",
+                "<p>An ordinary blog post that happens to use an at-sign path.</p>".repeat(5)
+            )))
+            .mount(&server)
+            .await;
+
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
+        let result = extractor
+            .fetch_article_content(&format!("{}/@writer/2026", server.uri()))
+            .await;
+
+        let ExtractionResult::Success(content) = result else {
+            panic!("expected success, got {:?}", result);
+        };
+        assert!(content.text.contains("ordinary blog post"));
+    }
+
+    #[tokio::test]
+    async fn test_canonical_url_follows_redirects() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/s"))
+            .respond_with(
+                ResponseTemplate::new(302).insert_header("Location", "/amp/story?utm_source=x"),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/amp/story"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(
+                format!(
+                    r#"<html><head><link rel="canonical" href="/news/story"></head>
+                    <body><article>{}</article></body></html>"#,
+                    "<p>The AMP copy of a story that lives at a different address.</p>".repeat(5)
+                ),
+                "text/html",
+            ))
+            .mount(&server)
+            .await;
+
+        let extractor = ContentExtractor::from_client(reqwest::Client::new());
+        let content = extractor
+            .try_fetch_article(&format!("{}/s", server.uri()), None)
+            .await
+            .unwrap()
+            .content;
+        assert_eq!(
+            content.metadata.canonical_url,
+            Some(format!("{}/news/story", server.uri()))
+        );
+    }
+
+    #[test]
+    fn test_extraction_error_serialization() {
+        let json = serde_json::to_string(&ExtractionError::HttpStatus { status: 404 }).unwrap();
+        assert_eq!(json, r#"{"kind":"http_status","status":404}"#);
+
+        let parsed: ExtractionError = serde_json::from_str(r#"{"kind":"paywall"}"#).unwrap();
+        assert_eq!(parsed, ExtractionError::Paywall);
+    }
+
+    #[test]
+    fn test_extraction_error_from_message() {
+        let errors = [
+            ExtractionError::HttpStatus { status: 403 },
+            ExtractionError::HttpStatus { status: 503 },
+            ExtractionError::HttpStatus { status: 418 },
+            ExtractionError::Timeout,
+            ExtractionError::DnsFailure,
+            ExtractionError::Connection {
+                message: "tls handshake eof".to_string(),
+            },
+            ExtractionError::TooShort { chars: 120 },
+            ExtractionError::JavaScriptRequired,
+            ExtractionError::Paywall,
+            ExtractionError::NotHtml {
+                content_type: "image/png".to_string(),
+            },
+            ExtractionError::RobotsDisallowed,
+            ExtractionError::Other {
+                message: "Something odd".to_string(),
+            },
+        ];
+        for error in errors {
+            assert_eq!(ExtractionError::from_message(&error.to_string()), error);
+        }
+    }
+
     #[test]
     fn test_article_content_struct() {
         let content = ArticleContent {
             text: "Article text".to_string(),
             published_date: Some("2026-02-01T00:00:00+00:00".to_string()),
+            metadata: ArticleMetadata::default(),
         };
 
         assert_eq!(content.text, "Article text");
@@ -324,13 +1408,11 @@ mod tests {
         let success = ExtractionResult::Success(ArticleContent {
             text: "text".to_string(),
             published_date: None,
+            metadata: ArticleMetadata::default(),
         });
         assert!(matches!(success, ExtractionResult::Success(_)));
 
-        let paywalled = ExtractionResult::Paywalled;
-        assert!(matches!(paywalled, ExtractionResult::Paywalled));
-
-        let failed = ExtractionResult::Failed("error".to_string());
-        assert!(matches!(failed, ExtractionResult::Failed(_)));
+        let failed = ExtractionResult::Failed(ExtractionError::Paywall);
+        assert!(matches!(failed, ExtractionResult::Failed(e) if e.is_paywall()));
     }
 }
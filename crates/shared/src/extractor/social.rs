@@ -0,0 +1,650 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use reqwest::Client;
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+
+use crate::extractor::{ArticleContent, ExtractionError};
+use crate::metadata::ArticleMetadata;
+use crate::politeness::Politeness;
+
+/// Where the social extractors send their API requests. Overridable so tests
+/// can point them at a local stub.
+#[derive(Debug, Clone)]
+pub struct SocialEndpoints {
+    pub x_oembed: String,
+    pub bluesky_appview: String,
+    /// Send Mastodon status lookups here instead of the post's own instance
+    pub mastodon: Option<String>,
+}
+
+impl Default for SocialEndpoints {
+    fn default() -> Self {
+        Self {
+            x_oembed: "https://publish.twitter.com/oembed".to_string(),
+            bluesky_appview: "https://public.api.bsky.app".to_string(),
+            mastodon: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Platform {
+    X,
+    Mastodon,
+    Bluesky,
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Platform::X => write!(f, "X"),
+            Platform::Mastodon => write!(f, "Mastodon"),
+            Platform::Bluesky => write!(f, "Bluesky"),
+        }
+    }
+}
+
+/// A link to a single post on a platform we have an extractor for
+#[derive(Debug, Clone, PartialEq)]
+pub enum SocialLink {
+    X { user: String, id: String },
+    Mastodon { origin: String, id: String },
+    Bluesky { actor: String, rkey: String },
+}
+
+impl SocialLink {
+    pub fn parse(url: &str) -> Option<Self> {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.to_lowercase();
+        let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let is_id = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        if matches!(
+            host,
+            "x.com" | "twitter.com" | "mobile.twitter.com" | "mobile.x.com"
+        ) {
+            return match segments.as_slice() {
+                [user, "status", id, ..] if is_id(id) => Some(SocialLink::X {
+                    user: user.to_string(),
+                    id: id.to_string(),
+                }),
+                _ => None,
+            };
+        }
+
+        if matches!(host, "bsky.app" | "staging.bsky.app") {
+            return match segments.as_slice() {
+                ["profile", actor, "post", rkey] => Some(SocialLink::Bluesky {
+                    actor: actor.to_string(),
+                    rkey: rkey.to_string(),
+                }),
+                _ => None,
+            };
+        }
+
+        // Mastodon runs on thousands of hosts, so go by its URL shapes
+        let mastodon_id = match segments.as_slice() {
+            [user, id] if user.starts_with('@') && is_id(id) => Some(id),
+            ["users", _, "statuses", id] if is_id(id) => Some(id),
+            _ => None,
+        };
+        mastodon_id.map(|id| SocialLink::Mastodon {
+            origin: parsed.origin().ascii_serialization(),
+            id: id.to_string(),
+        })
+    }
+}
+
+/// A post pulled from a platform's API
+#[derive(Debug, Clone, PartialEq)]
+pub struct SocialPost {
+    pub platform: Platform,
+    /// `@user` on X, `@user@instance` on Mastodon, `@handle.bsky.social` on Bluesky
+    pub author_handle: String,
+    pub author_name: Option<String>,
+    pub text: String,
+    /// RFC 3339
+    pub published: Option<String>,
+    pub quoted: Option<Box<SocialPost>>,
+    /// The article the post links to, if any
+    pub linked_url: Option<String>,
+    pub linked_title: Option<String>,
+}
+
+impl SocialPost {
+    fn byline(&self) -> String {
+        match &self.author_name {
+            Some(name) if !name.is_empty() => format!("{} ({})", name, self.author_handle),
+            _ => self.author_handle.clone(),
+        }
+    }
+
+    /// The post as article text for the summarizer, with the quoted post and
+    /// linked article spelled out
+    pub fn into_article(self, url: &str) -> ArticleContent {
+        let mut text = format!("{} on {}:\n\n{}", self.byline(), self.platform, self.text);
+        if let Some(quoted) = &self.quoted {
+            text.push_str(&format!(
+                "\n\nQuoting {}:\n\n{}",
+                quoted.byline(),
+                quoted.text
+            ));
+        }
+        match (&self.linked_url, &self.linked_title) {
+            (Some(link), Some(title)) => {
+                text.push_str(&format!("\n\nLinked article: {} - {}", title, link))
+            }
+            (Some(link), None) => text.push_str(&format!("\n\nLinked article: {}", link)),
+            _ => {}
+        }
+
+        ArticleContent {
+            text,
+            published_date: self.published.clone(),
+            metadata: ArticleMetadata {
+                author: Some(self.byline()),
+                publisher: Some(self.platform.to_string()),
+                canonical_url: Some(url.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Fetch a post through its platform's public API
+pub async fn fetch_post(
+    client: &Client,
+    politeness: &Politeness,
+    endpoints: &SocialEndpoints,
+    link: &SocialLink,
+) -> Result<SocialPost, ExtractionError> {
+    match link {
+        SocialLink::X { user, id } => {
+            let url = format!("https://twitter.com/{}/status/{}", user, id);
+            let oembed: XOembed = get_json(
+                client,
+                politeness,
+                &endpoints.x_oembed,
+                &[
+                    ("url", url.as_str()),
+                    ("omit_script", "true"),
+                    ("dnt", "true"),
+                ],
+            )
+            .await?;
+            Ok(oembed.into_post())
+        }
+        SocialLink::Mastodon { origin, id } => {
+            let base = endpoints.mastodon.as_deref().unwrap_or(origin);
+            let url = format!("{}/api/v1/statuses/{}", base.trim_end_matches('/'), id);
+            let status: MastodonStatus = get_json(client, politeness, &url, &[]).await?;
+            let host = url::Url::parse(origin)
+                .ok()
+                .and_then(|u| u.host_str().map(String::from))
+                .unwrap_or_default();
+            Ok(status.into_post(&host))
+        }
+        SocialLink::Bluesky { actor, rkey } => {
+            let uri = format!("at://{}/app.bsky.feed.post/{}", actor, rkey);
+            let url = format!(
+                "{}/xrpc/app.bsky.feed.getPostThread",
+                endpoints.bluesky_appview.trim_end_matches('/')
+            );
+            let response: Value = get_json(
+                client,
+                politeness,
+                &url,
+                &[("uri", uri.as_str()), ("depth", "0"), ("parentHeight", "0")],
+            )
+            .await?;
+            bluesky_post(&response["thread"]["post"]).ok_or_else(|| ExtractionError::Other {
+                message: "Bluesky post not found".to_string(),
+            })
+        }
+    }
+}
+
+async fn get_json<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    politeness: &Politeness,
+    url: &str,
+    query: &[(&str, &str)],
+) -> Result<T, ExtractionError> {
+    let url = url::Url::parse_with_params(url, query).map_err(|e| ExtractionError::Other {
+        message: format!("Invalid API URL {}: {}", url, e),
+    })?;
+    let request = client
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/json");
+    let response = politeness
+        .send(request)
+        .await
+        .map_err(ExtractionError::from_request)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ExtractionError::HttpStatus {
+            status: status.as_u16(),
+        });
+    }
+
+    response.json().await.map_err(|e| ExtractionError::Other {
+        message: format!("Unexpected API response: {}", e),
+    })
+}
+
+/// Text of a post's HTML, keeping its paragraph and line breaks
+fn html_to_text(html: &str) -> String {
+    let html = html
+        .replace("<br>", "\n")
+        .replace("<br/>", "\n")
+        .replace("<br />", "\n")
+        .replace("</p><p>", "</p>\n\n<p>");
+    let fragment = Html::parse_fragment(&html);
+    fragment
+        .root_element()
+        .text()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// X's oEmbed response; the post itself is only available as embed HTML
+#[derive(Debug, Deserialize)]
+struct XOembed {
+    author_name: Option<String>,
+    author_url: Option<String>,
+    html: String,
+}
+
+impl XOembed {
+    fn into_post(self) -> SocialPost {
+        let document = Html::parse_fragment(&self.html);
+        let paragraph = Selector::parse("blockquote p").unwrap();
+        let anchor = Selector::parse("a").unwrap();
+
+        let body = document.select(&paragraph).next();
+        let text = body
+            .map(|p| html_to_text(&p.inner_html()))
+            .unwrap_or_default();
+
+        // Shortened links that aren't attached media, hashtags or mentions
+        let linked_url = body.and_then(|p| {
+            p.select(&anchor)
+                .filter(|a| !a.text().collect::<String>().starts_with("pic."))
+                .filter_map(|a| a.value().attr("href"))
+                .find(|href| href.starts_with("https://t.co/"))
+                .map(String::from)
+        });
+
+        // The embed ends with a permalink whose text is the date, e.g. "February 1, 2026"
+        let published = document
+            .select(&anchor)
+            .next_back()
+            .map(|a| a.text().collect::<String>())
+            .and_then(|date| NaiveDate::parse_from_str(date.trim(), "%B %d, %Y").ok())
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc().to_rfc3339());
+
+        let handle = self
+            .author_url
+            .as_deref()
+            .and_then(|u| u.trim_end_matches('/').rsplit('/').next())
+            .map(|user| format!("@{}", user))
+            .unwrap_or_default();
+
+        SocialPost {
+            platform: Platform::X,
+            author_handle: handle,
+            author_name: self.author_name,
+            text,
+            published,
+            quoted: None,
+            linked_url,
+            linked_title: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct MastodonStatus {
+    created_at: Option<String>,
+    content: String,
+    account: MastodonAccount,
+    #[serde(default)]
+    card: Option<MastodonCard>,
+    #[serde(default)]
+    reblog: Option<Box<MastodonStatus>>,
+    #[serde(default)]
+    quote: Option<MastodonQuote>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MastodonAccount {
+    acct: String,
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MastodonCard {
+    url: String,
+    #[serde(default)]
+    title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MastodonQuote {
+    #[serde(default)]
+    quoted_status: Option<Box<MastodonStatus>>,
+}
+
+impl MastodonStatus {
+    /// `host` is the instance the post was linked from; local accounts'
+    /// `acct` leaves it out
+    fn into_post(self, host: &str) -> SocialPost {
+        // A boost carries no text of its own
+        if let Some(reblog) = self.reblog {
+            return reblog.into_post(host);
+        }
+
+        let author_handle = if self.account.acct.contains('@') {
+            format!("@{}", self.account.acct)
+        } else {
+            format!("@{}@{}", self.account.acct, host)
+        };
+        let card = self.card;
+
+        SocialPost {
+            platform: Platform::Mastodon,
+            author_handle,
+            author_name: self.account.display_name.filter(|name| !name.is_empty()),
+            text: html_to_text(&self.content),
+            published: self.created_at.as_deref().and_then(rfc3339),
+            quoted: self
+                .quote
+                .and_then(|quote| quote.quoted_status)
+                .map(|quoted| Box::new(quoted.into_post(host))),
+            linked_url: card.as_ref().map(|card| card.url.clone()),
+            linked_title: card
+                .and_then(|card| card.title)
+                .filter(|title| !title.is_empty()),
+        }
+    }
+}
+
+fn rfc3339(date: &str) -> Option<String> {
+    DateTime::parse_from_rfc3339(date)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc).to_rfc3339())
+}
+
+/// A post view from the Bluesky AppView (`postView` or an embedded `viewRecord`)
+fn bluesky_post(post: &Value) -> Option<SocialPost> {
+    // Thread posts keep the record under `record`, embedded quotes under `value`
+    let record = post.get("record").or_else(|| post.get("value"))?;
+    let text = record["text"].as_str()?.to_string();
+    let author = &post["author"];
+
+    let mut linked_url = None;
+    let mut linked_title = None;
+    let mut quoted = None;
+
+    let embeds = post
+        .get("embed")
+        .into_iter()
+        .chain(post["embeds"].as_array().into_iter().flatten());
+    for embed in embeds {
+        let kind = embed["$type"].as_str().unwrap_or_default();
+        if kind.starts_with("app.bsky.embed.external") {
+            linked_url = embed["external"]["uri"].as_str().map(String::from);
+            linked_title = embed["external"]["title"]
+                .as_str()
+                .filter(|title| !title.is_empty())
+                .map(String::from);
+        } else if kind.starts_with("app.bsky.embed.recordWithMedia") {
+            quoted = bluesky_post(&embed["record"]["record"]).map(Box::new);
+        } else if kind.starts_with("app.bsky.embed.record") {
+            quoted = bluesky_post(&embed["record"]).map(Box::new);
+        }
+    }
+
+    // Links in the text are shortened for display; the facets hold the full URL
+    if linked_url.is_none() {
+        linked_url = record["facets"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|facet| facet["features"].as_array().into_iter().flatten())
+            .find(|feature| feature["$type"] == "app.bsky.richtext.facet#link")
+            .and_then(|feature| feature["uri"].as_str())
+            .map(String::from);
+    }
+
+    Some(SocialPost {
+        platform: Platform::Bluesky,
+        author_handle: format!("@{}", author["handle"].as_str()?),
+        author_name: author["displayName"]
+            .as_str()
+            .filter(|name| !name.is_empty())
+            .map(String::from),
+        text,
+        published: record["createdAt"].as_str().and_then(rfc3339),
+        quoted,
+        linked_url,
+        linked_title,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn politeness() -> Politeness {
+        Politeness::new(crate::config::PolitenessSettings {
+            host_delay_ms: 0,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_parse_links() {
+        assert_eq!(
+            SocialLink::parse("https://x.com/jack/status/20?s=20"),
+            Some(SocialLink::X {
+                user: "jack".to_string(),
+                id: "20".to_string()
+            })
+        );
+        assert!(SocialLink::parse("https://mobile.twitter.com/jack/status/20").is_some());
+        assert_eq!(
+            SocialLink::parse("https://bsky.app/profile/jay.bsky.team/post/3l6oveex3ii2l"),
+            Some(SocialLink::Bluesky {
+                actor: "jay.bsky.team".to_string(),
+                rkey: "3l6oveex3ii2l".to_string()
+            })
+        );
+        assert_eq!(
+            SocialLink::parse("https://mastodon.social/@Gargron/113374423485279000"),
+            Some(SocialLink::Mastodon {
+                origin: "https://mastodon.social".to_string(),
+                id: "113374423485279000".to_string()
+            })
+        );
+        assert!(SocialLink::parse("https://infosec.exchange/users/someone/statuses/1").is_some());
+
+        assert!(SocialLink::parse("https://x.com/jack").is_none());
+        assert!(SocialLink::parse("https://bsky.app/profile/jay.bsky.team").is_none());
+        assert!(SocialLink::parse("https://medium.com/@writer/a-story-1a2b3c").is_none());
+        assert!(SocialLink::parse("https://www.theverge.com/2026/2/1/story").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_x_oembed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/oembed"))
+            .and(query_param("url", "https://twitter.com/jack/status/20"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "author_name": "jack",
+                "author_url": "https://twitter.com/jack",
+                "html": "<blockquote class=\"twitter-tweet\"><p lang=\"en\" dir=\"ltr\">just setting up my twttr<br>read this <a href=\"https://t.co/abc\">example.com/story</a> <a href=\"https://t.co/pic\">pic.twitter.com/xyz</a></p>&mdash; jack (@jack) <a href=\"https://twitter.com/jack/status/20?ref_src=twsrc%5Etfw\">March 21, 2006</a></blockquote>"
+            })))
+            .mount(&server)
+            .await;
+
+        let endpoints = SocialEndpoints {
+            x_oembed: format!("{}/oembed", server.uri()),
+            ..Default::default()
+        };
+        let link = SocialLink::parse("https://x.com/jack/status/20").unwrap();
+        let post = fetch_post(&Client::new(), &politeness(), &endpoints, &link)
+            .await
+            .unwrap();
+
+        assert_eq!(post.author_handle, "@jack");
+        assert!(post.text.starts_with("just setting up my twttr\nread this"));
+        assert_eq!(post.linked_url.as_deref(), Some("https://t.co/abc"));
+        assert_eq!(post.published.as_deref(), Some("2006-03-21T00:00:00+00:00"));
+    }
+
+    #[tokio::test]
+    async fn test_mastodon_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/statuses/1234"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "1234",
+                "created_at": "2026-02-01T18:04:05.000Z",
+                "content": "<p>New post on the blog.</p><p>Second paragraph</p>",
+                "account": {"acct": "jane", "display_name": "Jane Doe"},
+                "card": {"url": "https://example.com/blog", "title": "The Blog Post"},
+                "quote": {
+                    "state": "accepted",
+                    "quoted_status": {
+                        "created_at": "2026-01-31T10:00:00.000Z",
+                        "content": "<p>Original thought</p>",
+                        "account": {"acct": "sam@hachyderm.io", "display_name": ""}
+                    }
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let endpoints = SocialEndpoints {
+            mastodon: Some(server.uri()),
+            ..Default::default()
+        };
+        let link = SocialLink::parse("https://mastodon.social/@jane/1234").unwrap();
+        let post = fetch_post(&Client::new(), &politeness(), &endpoints, &link)
+            .await
+            .unwrap();
+
+        assert_eq!(post.author_handle, "@jane@mastodon.social");
+        assert_eq!(post.author_name.as_deref(), Some("Jane Doe"));
+        assert_eq!(post.text, "New post on the blog.\n\nSecond paragraph");
+        assert_eq!(post.published.as_deref(), Some("2026-02-01T18:04:05+00:00"));
+        assert_eq!(post.linked_url.as_deref(), Some("https://example.com/blog"));
+        assert_eq!(post.linked_title.as_deref(), Some("The Blog Post"));
+
+        let quoted = post.quoted.unwrap();
+        assert_eq!(quoted.author_handle, "@sam@hachyderm.io");
+        assert_eq!(quoted.author_name, None);
+        assert_eq!(quoted.text, "Original thought");
+    }
+
+    #[tokio::test]
+    async fn test_bluesky_post_thread() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/xrpc/app.bsky.feed.getPostThread"))
+            .and(query_param(
+                "uri",
+                "at://jay.bsky.team/app.bsky.feed.post/3abc",
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "thread": {
+                    "$type": "app.bsky.feed.defs#threadViewPost",
+                    "post": {
+                        "author": {"handle": "jay.bsky.team", "displayName": "Jay"},
+                        "record": {
+                            "text": "Worth a read: example.com/long-art...",
+                            "createdAt": "2026-02-01T12:00:00.000Z",
+                            "facets": [{"features": [{
+                                "$type": "app.bsky.richtext.facet#link",
+                                "uri": "https://example.com/long-article"
+                            }]}]
+                        },
+                        "embed": {
+                            "$type": "app.bsky.embed.record#view",
+                            "record": {
+                                "$type": "app.bsky.embed.record#viewRecord",
+                                "author": {"handle": "pfrazee.com"},
+                                "value": {"text": "Quoted skeet", "createdAt": "2026-01-30T00:00:00Z"}
+                            }
+                        }
+                    }
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let endpoints = SocialEndpoints {
+            bluesky_appview: server.uri(),
+            ..Default::default()
+        };
+        let link = SocialLink::parse("https://bsky.app/profile/jay.bsky.team/post/3abc").unwrap();
+        let post = fetch_post(&Client::new(), &politeness(), &endpoints, &link)
+            .await
+            .unwrap();
+
+        assert_eq!(post.author_handle, "@jay.bsky.team");
+        assert_eq!(post.published.as_deref(), Some("2026-02-01T12:00:00+00:00"));
+        assert_eq!(
+            post.linked_url.as_deref(),
+            Some("https://example.com/long-article")
+        );
+        let quoted = post.quoted.as_ref().unwrap();
+        assert_eq!(quoted.author_handle, "@pfrazee.com");
+        assert_eq!(quoted.text, "Quoted skeet");
+
+        let article = post.into_article("https://bsky.app/profile/jay.bsky.team/post/3abc");
+        assert!(article.text.starts_with("Jay (@jay.bsky.team) on Bluesky:"));
+        assert!(article
+            .text
+            .contains("Quoting @pfrazee.com:\n\nQuoted skeet"));
+        assert!(article
+            .text
+            .ends_with("Linked article: https://example.com/long-article"));
+        assert_eq!(
+            article.metadata.attribution().as_deref(),
+            Some("Bluesky's Jay (@jay.bsky.team)")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_post() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&server)
+            .await;
+
+        let endpoints = SocialEndpoints {
+            x_oembed: server.uri(),
+            ..Default::default()
+        };
+        let link = SocialLink::parse("https://x.com/jack/status/21").unwrap();
+        assert_eq!(
+            fetch_post(&Client::new(), &politeness(), &endpoints, &link)
+                .await
+                .unwrap_err(),
+            ExtractionError::HttpStatus { status: 404 }
+        );
+    }
+}
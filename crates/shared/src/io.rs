@@ -118,7 +118,13 @@ mod tests {
             summary: Summary::Success {
                 points: vec!["Point 1".to_string()],
                 quote: None,
+                speaker: None,
             },
+            producer_note: None,
+            highlights: Vec::new(),
+            metadata: Default::default(),
+            extraction_error: None,
+            original_url: None,
         };
         let topics = vec![Topic {
             title: "News".to_string(),
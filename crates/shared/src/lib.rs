@@ -1,19 +1,44 @@
 // Public modules
+pub mod article_cache;
+pub mod bookmarks;
 pub mod briefing;
+pub mod cache_db;
 pub mod clustering;
 pub mod config;
 pub mod cookies;
 pub mod extractor;
 pub mod io;
+pub mod llm;
+pub mod metadata;
 pub mod models;
+pub mod pdf;
+pub mod politeness;
+pub mod query;
 pub mod raindrop;
+pub mod raindrop_cache;
+pub mod rate_limit;
+pub mod readability;
+pub mod site_rules;
 pub mod summarizer;
+pub mod summary_cache;
+pub mod urls;
+pub mod video;
 
 // Re-export commonly used types
+pub use bookmarks::{Bookmark, BookmarkSource};
 pub use clustering::{Story, Topic, TopicClusterer};
-pub use config::Config;
-pub use extractor::{ArticleContent, ContentExtractor, ExtractionResult};
+pub use config::{
+    Config, CookieSettings, LlmSettings, PolitenessSettings, Settings, SummarySettings,
+};
+pub use extractor::{ArticleContent, ContentExtractor, ExtractionError, ExtractionResult};
 pub use io::{get_default_stories_dir, list_story_files, load_stories, save_stories};
+pub use llm::{LlmProvider, LlmTask};
+pub use metadata::ArticleMetadata;
 pub use models::{BriefingData, ShowInfo};
+pub use query::{BookmarkQuery, TagExpr};
 pub use raindrop::RaindropClient;
-pub use summarizer::{ClaudeSummarizer, Summary};
+pub use rate_limit::RateLimiters;
+pub use site_rules::SiteRules;
+pub use summarizer::{Summarizer, Summary};
+pub use summary_cache::SummaryCache;
+pub use urls::UrlResolver;
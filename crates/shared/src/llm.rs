@@ -0,0 +1,1019 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::config::{LlmSettings, RateLimitSettings};
+use crate::rate_limit::{estimate_tokens, RateLimiter, RateLimiters};
+
+/// A language model that answers a single prompt with text
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Provider and model, for progress output
+    fn name(&self) -> String;
+
+    /// Send `prompt` as one user message and return the reply text
+    async fn complete(&self, prompt: &str) -> Result<String>;
+
+    /// Send `prompt` and make the model answer by calling `tool`, returning
+    /// the tool's arguments. Providers without tool use get the schema in the
+    /// prompt and have the JSON picked out of their reply.
+    async fn complete_json(&self, prompt: &str, tool: &JsonTool) -> Result<Value> {
+        let prompt = format!(
+            "{}\n\nRespond with only a JSON object matching this JSON schema:\n{}",
+            prompt, tool.schema
+        );
+        let reply = self.complete(&prompt).await?;
+        extract_json_object(&reply)
+    }
+
+    /// Whether [`LlmProvider::complete_json_batch`] is available
+    fn supports_batch(&self) -> bool {
+        false
+    }
+
+    /// Answer many `(id, prompt)` pairs through `tool` as one asynchronous
+    /// batch job, waiting for it to finish. Ids must match
+    /// `[a-zA-Z0-9_-]{1,64}`. Requests that failed inside the batch come back
+    /// as `Err` with the reason; the whole batch failing is the outer `Err`.
+    async fn complete_json_batch(
+        &self,
+        _requests: &[(String, String)],
+        _tool: &JsonTool,
+    ) -> Result<HashMap<String, Result<Value, String>>> {
+        anyhow::bail!("{} has no batch API", self.name())
+    }
+}
+
+/// A function the model is made to call, to get its answer in a fixed JSON shape
+#[derive(Debug, Clone)]
+pub struct JsonTool {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// JSON schema for the arguments
+    pub schema: Value,
+}
+
+/// Parse the outermost `{...}` in a reply, skipping any prose or code fence
+/// around it
+pub fn extract_json_object(reply: &str) -> Result<Value> {
+    let json_text = match (reply.find('{'), reply.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => reply,
+    };
+    serde_json::from_str(json_text).context("Reply is not a JSON object")
+}
+
+/// Which API a provider speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    #[default]
+    Anthropic,
+    /// OpenAI's chat completions API, also served by Ollama, llama.cpp,
+    /// vLLM and LM Studio
+    OpenAi,
+}
+
+impl ProviderKind {
+    fn default_base_url(self) -> &'static str {
+        match self {
+            ProviderKind::Anthropic => "https://api.anthropic.com",
+            ProviderKind::OpenAi => "https://api.openai.com/v1",
+        }
+    }
+
+    fn default_model(self) -> &'static str {
+        match self {
+            ProviderKind::Anthropic => "claude-3-5-haiku-20241022",
+            ProviderKind::OpenAi => "gpt-4o-mini",
+        }
+    }
+
+    fn default_api_key_env(self) -> &'static str {
+        match self {
+            ProviderKind::Anthropic => "ANTHROPIC_API_KEY",
+            ProviderKind::OpenAi => "OPENAI_API_KEY",
+        }
+    }
+}
+
+impl fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::OpenAi => "openai",
+        })
+    }
+}
+
+/// The jobs a model is used for, each configurable on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LlmTask {
+    Summarize,
+    Cluster,
+}
+
+impl LlmTask {
+    fn default_max_tokens(self) -> u32 {
+        match self {
+            LlmTask::Summarize => 512,
+            LlmTask::Cluster => 2048,
+        }
+    }
+}
+
+/// Which model to ask, where, and with what parameters
+#[derive(Debug, Clone)]
+pub struct ModelConfig {
+    pub base_url: String,
+    pub model: String,
+    pub max_tokens: u32,
+    pub temperature: Option<f32>,
+    pub timeout: Duration,
+}
+
+impl ModelConfig {
+    fn client(&self) -> Result<Client> {
+        Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .context("Failed to create HTTP client")
+    }
+
+    fn endpoint(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), path)
+    }
+}
+
+/// The provider configured for `task`, with the task's `[llm.*]` overrides
+/// applied over the `[llm]` defaults. Providers calling the same API share
+/// its limiter from `limiters`.
+pub fn provider_for(
+    settings: &LlmSettings,
+    task: LlmTask,
+    limiters: &RateLimiters,
+) -> Result<Arc<dyn LlmProvider>> {
+    let task_settings = settings.for_task(task);
+    let kind = task_settings.provider.unwrap_or_default();
+
+    let model = ModelConfig {
+        base_url: task_settings
+            .base_url
+            .unwrap_or_else(|| kind.default_base_url().to_string()),
+        model: task_settings
+            .model
+            .unwrap_or_else(|| kind.default_model().to_string()),
+        max_tokens: task_settings
+            .max_tokens
+            .unwrap_or_else(|| task.default_max_tokens()),
+        temperature: task_settings.temperature,
+        timeout: Duration::from_secs(task_settings.timeout_secs.unwrap_or(60)),
+    };
+
+    let key_env = task_settings
+        .api_key_env
+        .unwrap_or_else(|| kind.default_api_key_env().to_string());
+    let api_key = std::env::var(&key_env).ok().filter(|key| !key.is_empty());
+    let limiter = limiters.for_api(&model.base_url);
+
+    Ok(match kind {
+        ProviderKind::Anthropic => {
+            let api_key = api_key.with_context(|| {
+                format!(
+                    "{} not found.\n\n\
+                    To fix this, add it to ~/.config/podcast-briefing/.env:\n  \
+                    {}=your_key_here\n\n\
+                    Get your Anthropic API key from: https://console.anthropic.com/settings/keys\n\
+                    Or point [llm] in config.toml at a local model instead.",
+                    key_env, key_env
+                )
+            })?;
+            Arc::new(AnthropicProvider::new(api_key, model)?.with_rate_limiter(limiter))
+        }
+        // Local servers don't need a key
+        ProviderKind::OpenAi => {
+            Arc::new(OpenAiProvider::new(api_key, model)?.with_rate_limiter(limiter))
+        }
+    })
+}
+
+#[derive(Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    messages: Vec<ChatMessage<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<Value>,
+}
+
+#[derive(Serialize)]
+struct ChatMessage<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+impl<'a> ChatRequest<'a> {
+    fn new(model: &'a ModelConfig, prompt: &'a str) -> Self {
+        Self {
+            model: &model.model,
+            max_tokens: model.max_tokens,
+            temperature: model.temperature,
+            messages: vec![ChatMessage {
+                role: "user",
+                content: prompt,
+            }],
+            tools: None,
+            tool_choice: None,
+        }
+    }
+}
+
+/// A model API answering with an error status
+#[derive(Debug)]
+pub struct ApiError {
+    pub provider: String,
+    pub status: u16,
+    pub body: String,
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} API error (status {}): {}",
+            self.provider, self.status, self.body
+        )
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// Whether `error` is the API turning a call away for going over its rate
+/// limit; the provider's [`RateLimiter`] already holds off the next call
+pub fn is_rate_limit(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .any(|e| matches!(e.downcast_ref::<ApiError>(), Some(e) if e.status == 429))
+}
+
+/// Bail with an [`ApiError`] holding the status and body of a failed call
+async fn check_status(provider: &str, response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| String::from("unknown error"));
+    Err(ApiError {
+        provider: provider.to_string(),
+        status: status.as_u16(),
+        body,
+    }
+    .into())
+}
+
+/// Send a chat request once `limiter` has room for it, and tell the limiter
+/// what the response said about the budget
+async fn send_limited(
+    limiter: &Arc<RateLimiter>,
+    builder: reqwest::RequestBuilder,
+    request: &ChatRequest<'_>,
+) -> reqwest::Result<reqwest::Response> {
+    let body = serde_json::to_vec(request).expect("chat requests always serialize");
+    let _permit = limiter.acquire(estimate_tokens(&body)).await;
+    let response = builder
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await?;
+    limiter.observe(response.status().as_u16(), response.headers());
+    Ok(response)
+}
+
+/// How often to check on a submitted batch
+const BATCH_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Give up on a batch that hasn't finished by then; Anthropic allows up to 24 hours
+const BATCH_MAX_WAIT: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// Claude via Anthropic's Messages API
+pub struct AnthropicProvider {
+    client: Client,
+    api_key: String,
+    model: ModelConfig,
+    limiter: Arc<RateLimiter>,
+    batch_poll_interval: Duration,
+    batch_max_wait: Duration,
+}
+
+#[derive(Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+}
+
+#[derive(Deserialize)]
+struct AnthropicContent {
+    #[serde(default)]
+    text: String,
+    /// Arguments of a `tool_use` block
+    #[serde(default)]
+    input: Option<Value>,
+}
+
+impl AnthropicResponse {
+    fn tool_input(self, tool: &JsonTool) -> Result<Value> {
+        self.content
+            .into_iter()
+            .find_map(|c| c.input)
+            .with_context(|| format!("Claude didn't call the {} tool", tool.name))
+    }
+}
+
+#[derive(Serialize)]
+struct BatchItem<'a> {
+    custom_id: &'a str,
+    params: ChatRequest<'a>,
+}
+
+#[derive(Deserialize)]
+struct BatchStatus {
+    id: String,
+    processing_status: String,
+    results_url: Option<String>,
+}
+
+/// One line of a batch's JSONL results
+#[derive(Deserialize)]
+struct BatchResultLine {
+    custom_id: String,
+    result: BatchOutcome,
+}
+
+#[derive(Deserialize)]
+struct BatchOutcome {
+    /// succeeded, errored, canceled or expired
+    #[serde(rename = "type")]
+    kind: String,
+    message: Option<AnthropicResponse>,
+    error: Option<Value>,
+}
+
+impl AnthropicProvider {
+    pub fn new(api_key: String, model: ModelConfig) -> Result<Self> {
+        Ok(Self {
+            client: model.client()?,
+            api_key,
+            model,
+            limiter: Arc::new(RateLimiter::new(&RateLimitSettings::default())),
+            batch_poll_interval: BATCH_POLL_INTERVAL,
+            batch_max_wait: BATCH_MAX_WAIT,
+        })
+    }
+
+    /// Share `limiter` with other providers calling the same API
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Check on batches every `interval`, giving up after `max_wait`
+    pub fn with_batch_polling(mut self, interval: Duration, max_wait: Duration) -> Self {
+        self.batch_poll_interval = interval;
+        self.batch_max_wait = max_wait;
+        self
+    }
+
+    fn tool_request<'a>(&'a self, prompt: &'a str, tool: &JsonTool) -> ChatRequest<'a> {
+        let mut request = ChatRequest::new(&self.model, prompt);
+        request.tools = Some(vec![serde_json::json!({
+            "name": tool.name,
+            "description": tool.description,
+            "input_schema": tool.schema,
+        })]);
+        request.tool_choice = Some(serde_json::json!({ "type": "tool", "name": tool.name }));
+        request
+    }
+
+    fn request(&self, method: reqwest::Method, url: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+    }
+
+    async fn send(&self, request: &ChatRequest<'_>) -> Result<AnthropicResponse> {
+        let builder = self.request(reqwest::Method::POST, &self.model.endpoint("v1/messages"));
+        let response = send_limited(&self.limiter, builder, request)
+            .await
+            .context("Failed to send request to Claude API")?;
+
+        check_status("Claude", response)
+            .await?
+            .json()
+            .await
+            .context("Failed to parse Claude API response")
+    }
+
+    async fn batch_status(&self, id: &str) -> Result<BatchStatus> {
+        let url = self.model.endpoint(&format!("v1/messages/batches/{}", id));
+        let response = self
+            .request(reqwest::Method::GET, &url)
+            .send()
+            .await
+            .context("Failed to check on message batch")?;
+
+        check_status("Claude batch", response)
+            .await?
+            .json()
+            .await
+            .context("Failed to parse message batch status")
+    }
+
+    /// Wait for a batch to end, cancelling it if it runs past the maximum wait
+    async fn wait_for_batch(&self, mut status: BatchStatus) -> Result<BatchStatus> {
+        let started = std::time::Instant::now();
+        while status.processing_status != "ended" {
+            if started.elapsed() >= self.batch_max_wait {
+                let url = self
+                    .model
+                    .endpoint(&format!("v1/messages/batches/{}/cancel", status.id));
+                let _ = self.request(reqwest::Method::POST, &url).send().await;
+                anyhow::bail!(
+                    "Message batch {} still {} after {:?}; cancelled it",
+                    status.id,
+                    status.processing_status,
+                    self.batch_max_wait
+                );
+            }
+            tokio::time::sleep(self.batch_poll_interval).await;
+            status = self.batch_status(&status.id).await?;
+        }
+        Ok(status)
+    }
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> String {
+        format!("Anthropic ({})", self.model.model)
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        let response = self.send(&ChatRequest::new(&self.model, prompt)).await?;
+
+        Ok(response
+            .content
+            .into_iter()
+            .next()
+            .map(|c| c.text)
+            .unwrap_or_default())
+    }
+
+    async fn complete_json(&self, prompt: &str, tool: &JsonTool) -> Result<Value> {
+        self.send(&self.tool_request(prompt, tool))
+            .await?
+            .tool_input(tool)
+    }
+
+    fn supports_batch(&self) -> bool {
+        true
+    }
+
+    async fn complete_json_batch(
+        &self,
+        requests: &[(String, String)],
+        tool: &JsonTool,
+    ) -> Result<HashMap<String, Result<Value, String>>> {
+        let items: Vec<BatchItem> = requests
+            .iter()
+            .map(|(id, prompt)| BatchItem {
+                custom_id: id,
+                params: self.tool_request(prompt, tool),
+            })
+            .collect();
+
+        let response = self
+            .request(
+                reqwest::Method::POST,
+                &self.model.endpoint("v1/messages/batches"),
+            )
+            .json(&serde_json::json!({ "requests": items }))
+            .send()
+            .await
+            .context("Failed to submit message batch")?;
+        let status: BatchStatus = check_status("Claude batch", response)
+            .await?
+            .json()
+            .await
+            .context("Failed to parse message batch status")?;
+        eprintln!(
+            "  Submitted batch {} with {} requests, waiting for results...",
+            status.id,
+            requests.len()
+        );
+
+        let status = self.wait_for_batch(status).await?;
+        let results_url = status
+            .results_url
+            .with_context(|| format!("Message batch {} ended without results", status.id))?;
+        let response = self
+            .request(reqwest::Method::GET, &results_url)
+            .send()
+            .await
+            .context("Failed to fetch message batch results")?;
+        let body = check_status("Claude batch", response).await?.text().await?;
+
+        let mut results = HashMap::new();
+        for line in body.lines().filter(|line| !line.trim().is_empty()) {
+            let line: BatchResultLine =
+                serde_json::from_str(line).context("Failed to parse message batch result")?;
+            let result = match (line.result.kind.as_str(), line.result.message) {
+                ("succeeded", Some(message)) => message.tool_input(tool).map_err(|e| e.to_string()),
+                (kind, _) => Err(match line.result.error {
+                    Some(error) => format!("Batch request {}: {}", kind, error),
+                    None => format!("Batch request {}", kind),
+                }),
+            };
+            results.insert(line.custom_id, result);
+        }
+
+        Ok(results)
+    }
+}
+
+/// Any server speaking OpenAI's chat completions API: OpenAI itself, or a
+/// local Ollama (`http://localhost:11434/v1`) or llama.cpp server
+pub struct OpenAiProvider {
+    client: Client,
+    api_key: Option<String>,
+    model: ModelConfig,
+    limiter: Arc<RateLimiter>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiResponse {
+    choices: Vec<OpenAiChoice>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiChoice {
+    message: OpenAiMessage,
+}
+
+#[derive(Deserialize)]
+struct OpenAiMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAiToolCall>,
+}
+
+#[derive(Deserialize)]
+struct OpenAiToolCall {
+    function: OpenAiFunction,
+}
+
+#[derive(Deserialize)]
+struct OpenAiFunction {
+    /// JSON-encoded arguments
+    arguments: String,
+}
+
+impl OpenAiProvider {
+    pub fn new(api_key: Option<String>, model: ModelConfig) -> Result<Self> {
+        Ok(Self {
+            client: model.client()?,
+            api_key,
+            model,
+            limiter: Arc::new(RateLimiter::new(&RateLimitSettings::default())),
+        })
+    }
+
+    /// Share `limiter` with other providers calling the same API
+    pub fn with_rate_limiter(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    async fn send(&self, request: &ChatRequest<'_>) -> Result<Option<OpenAiMessage>> {
+        let mut builder = self.client.post(self.model.endpoint("chat/completions"));
+        if let Some(api_key) = &self.api_key {
+            builder = builder.bearer_auth(api_key);
+        }
+
+        let response = send_limited(&self.limiter, builder, request)
+            .await
+            .with_context(|| format!("Failed to send request to {}", self.model.base_url))?;
+
+        let response: OpenAiResponse = check_status("Chat completions", response)
+            .await?
+            .json()
+            .await
+            .context("Failed to parse chat completions response")?;
+
+        Ok(response.choices.into_iter().next().map(|c| c.message))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> String {
+        format!("{} ({})", self.model.base_url, self.model.model)
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        let message = self.send(&ChatRequest::new(&self.model, prompt)).await?;
+        Ok(message.and_then(|m| m.content).unwrap_or_default())
+    }
+
+    async fn complete_json(&self, prompt: &str, tool: &JsonTool) -> Result<Value> {
+        let mut request = ChatRequest::new(&self.model, prompt);
+        request.tools = Some(vec![serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": tool.name,
+                "description": tool.description,
+                "parameters": tool.schema,
+            },
+        })]);
+        request.tool_choice = Some(serde_json::json!({
+            "type": "function",
+            "function": { "name": tool.name },
+        }));
+
+        let message = self
+            .send(&request)
+            .await?
+            .context("Chat completions response had no choices")?;
+        match message.tool_calls.into_iter().next() {
+            Some(call) => serde_json::from_str(&call.function.arguments)
+                .context("Tool call arguments are not valid JSON"),
+            // Some local models answer in the message text instead
+            None => extract_json_object(message.content.as_deref().unwrap_or_default()),
+        }
+    }
+}
+
+type Responder = dyn Fn(&str) -> Result<String> + Send + Sync;
+
+/// Deterministic stand-in for tests: replies are computed from the prompt and
+/// every prompt is recorded
+pub struct FakeProvider {
+    respond: Box<Responder>,
+    prompts: Mutex<Vec<String>>,
+}
+
+impl FakeProvider {
+    pub fn new(respond: impl Fn(&str) -> Result<String> + Send + Sync + 'static) -> Self {
+        Self {
+            respond: Box::new(respond),
+            prompts: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Give the same reply to every prompt
+    pub fn replying(reply: impl Into<String>) -> Self {
+        let reply = reply.into();
+        Self::new(move |_| Ok(reply.clone()))
+    }
+
+    /// Every prompt sent so far, oldest first
+    pub fn prompts(&self) -> Vec<String> {
+        self.prompts.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for FakeProvider {
+    fn name(&self) -> String {
+        "fake".to_string()
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        self.prompts.lock().unwrap().push(prompt.to_string());
+        (self.respond)(prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn model(base_url: &str) -> ModelConfig {
+        ModelConfig {
+            base_url: base_url.to_string(),
+            model: "test-model".to_string(),
+            max_tokens: 256,
+            temperature: Some(0.2),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_provider() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "secret"))
+            .and(header("anthropic-version", "2023-06-01"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "test-model",
+                "max_tokens": 256,
+                "temperature": 0.2,
+                "messages": [{ "role": "user", "content": "Hello" }],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{ "type": "text", "text": "Hi there" }],
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = AnthropicProvider::new("secret".to_string(), model(&server.uri())).unwrap();
+        assert_eq!(provider.complete("Hello").await.unwrap(), "Hi there");
+    }
+
+    #[tokio::test]
+    async fn test_openai_provider() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(serde_json::json!({
+                "model": "test-model",
+                "messages": [{ "role": "user", "content": "Hello" }],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": { "role": "assistant", "content": "Hi there" } }],
+            })))
+            .mount(&server)
+            .await;
+
+        // No key, as with a local Ollama server
+        let base_url = format!("{}/v1/", server.uri());
+        let provider = OpenAiProvider::new(None, model(&base_url)).unwrap();
+        assert_eq!(provider.complete("Hello").await.unwrap(), "Hi there");
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(!requests[0].headers.contains_key("authorization"));
+    }
+
+    #[tokio::test]
+    async fn test_openai_provider_sends_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": { "content": "ok" } }],
+            })))
+            .mount(&server)
+            .await;
+
+        let provider =
+            OpenAiProvider::new(Some("secret".to_string()), model(&server.uri())).unwrap();
+        assert_eq!(provider.complete("Hello").await.unwrap(), "ok");
+    }
+
+    #[tokio::test]
+    async fn test_errors_keep_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(429)
+                    .insert_header("retry-after", "1")
+                    .set_body_string("rate_limit_error"),
+            )
+            .mount(&server)
+            .await;
+
+        let limiter = Arc::new(RateLimiter::new(&RateLimitSettings {
+            start_concurrent: 4,
+            max_concurrent: 8,
+        }));
+        let provider = AnthropicProvider::new("secret".to_string(), model(&server.uri()))
+            .unwrap()
+            .with_rate_limiter(limiter.clone());
+        let err = provider.complete("Hello").await.unwrap_err();
+        assert!(is_rate_limit(&err));
+        let err = err.to_string();
+        assert!(err.contains("status 429"), "{}", err);
+        assert!(err.contains("rate_limit_error"), "{}", err);
+        // The next call waits out the Retry-After with half the concurrency
+        assert_eq!(limiter.concurrency(), 2);
+        let started = std::time::Instant::now();
+        let _ = provider.complete("Hello").await;
+        assert!(started.elapsed() >= Duration::from_millis(900));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_headers_raise_concurrency() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("anthropic-ratelimit-requests-limit", "50")
+                    .insert_header("anthropic-ratelimit-requests-remaining", "49")
+                    .set_body_json(serde_json::json!({
+                        "content": [{ "type": "text", "text": "Hi there" }],
+                    })),
+            )
+            .mount(&server)
+            .await;
+
+        let limiters = RateLimiters::new(RateLimitSettings::default());
+        let limiter = limiters.for_api(&server.uri());
+        let summarize = AnthropicProvider::new("secret".to_string(), model(&server.uri()))
+            .unwrap()
+            .with_rate_limiter(limiters.for_api(&server.uri()));
+        let cluster = OpenAiProvider::new(None, model(&server.uri()))
+            .unwrap()
+            .with_rate_limiter(limiters.for_api(&server.uri()));
+
+        summarize.complete("Hello").await.unwrap();
+        let _ = cluster.complete("Hello").await;
+        assert_eq!(limiter.concurrency(), 4);
+    }
+
+    fn tool() -> JsonTool {
+        JsonTool {
+            name: "record_answer",
+            description: "Record the answer",
+            schema: serde_json::json!({
+                "type": "object",
+                "properties": { "answer": { "type": "string" } },
+                "required": ["answer"],
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_anthropic_tool_use() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(serde_json::json!({
+                "tools": [{ "name": "record_answer", "input_schema": { "type": "object" } }],
+                "tool_choice": { "type": "tool", "name": "record_answer" },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "content": [{
+                    "type": "tool_use",
+                    "id": "toolu_01",
+                    "name": "record_answer",
+                    "input": { "answer": "42" },
+                }],
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = AnthropicProvider::new("secret".to_string(), model(&server.uri())).unwrap();
+        let value = provider.complete_json("Question", &tool()).await.unwrap();
+        assert_eq!(value, serde_json::json!({ "answer": "42" }));
+    }
+
+    #[tokio::test]
+    async fn test_openai_tool_calls() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "tools": [{ "type": "function", "function": { "name": "record_answer" } }],
+                "tool_choice": { "type": "function", "function": { "name": "record_answer" } },
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": {
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "record_answer", "arguments": "{\"answer\":\"42\"}" },
+                    }],
+                } }],
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = OpenAiProvider::new(None, model(&server.uri())).unwrap();
+        let value = provider.complete_json("Question", &tool()).await.unwrap();
+        assert_eq!(value["answer"], "42");
+    }
+
+    #[tokio::test]
+    async fn test_openai_json_in_content() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "choices": [{ "message": { "content": "```json\n{\"answer\": \"42\"}\n```" } }],
+            })))
+            .mount(&server)
+            .await;
+
+        let provider = OpenAiProvider::new(None, model(&server.uri())).unwrap();
+        let value = provider.complete_json("Question", &tool()).await.unwrap();
+        assert_eq!(value["answer"], "42");
+    }
+
+    #[tokio::test]
+    async fn test_fake_provider_json() {
+        let provider = FakeProvider::replying("Sure! {\"answer\": \"42\"}");
+        let value = provider.complete_json("Question", &tool()).await.unwrap();
+        assert_eq!(value["answer"], "42");
+        // The schema goes in the prompt when there's no tool use
+        assert!(provider.prompts()[0].contains("\"required\":[\"answer\"]"));
+
+        let provider = FakeProvider::replying("no JSON here");
+        assert!(provider.complete_json("Question", &tool()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_cancelled_after_max_wait() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/batches"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msgbatch_01",
+                "processing_status": "in_progress",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_01"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msgbatch_01",
+                "processing_status": "in_progress",
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/batches/msgbatch_01/cancel"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = AnthropicProvider::new("secret".to_string(), model(&server.uri()))
+            .unwrap()
+            .with_batch_polling(Duration::from_millis(10), Duration::from_millis(50));
+        let requests = vec![("article-0".to_string(), "Question".to_string())];
+        let err = provider
+            .complete_json_batch(&requests, &tool())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("cancelled"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_fake_provider() {
+        let provider = FakeProvider::new(|prompt| Ok(prompt.to_uppercase()));
+        assert_eq!(provider.complete("one").await.unwrap(), "ONE");
+        assert_eq!(provider.complete("two").await.unwrap(), "TWO");
+        assert_eq!(provider.prompts(), vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_provider_for_task_overrides() {
+        let settings: LlmSettings = toml::from_str(
+            r#"
+            provider = "openai"
+            base_url = "http://localhost:11434/v1"
+            model = "llama3.1:8b"
+            api_key_env = "COLLECT_STORIES_TEST_UNSET_KEY"
+
+            [cluster]
+            model = "qwen2.5:14b"
+            max_tokens = 4096
+            "#,
+        )
+        .unwrap();
+
+        let limiters = RateLimiters::new(settings.rate_limit.clone());
+        let summarize = provider_for(&settings, LlmTask::Summarize, &limiters).unwrap();
+        assert_eq!(summarize.name(), "http://localhost:11434/v1 (llama3.1:8b)");
+        let cluster = provider_for(&settings, LlmTask::Cluster, &limiters).unwrap();
+        assert_eq!(cluster.name(), "http://localhost:11434/v1 (qwen2.5:14b)");
+    }
+
+    #[test]
+    fn test_anthropic_requires_key() {
+        let settings: LlmSettings =
+            toml::from_str("api_key_env = \"COLLECT_STORIES_TEST_UNSET_KEY\"\n").unwrap();
+        let limiters = RateLimiters::new(settings.rate_limit.clone());
+        let err = provider_for(&settings, LlmTask::Summarize, &limiters)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("COLLECT_STORIES_TEST_UNSET_KEY not found"));
+    }
+}
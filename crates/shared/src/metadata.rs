@@ -0,0 +1,435 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// schema.org types whose JSON-LD describes the article itself
+const ARTICLE_TYPES: &[&str] = &[
+    "Article",
+    "NewsArticle",
+    "ReportageNewsArticle",
+    "AnalysisNewsArticle",
+    "OpinionNewsArticle",
+    "BackgroundNewsArticle",
+    "BlogPosting",
+    "TechArticle",
+    "ScholarlyArticle",
+    "Report",
+];
+
+/// Who wrote an article and where, taken from JSON-LD, OpenGraph and Twitter card tags
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ArticleMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Publication or site name, e.g. "The Verge"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub publisher: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub canonical_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headline: Option<String>,
+    /// Lead image URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+    /// RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+}
+
+impl ArticleMetadata {
+    pub fn from_html(html: &str) -> Self {
+        Self::from_document(&Html::parse_document(html))
+    }
+
+    /// JSON-LD wins over OpenGraph, which wins over Twitter cards and plain meta tags
+    pub fn from_document(document: &Html) -> Self {
+        let ld = json_ld_article(document);
+        let ld_field = |f: fn(&JsonLd, &Value) -> Option<String>| {
+            ld.as_ref().and_then(|(article, graph)| f(graph, article))
+        };
+
+        Self {
+            author: ld_field(|graph, a| graph.names(a.get("author")?))
+                .or_else(|| meta(document, "author").filter(|a| !a.starts_with("http")))
+                .or_else(|| meta(document, "article:author").filter(|a| !a.starts_with("http"))),
+            publisher: ld_field(|graph, a| graph.names(a.get("publisher")?))
+                .or_else(|| meta(document, "og:site_name"))
+                .or_else(|| meta(document, "application-name")),
+            canonical_url: link_href(document, "canonical")
+                .or_else(|| meta(document, "og:url"))
+                .or_else(|| ld_field(|_, a| first_url(a.get("url")?))),
+            headline: ld_field(|_, a| text(a.get("headline")?))
+                .or_else(|| meta(document, "og:title"))
+                .or_else(|| meta(document, "twitter:title")),
+            image: ld_field(|_, a| first_url(a.get("image")?))
+                .or_else(|| meta(document, "og:image"))
+                .or_else(|| meta(document, "twitter:image")),
+            modified_date: ld_field(|_, a| text(a.get("dateModified")?))
+                .or_else(|| meta(document, "article:modified_time"))
+                .or_else(|| meta(document, "og:updated_time"))
+                .and_then(|date| normalize_date(&date)),
+            section: ld_field(|_, a| text(a.get("articleSection")?))
+                .or_else(|| meta(document, "article:section")),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// How the host credits the story on air, e.g. "The Verge's Jane Doe"
+    pub fn attribution(&self) -> Option<String> {
+        match (&self.publisher, &self.author) {
+            (Some(publisher), Some(author)) if publisher != author => {
+                let possessive = if publisher.ends_with('s') { "'" } else { "'s" };
+                Some(format!("{}{} {}", publisher, possessive, author))
+            }
+            (Some(publisher), _) => Some(publisher.clone()),
+            (None, Some(author)) => Some(author.clone()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// `datePublished` from the page's JSON-LD article, as RFC 3339
+pub fn json_ld_published_date(document: &Html) -> Option<String> {
+    let (article, _) = json_ld_article(document)?;
+    text(article.get("datePublished")?).and_then(|date| normalize_date(&date))
+}
+
+/// Normalize the date formats pages use to RFC 3339
+pub fn normalize_date(date_str: &str) -> Option<String> {
+    let date_str = date_str.trim();
+
+    // Try parsing ISO 8601 / RFC 3339 format first — keep it as-is
+    if let Ok(dt) = date_str.parse::<DateTime<Utc>>() {
+        return Some(dt.to_rfc3339());
+    }
+
+    // JSON-LD often leaves out the timezone; assume UTC
+    if let Ok(naive) = NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S") {
+        return Some(naive.and_utc().to_rfc3339());
+    }
+
+    // If it's just a date without time, normalize to RFC 3339 at midnight UTC
+    if let Ok(naive_date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+        let datetime = naive_date.and_hms_opt(0, 0, 0)?;
+        let dt: DateTime<Utc> = DateTime::from_naive_utc_and_offset(datetime, Utc);
+        return Some(dt.to_rfc3339());
+    }
+
+    None
+}
+
+/// Every node from the page's JSON-LD blocks, with `@graph` flattened so
+/// `{"@id": ...}` references can be resolved
+struct JsonLd {
+    nodes: Vec<Value>,
+}
+
+impl JsonLd {
+    fn from_document(document: &Html) -> Self {
+        let selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+        let mut nodes = Vec::new();
+
+        for script in document.select(&selector) {
+            let json = script.text().collect::<String>();
+            // Some CMSes leave trailing semicolons or HTML comments around the JSON
+            let json = json
+                .trim()
+                .trim_start_matches("<!--")
+                .trim_end_matches("-->")
+                .trim()
+                .trim_end_matches(';');
+            if let Ok(value) = serde_json::from_str::<Value>(json) {
+                Self::collect(value, &mut nodes);
+            }
+        }
+
+        Self { nodes }
+    }
+
+    fn collect(value: Value, nodes: &mut Vec<Value>) {
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    Self::collect(item, nodes);
+                }
+            }
+            Value::Object(mut object) => {
+                if let Some(graph) = object.remove("@graph") {
+                    Self::collect(graph, nodes);
+                }
+                if !object.is_empty() {
+                    nodes.push(Value::Object(object));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn article(&self) -> Option<&Value> {
+        self.nodes.iter().find(|node| {
+            types(node)
+                .iter()
+                .any(|t| ARTICLE_TYPES.contains(&t.as_str()))
+        })
+    }
+
+    /// Follow an `{"@id": ...}` reference to the full node in the graph
+    fn resolve<'a>(&'a self, value: &'a Value) -> &'a Value {
+        let is_reference = value
+            .as_object()
+            .is_some_and(|o| o.contains_key("@id") && !o.contains_key("name"));
+        if !is_reference {
+            return value;
+        }
+
+        let id = &value["@id"];
+        self.nodes
+            .iter()
+            .find(|node| node.get("@id") == Some(id) && node.get("name").is_some())
+            .unwrap_or(value)
+    }
+
+    /// A name, or several joined with "and", from a Person/Organization value
+    fn names(&self, value: &Value) -> Option<String> {
+        let names: Vec<String> = match value {
+            Value::Array(items) => items.iter().filter_map(|v| self.name(v)).collect(),
+            other => self.name(other).into_iter().collect(),
+        };
+
+        match names.len() {
+            0 => None,
+            1 => names.into_iter().next(),
+            _ => {
+                let (last, rest) = names.split_last()?;
+                Some(format!("{} and {}", rest.join(", "), last))
+            }
+        }
+    }
+
+    fn name(&self, value: &Value) -> Option<String> {
+        match self.resolve(value) {
+            Value::String(s) => non_empty(s),
+            Value::Object(o) => o.get("name").and_then(text),
+            _ => None,
+        }
+    }
+}
+
+fn json_ld_article(document: &Html) -> Option<(Value, JsonLd)> {
+    let graph = JsonLd::from_document(document);
+    let article = graph.article()?.clone();
+    Some((article, graph))
+}
+
+fn types(node: &Value) -> Vec<String> {
+    match node.get("@type") {
+        Some(Value::String(t)) => vec![t.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .filter_map(|t| t.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A string, or the first string of an array
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => non_empty(s),
+        Value::Array(items) => items.iter().find_map(text),
+        _ => None,
+    }
+}
+
+/// An image or page URL given as a string, an ImageObject, or a list of either
+fn first_url(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => non_empty(s),
+        Value::Array(items) => items.iter().find_map(first_url),
+        Value::Object(o) => o.get("url").or_else(|| o.get("@id")).and_then(first_url),
+        _ => None,
+    }
+}
+
+/// Content of a `<meta>` tag by `property` (OpenGraph) or `name` (Twitter, plain)
+fn meta(document: &Html, key: &str) -> Option<String> {
+    let selector =
+        Selector::parse(&format!(r#"meta[property="{key}"], meta[name="{key}"]"#)).ok()?;
+    document
+        .select(&selector)
+        .find_map(|el| el.value().attr("content").and_then(non_empty))
+}
+
+fn link_href(document: &Html, rel: &str) -> Option<String> {
+    let selector = Selector::parse(&format!(r#"link[rel="{rel}"]"#)).ok()?;
+    document
+        .select(&selector)
+        .find_map(|el| el.value().attr("href").and_then(non_empty))
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!s.is_empty()).then_some(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_ld_graph_with_references() {
+        let html = r#"
+            <html><head>
+            <script type="application/ld+json">
+            {
+              "@context": "https://schema.org",
+              "@graph": [
+                {"@type": "Organization", "@id": "https://www.theverge.com/#org", "name": "The Verge"},
+                {"@type": "Person", "@id": "https://www.theverge.com/authors/jane", "name": "Jane Doe"},
+                {
+                  "@type": ["NewsArticle"],
+                  "headline": "Apple announces new chips",
+                  "author": {"@id": "https://www.theverge.com/authors/jane"},
+                  "publisher": {"@id": "https://www.theverge.com/#org"},
+                  "datePublished": "2026-02-03T09:15:00-05:00",
+                  "dateModified": "2026-02-03T11:00:00-05:00",
+                  "image": [{"@type": "ImageObject", "url": "https://cdn.example.com/lead.jpg"}],
+                  "articleSection": ["Tech", "Apple"]
+                }
+              ]
+            }
+            </script>
+            <meta property="og:site_name" content="Verge">
+            </head></html>
+        "#;
+
+        let document = Html::parse_document(html);
+        let metadata = ArticleMetadata::from_document(&document);
+
+        assert_eq!(metadata.author.as_deref(), Some("Jane Doe"));
+        assert_eq!(metadata.publisher.as_deref(), Some("The Verge"));
+        assert_eq!(
+            metadata.headline.as_deref(),
+            Some("Apple announces new chips")
+        );
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("https://cdn.example.com/lead.jpg")
+        );
+        assert_eq!(
+            metadata.modified_date.as_deref(),
+            Some("2026-02-03T16:00:00+00:00")
+        );
+        assert_eq!(metadata.section.as_deref(), Some("Tech"));
+        assert_eq!(
+            json_ld_published_date(&document).as_deref(),
+            Some("2026-02-03T14:15:00+00:00")
+        );
+    }
+
+    #[test]
+    fn test_json_ld_multiple_authors() {
+        let html = r#"
+            <script type="application/ld+json">
+            [{"@type": "WebSite", "name": "Ars"},
+             {"@type": "BlogPosting", "author": [{"name": "A. One"}, "B. Two", {"name": "C. Three"}]}]
+            </script>
+        "#;
+
+        let metadata = ArticleMetadata::from_html(html);
+        assert_eq!(
+            metadata.author.as_deref(),
+            Some("A. One, B. Two and C. Three")
+        );
+    }
+
+    #[test]
+    fn test_opengraph_and_twitter_fallbacks() {
+        let html = r#"
+            <html><head>
+            <link rel="canonical" href="https://example.com/story">
+            <meta property="og:site_name" content="Example News">
+            <meta property="og:title" content="OG headline">
+            <meta name="twitter:image" content="https://example.com/card.jpg">
+            <meta name="author" content="Sam Writer">
+            <meta property="article:section" content="Business">
+            <meta property="article:modified_time" content="2026-02-01">
+            </head></html>
+        "#;
+
+        let metadata = ArticleMetadata::from_html(html);
+
+        assert_eq!(metadata.publisher.as_deref(), Some("Example News"));
+        assert_eq!(metadata.headline.as_deref(), Some("OG headline"));
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("https://example.com/card.jpg")
+        );
+        assert_eq!(metadata.author.as_deref(), Some("Sam Writer"));
+        assert_eq!(
+            metadata.canonical_url.as_deref(),
+            Some("https://example.com/story")
+        );
+        assert_eq!(metadata.section.as_deref(), Some("Business"));
+        assert_eq!(
+            metadata.modified_date.as_deref(),
+            Some("2026-02-01T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn test_author_profile_urls_ignored() {
+        let html = r#"<meta property="article:author" content="https://facebook.com/someone">"#;
+        assert!(ArticleMetadata::from_html(html).author.is_none());
+    }
+
+    #[test]
+    fn test_malformed_json_ld_ignored() {
+        let html = r#"
+            <script type="application/ld+json">{"@type": "NewsArticle", "headline": </script>
+            <meta property="og:title" content="Fallback">
+        "#;
+        assert_eq!(
+            ArticleMetadata::from_html(html).headline.as_deref(),
+            Some("Fallback")
+        );
+    }
+
+    #[test]
+    fn test_attribution() {
+        let mut metadata = ArticleMetadata {
+            author: Some("Jane Doe".to_string()),
+            publisher: Some("The Verge".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            metadata.attribution().as_deref(),
+            Some("The Verge's Jane Doe")
+        );
+
+        metadata.publisher = Some("The New York Times".to_string());
+        assert_eq!(
+            metadata.attribution().as_deref(),
+            Some("The New York Times' Jane Doe")
+        );
+
+        metadata.publisher = None;
+        assert_eq!(metadata.attribution().as_deref(), Some("Jane Doe"));
+
+        assert!(ArticleMetadata::default().attribution().is_none());
+    }
+
+    #[test]
+    fn test_normalize_date_without_timezone() {
+        assert_eq!(
+            normalize_date("2026-02-01T08:30:00").as_deref(),
+            Some("2026-02-01T08:30:00+00:00")
+        );
+    }
+}
@@ -7,7 +7,14 @@ use crate::clustering::Topic;
 pub struct ShowInfo {
     pub name: String,
     pub slug: String,
+    /// Tag expression selecting the show's bookmarks, e.g. `#twit -#skip`
     pub tag: String,
+    /// Raindrop collection IDs to search; empty means all collections
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collections: Vec<i64>,
+    /// Domains whose links are never included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_domains: Vec<String>,
 }
 
 impl ShowInfo {
@@ -16,6 +23,8 @@ impl ShowInfo {
             name: name.into(),
             slug: slug.into(),
             tag: tag.into(),
+            collections: Vec::new(),
+            exclude_domains: Vec::new(),
         }
     }
 }
@@ -51,6 +60,8 @@ mod tests {
         assert_eq!(show.name, "This Week in Tech");
         assert_eq!(show.slug, "twit");
         assert_eq!(show.tag, "TWiT");
+        assert!(show.collections.is_empty());
+        assert!(show.exclude_domains.is_empty());
     }
 
     #[test]
@@ -90,7 +101,13 @@ mod tests {
             summary: Summary::Success {
                 points: vec!["Point 1".to_string()],
                 quote: None,
+                speaker: None,
             },
+            producer_note: None,
+            highlights: Vec::new(),
+            metadata: Default::default(),
+            extraction_error: None,
+            original_url: None,
         };
         let topics = vec![Topic {
             title: "News".to_string(),
@@ -0,0 +1,287 @@
+use anyhow::{Context, Result};
+use chrono::{FixedOffset, NaiveDate, TimeZone, Utc};
+use lopdf::Document;
+
+use crate::extractor::ArticleContent;
+use crate::metadata::ArticleMetadata;
+
+/// PDFs above this size are skipped rather than parsed in memory
+pub const MAX_PDF_BYTES: usize = 25 * 1024 * 1024;
+
+/// Whether a response is a PDF, going by its Content-Type, or by the URL when
+/// the server only says `application/octet-stream`
+pub fn is_pdf(content_type: Option<&str>, url: &str) -> bool {
+    let pdf_path = url::Url::parse(url)
+        .map(|u| u.path().to_lowercase().ends_with(".pdf"))
+        .unwrap_or(false);
+
+    match content_type {
+        Some(content_type) if content_type.contains("pdf") => true,
+        Some(content_type) if content_type.contains("octet-stream") => pdf_path,
+        Some(_) => false,
+        None => pdf_path,
+    }
+}
+
+/// Pull the text out of a PDF, with the title, author and dates from its
+/// document info dictionary standing in for the page metadata HTML would have
+pub fn extract(bytes: &[u8]) -> Result<ArticleContent> {
+    let document = Document::load_mem(bytes).context("Failed to parse PDF")?;
+    let text = pdf_extract::extract_text_from_mem(bytes).context("Failed to extract PDF text")?;
+
+    let info = document
+        .trailer
+        .get(b"Info")
+        .and_then(|info| document.dereference(info))
+        .and_then(|(_, info)| info.as_dict());
+    let field = |key: &[u8]| -> Option<String> {
+        let info = info.as_ref().ok()?;
+        let (_, value) = document.dereference(info.get(key).ok()?).ok()?;
+        let value = lopdf::decode_text_string(value).ok()?;
+        let value = value.trim().trim_matches('\0').trim();
+        (!value.is_empty()).then(|| value.to_string())
+    };
+
+    let metadata = ArticleMetadata {
+        headline: field(b"Title").filter(|title| !is_file_name(title)),
+        author: field(b"Author"),
+        modified_date: field(b"ModDate").and_then(|d| parse_pdf_date(&d)),
+        ..Default::default()
+    };
+
+    Ok(ArticleContent {
+        text: clean_text(&text),
+        published_date: field(b"CreationDate").and_then(|d| parse_pdf_date(&d)),
+        metadata,
+    })
+}
+
+/// Word processors often leave the file name as the title, e.g.
+/// "Microsoft Word - Complaint FINAL.docx"
+fn is_file_name(title: &str) -> bool {
+    let lower = title.to_lowercase();
+    lower.starts_with("microsoft word - ")
+        || [".doc", ".docx", ".pdf", ".tex", ".dvi", ".indd", ".rtf"]
+            .iter()
+            .any(|ext| lower.ends_with(ext))
+}
+
+/// Trim the ragged spacing PDF text comes out with: trailing spaces, form
+/// feeds between pages and long runs of blank lines
+fn clean_text(text: &str) -> String {
+    let mut cleaned = String::new();
+    let mut blank_lines = 0;
+
+    for line in text.replace('\u{c}', "\n").lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+        if !cleaned.is_empty() {
+            cleaned.push_str(if blank_lines > 0 { "\n\n" } else { "\n" });
+        }
+        cleaned.push_str(line);
+        blank_lines = 0;
+    }
+
+    cleaned
+}
+
+/// Parse a PDF date (`D:YYYYMMDDHHmmSS+HH'mm'`, everything after the year
+/// optional) into RFC 3339 UTC, matching dates from HTML pages
+pub fn parse_pdf_date(value: &str) -> Option<String> {
+    let value = value.trim();
+    let value = value.strip_prefix("D:").unwrap_or(value);
+    let digits_len = value.chars().take_while(|c| c.is_ascii_digit()).count();
+    let (digits, zone) = value.split_at(digits_len);
+    if digits.len() < 4 {
+        return None;
+    }
+
+    let part = |start: usize, len: usize, default: u32| -> Option<u32> {
+        match digits.get(start..start + len) {
+            Some(s) => s.parse().ok(),
+            None => Some(default),
+        }
+    };
+    let year: i32 = digits[..4].parse().ok()?;
+    let date = NaiveDate::from_ymd_opt(year, part(4, 2, 1)?, part(6, 2, 1)?)?;
+    let datetime = date.and_hms_opt(part(8, 2, 0)?, part(10, 2, 0)?, part(12, 2, 0)?)?;
+
+    // Zone is Z, or +HH'mm' / -HH'mm' with the minutes and quotes optional;
+    // a missing zone is taken as UTC
+    let offset = match zone.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let numbers: String = zone[1..].chars().filter(|c| c.is_ascii_digit()).collect();
+            let hours: i32 = numbers.get(..2)?.parse().ok()?;
+            let minutes: i32 = numbers.get(2..4).unwrap_or("0").parse().ok()?;
+            let seconds = hours * 3600 + minutes * 60;
+            FixedOffset::east_opt(if sign == '-' { -seconds } else { seconds })?
+        }
+        _ => FixedOffset::east_opt(0)?,
+    };
+
+    let local = offset.from_local_datetime(&datetime).single()?;
+    Some(local.with_timezone(&Utc).to_rfc3339())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Object, Stream};
+
+    /// A one-page PDF with `lines` of Helvetica text and the given info entries
+    pub(crate) fn build_pdf(lines: &[&str], info: &[(&str, &str)]) -> Vec<u8> {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+        });
+        let resources_id = doc.add_object(dictionary! {
+            "Font" => dictionary! { "F1" => font_id },
+        });
+
+        let mut operations = vec![
+            Operation::new("BT", vec![]),
+            Operation::new("Tf", vec!["F1".into(), 12.into()]),
+            Operation::new("Td", vec![72.into(), 720.into()]),
+            Operation::new("TL", vec![16.into()]),
+        ];
+        for line in lines {
+            operations.push(Operation::new("Tj", vec![Object::string_literal(*line)]));
+            operations.push(Operation::new("T*", vec![]));
+        }
+        operations.push(Operation::new("ET", vec![]));
+        let content = Content { operations };
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content.encode().unwrap()));
+
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "Contents" => content_id,
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![page_id.into()],
+                "Count" => 1,
+                "Resources" => resources_id,
+                "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+
+        if !info.is_empty() {
+            let mut dict = lopdf::Dictionary::new();
+            for (key, value) in info {
+                dict.set(*key, lopdf::text_string(value));
+            }
+            let info_id = doc.add_object(dict);
+            doc.trailer.set("Info", info_id);
+        }
+
+        let mut bytes = Vec::new();
+        doc.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_is_pdf() {
+        assert!(is_pdf(Some("application/pdf"), "https://example.com/doc"));
+        assert!(is_pdf(
+            Some("application/octet-stream"),
+            "https://courtlistener.com/filing.PDF"
+        ));
+        assert!(is_pdf(None, "https://arxiv.org/pdf/2601.00001.pdf"));
+        assert!(!is_pdf(
+            Some("application/octet-stream"),
+            "https://example.com/download"
+        ));
+        assert!(!is_pdf(Some("text/html"), "https://example.com/paper.pdf"));
+    }
+
+    #[test]
+    fn test_extract_text_and_info() {
+        let bytes = build_pdf(
+            &[
+                "UNITED STATES DISTRICT COURT",
+                "Plaintiffs allege the model was trained on their books.",
+            ],
+            &[
+                ("Title", "Complaint for Copyright Infringement"),
+                ("Author", "Jane Counsel"),
+                ("CreationDate", "D:20260201093000-08'00'"),
+                ("ModDate", "D:20260202"),
+            ],
+        );
+
+        let content = extract(&bytes).unwrap();
+
+        assert!(content.text.contains("UNITED STATES DISTRICT COURT"));
+        assert!(content.text.contains("trained on their books"));
+        assert_eq!(
+            content.published_date.as_deref(),
+            Some("2026-02-01T17:30:00+00:00")
+        );
+        assert_eq!(
+            content.metadata.headline.as_deref(),
+            Some("Complaint for Copyright Infringement")
+        );
+        assert_eq!(content.metadata.author.as_deref(), Some("Jane Counsel"));
+        assert_eq!(
+            content.metadata.modified_date.as_deref(),
+            Some("2026-02-02T00:00:00+00:00")
+        );
+    }
+
+    #[test]
+    fn test_file_name_titles_ignored() {
+        let bytes = build_pdf(
+            &["Quarterly results"],
+            &[("Title", "Microsoft Word - Q4 release FINAL.docx")],
+        );
+        let content = extract(&bytes).unwrap();
+        assert!(content.metadata.headline.is_none());
+        assert!(content.published_date.is_none());
+    }
+
+    #[test]
+    fn test_extract_rejects_garbage() {
+        assert!(extract(b"<html>not a pdf</html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_pdf_date() {
+        assert_eq!(
+            parse_pdf_date("D:20260201093000Z").as_deref(),
+            Some("2026-02-01T09:30:00+00:00")
+        );
+        assert_eq!(
+            parse_pdf_date("D:20260201093000+05'30'").as_deref(),
+            Some("2026-02-01T04:00:00+00:00")
+        );
+        assert_eq!(
+            parse_pdf_date("2026").as_deref(),
+            Some("2026-01-01T00:00:00+00:00")
+        );
+        assert_eq!(parse_pdf_date("D:2026133"), None);
+        assert_eq!(parse_pdf_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_clean_text() {
+        assert_eq!(
+            clean_text("  Title  \n\n\n\nFirst line   \nSecond line\u{c}\n\nPage two"),
+            "  Title\n\nFirst line\nSecond line\n\nPage two"
+        );
+    }
+}
@@ -0,0 +1,584 @@
+use chrono::{DateTime, Utc};
+use reqwest::{Client, RequestBuilder, Response};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OnceCell, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+use crate::config::PolitenessSettings;
+
+/// Limits for one host after domain overrides are applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct HostRules {
+    pub concurrency: usize,
+    pub delay: Duration,
+    pub respect_robots: bool,
+}
+
+struct HostState {
+    permits: Arc<Semaphore>,
+    /// Earliest time the next request to this host may start
+    next_request: tokio::sync::Mutex<Instant>,
+    delay: Duration,
+}
+
+/// Spaces out requests per host and caps how many run at once, overall and per host
+pub struct Politeness {
+    settings: PolitenessSettings,
+    global: Arc<Semaphore>,
+    hosts: Mutex<HashMap<String, Arc<HostState>>>,
+    robots: Mutex<HashMap<String, Arc<OnceCell<Arc<RobotsRules>>>>>,
+}
+
+/// Held while a request is in flight; dropping it frees the host and global slots
+pub struct Permit {
+    _host: OwnedSemaphorePermit,
+    _global: OwnedSemaphorePermit,
+}
+
+impl Politeness {
+    pub fn new(settings: PolitenessSettings) -> Self {
+        Self {
+            global: Arc::new(Semaphore::new(settings.max_concurrent.max(1))),
+            settings,
+            hosts: Mutex::new(HashMap::new()),
+            robots: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The host a URL is scheduled under; `www.` shares a slot with the bare domain
+    pub fn host_key(url: &str) -> Option<String> {
+        let host = url::Url::parse(url).ok()?.host_str()?.to_lowercase();
+        Some(host.strip_prefix("www.").unwrap_or(&host).to_string())
+    }
+
+    pub fn rules_for(&self, host: &str) -> HostRules {
+        let mut rules = HostRules {
+            concurrency: self.settings.host_concurrency,
+            delay: Duration::from_millis(self.settings.host_delay_ms),
+            respect_robots: self.settings.respect_robots,
+        };
+
+        // The most specific matching domain wins
+        let domain = self
+            .settings
+            .domains
+            .iter()
+            .filter(|(domain, _)| {
+                let domain = domain.trim_start_matches('.').to_lowercase();
+                host == domain || host.ends_with(&format!(".{}", domain))
+            })
+            .max_by_key(|(domain, _)| domain.len());
+
+        if let Some((_, overrides)) = domain {
+            if let Some(concurrency) = overrides.concurrency {
+                rules.concurrency = concurrency;
+            }
+            if let Some(delay_ms) = overrides.delay_ms {
+                rules.delay = Duration::from_millis(delay_ms);
+            }
+            if let Some(respect_robots) = overrides.respect_robots {
+                rules.respect_robots = respect_robots;
+            }
+        }
+
+        rules.concurrency = rules.concurrency.max(1);
+        rules
+    }
+
+    fn host_state(&self, host: &str) -> Arc<HostState> {
+        let mut hosts = self.hosts.lock().unwrap();
+        hosts
+            .entry(host.to_string())
+            .or_insert_with(|| {
+                let rules = self.rules_for(host);
+                Arc::new(HostState {
+                    permits: Arc::new(Semaphore::new(rules.concurrency)),
+                    next_request: tokio::sync::Mutex::new(Instant::now()),
+                    delay: rules.delay,
+                })
+            })
+            .clone()
+    }
+
+    /// Wait for a free slot on `host` and its turn after the previous request
+    pub async fn acquire(&self, host: &str) -> Permit {
+        let state = self.host_state(host);
+        let host_permit = state
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("host semaphore is never closed");
+
+        // Wait for this host's turn before taking a global slot, so requests
+        // queued behind a slow host don't block other hosts. The turn is only
+        // claimed once the global slot is held; if another request to the host
+        // went first in the meantime, give the slot back and wait again.
+        loop {
+            let next_request = *state.next_request.lock().await;
+            tokio::time::sleep_until(next_request).await;
+
+            let global_permit = self
+                .global
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("global semaphore is never closed");
+
+            let mut next_request = state.next_request.lock().await;
+            let now = Instant::now();
+            if now >= *next_request {
+                *next_request = now + state.delay;
+                return Permit {
+                    _host: host_permit,
+                    _global: global_permit,
+                };
+            }
+        }
+    }
+
+    /// Hold off on `host` after it asked us to (429/503 with `Retry-After`)
+    pub async fn back_off(&self, host: &str, wait: Duration) {
+        let wait = wait.min(Duration::from_secs(self.settings.max_retry_after_secs));
+        let state = self.host_state(host);
+        let mut next_request = state.next_request.lock().await;
+        *next_request = (*next_request).max(Instant::now() + wait);
+    }
+
+    /// Back off `host` for as long as a 429 or 503 response's `Retry-After` asks
+    pub async fn honor_retry_after(&self, host: &str, response: &Response) {
+        if !matches!(response.status().as_u16(), 429 | 503) {
+            return;
+        }
+        let wait = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| parse_retry_after(v, Utc::now()));
+        if let Some(wait) = wait {
+            self.back_off(host, wait).await;
+        }
+    }
+
+    /// Send `request` once its host has a free slot and its turn, for API
+    /// calls that don't go through the article fetch path
+    pub async fn send(&self, request: RequestBuilder) -> reqwest::Result<Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let host = Self::host_key(request.url().as_str()).unwrap_or_default();
+
+        let _permit = self.acquire(&host).await;
+        let response = client.execute(request).await?;
+        self.honor_retry_after(&host, &response).await;
+        Ok(response)
+    }
+
+    /// Whether robots.txt lets us fetch `url`. Hosts with robots checks turned
+    /// off, and robots.txt files that can't be fetched, allow everything.
+    pub async fn allowed_by_robots(&self, client: &Client, url: &str) -> bool {
+        let Ok(parsed) = url::Url::parse(url) else {
+            return true;
+        };
+        let Some(host) = Self::host_key(url) else {
+            return true;
+        };
+        if !self.rules_for(&host).respect_robots {
+            return true;
+        }
+
+        let origin = parsed.origin().ascii_serialization();
+        let cell = self
+            .robots
+            .lock()
+            .unwrap()
+            .entry(origin.clone())
+            .or_default()
+            .clone();
+
+        let rules = cell
+            .get_or_init(|| async {
+                let text = Self::fetch_robots(client, &origin).await;
+                Arc::new(RobotsRules::parse(
+                    text.as_deref().unwrap_or(""),
+                    &self.settings.robots_user_agent,
+                ))
+            })
+            .await;
+
+        let mut path = parsed.path().to_string();
+        if let Some(query) = parsed.query() {
+            path.push('?');
+            path.push_str(query);
+        }
+        rules.allows(&path)
+    }
+
+    async fn fetch_robots(client: &Client, origin: &str) -> Option<String> {
+        let response = client
+            .get(format!("{}/robots.txt", origin))
+            .timeout(Duration::from_secs(10))
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+        response.text().await.ok()
+    }
+}
+
+/// Parse a `Retry-After` header: delay in seconds or an HTTP date
+pub fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let wait = date.with_timezone(&Utc) - now;
+    Some(wait.to_std().unwrap_or(Duration::ZERO))
+}
+
+/// The Allow/Disallow rules from robots.txt that apply to our user agent
+#[derive(Debug, Default, PartialEq)]
+pub struct RobotsRules {
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Use the group naming `agent` if there is one, otherwise the `*` group
+    pub fn parse(text: &str, agent: &str) -> Self {
+        let agent = agent.to_lowercase();
+        let mut specific: Vec<(bool, String)> = Vec::new();
+        let mut wildcard: Vec<(bool, String)> = Vec::new();
+        let mut found_specific = false;
+
+        // Agents named by the current group, and whether its rules have started
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = field.trim().to_lowercase();
+            let value = value.trim();
+
+            match field.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow allows everything
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (field == "allow", value.to_string());
+                    if group_agents
+                        .iter()
+                        .any(|a| a != "*" && agent.contains(a.as_str()))
+                    {
+                        found_specific = true;
+                        specific.push(rule.clone());
+                    }
+                    if group_agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            rules: if found_specific { specific } else { wildcard },
+        }
+    }
+
+    /// The longest matching rule decides; Allow wins ties
+    pub fn allows(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, pattern)| Self::matches(pattern, path))
+            .max_by_key(|(allow, pattern)| (pattern.len(), *allow))
+            .is_none_or(|(allow, _)| *allow)
+    }
+
+    /// robots.txt patterns are path prefixes with `*` wildcards and an optional `$` anchor
+    fn matches(pattern: &str, path: &str) -> bool {
+        let (pattern, anchored) = match pattern.strip_suffix('$') {
+            Some(p) => (p, true),
+            None => (pattern, false),
+        };
+
+        let mut parts = pattern.split('*');
+        let first = parts.next().unwrap_or("");
+        let Some(mut rest) = path.strip_prefix(first) else {
+            return false;
+        };
+
+        let parts: Vec<&str> = parts.collect();
+        for (i, part) in parts.iter().enumerate() {
+            let is_last = i == parts.len() - 1;
+            if is_last && anchored {
+                return rest.ends_with(part);
+            }
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+
+        !anchored || rest.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DomainPoliteness;
+    use chrono::TimeZone;
+
+    fn settings() -> PolitenessSettings {
+        let mut settings = PolitenessSettings {
+            host_delay_ms: 0,
+            ..Default::default()
+        };
+        settings.domains.insert(
+            "theverge.com".to_string(),
+            DomainPoliteness {
+                concurrency: Some(1),
+                delay_ms: Some(150),
+                respect_robots: Some(true),
+            },
+        );
+        settings
+    }
+
+    #[test]
+    fn test_host_key() {
+        assert_eq!(
+            Politeness::host_key("https://www.TheVerge.com/a").as_deref(),
+            Some("theverge.com")
+        );
+        assert_eq!(Politeness::host_key("not a url"), None);
+    }
+
+    #[test]
+    fn test_rules_for_domain_overrides() {
+        let politeness = Politeness::new(settings());
+
+        let verge = politeness.rules_for("theverge.com");
+        assert_eq!(verge.concurrency, 1);
+        assert_eq!(verge.delay, Duration::from_millis(150));
+        assert!(verge.respect_robots);
+
+        // Subdomains inherit the override; other hosts get the defaults
+        assert_eq!(politeness.rules_for("live.theverge.com").concurrency, 1);
+        let other = politeness.rules_for("notheverge.com");
+        assert_eq!(other.concurrency, 2);
+        assert!(!other.respect_robots);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_spaces_requests_to_the_same_host() {
+        let politeness = Politeness::new(settings());
+        let start = Instant::now();
+
+        drop(politeness.acquire("theverge.com").await);
+        drop(politeness.acquire("theverge.com").await);
+        drop(politeness.acquire("theverge.com").await);
+
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_acquire_spaces_requests_queued_for_a_global_slot() {
+        let politeness = Politeness::new(PolitenessSettings {
+            max_concurrent: 1,
+            host_delay_ms: 100,
+            ..Default::default()
+        });
+        let request = || async {
+            drop(politeness.acquire("example.com").await);
+            Instant::now()
+        };
+
+        // Both example.com requests queue up while another host holds the
+        // only global slot
+        let blocker = politeness.acquire("other.com").await;
+        let (first, second, _) = tokio::join!(request(), request(), async {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            drop(blocker);
+        });
+
+        let gap = first.max(second) - first.min(second);
+        assert!(gap >= Duration::from_millis(100), "gap was {:?}", gap);
+    }
+
+    #[tokio::test]
+    async fn test_acquire_caps_concurrency_per_host() {
+        let politeness = Politeness::new(settings());
+
+        let held = politeness.acquire("theverge.com").await;
+        let waiting = tokio::time::timeout(
+            Duration::from_millis(300),
+            politeness.acquire("theverge.com"),
+        )
+        .await;
+        assert!(waiting.is_err(), "second Verge request should wait");
+
+        // Other hosts aren't held up
+        let other = tokio::time::timeout(
+            Duration::from_millis(100),
+            politeness.acquire("example.com"),
+        )
+        .await;
+        assert!(other.is_ok());
+        drop(held);
+    }
+
+    #[tokio::test]
+    async fn test_back_off_delays_next_request() {
+        let politeness = Politeness::new(PolitenessSettings {
+            host_delay_ms: 0,
+            ..Default::default()
+        });
+        let start = Instant::now();
+
+        politeness
+            .back_off("example.com", Duration::from_millis(200))
+            .await;
+        drop(politeness.acquire("example.com").await);
+
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_send_honors_retry_after() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+            .mount(&server)
+            .await;
+        let politeness = Politeness::new(PolitenessSettings {
+            host_delay_ms: 0,
+            ..Default::default()
+        });
+        let client = Client::new();
+
+        let response = politeness.send(client.get(server.uri())).await.unwrap();
+        assert_eq!(response.status().as_u16(), 429);
+
+        let start = Instant::now();
+        politeness.send(client.get(server.uri())).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(900));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 01 Feb 2026 12:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Sun, 01 Feb 2026 11:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[test]
+    fn test_robots_rules_prefer_specific_group() {
+        let robots = "
+            User-agent: *
+            Disallow: /
+
+            User-agent: collect-stories
+            User-agent: other-bot
+            Disallow: /private/
+            Allow: /private/press/
+        ";
+        let rules = RobotsRules::parse(robots, "collect-stories");
+
+        assert!(rules.allows("/news/story"));
+        assert!(!rules.allows("/private/draft"));
+        assert!(rules.allows("/private/press/release"));
+
+        let other = RobotsRules::parse(robots, "someone-else");
+        assert!(!other.allows("/news/story"));
+    }
+
+    #[test]
+    fn test_robots_rules_wildcards_and_anchors() {
+        let robots = "
+            User-agent: *
+            Disallow: /*.pdf$
+            Disallow: /search?*q=
+            Disallow:
+        ";
+        let rules = RobotsRules::parse(robots, "collect-stories");
+
+        assert!(!rules.allows("/files/report.pdf"));
+        assert!(rules.allows("/files/report.pdf.html"));
+        assert!(!rules.allows("/search?page=2&q=apple"));
+        assert!(rules.allows("/search"));
+        assert!(RobotsRules::parse("", "collect-stories").allows("/anything"));
+    }
+
+    #[tokio::test]
+    async fn test_allowed_by_robots_fetches_once_per_origin() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/robots.txt"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string("User-agent: *\nDisallow: /private/\n"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let politeness = Politeness::new(PolitenessSettings {
+            respect_robots: true,
+            ..Default::default()
+        });
+        let client = Client::new();
+
+        let private = format!("{}/private/story", server.uri());
+        let public = format!("{}/news/story", server.uri());
+        assert!(!politeness.allowed_by_robots(&client, &private).await);
+        assert!(politeness.allowed_by_robots(&client, &public).await);
+    }
+
+    #[tokio::test]
+    async fn test_missing_robots_allows_everything() {
+        let server = wiremock::MockServer::start().await;
+
+        let politeness = Politeness::new(PolitenessSettings {
+            respect_robots: true,
+            ..Default::default()
+        });
+
+        let url = format!("{}/private/story", server.uri());
+        assert!(politeness.allowed_by_robots(&Client::new(), &url).await);
+    }
+}
@@ -0,0 +1,370 @@
+use anyhow::{Context, Result};
+
+use crate::bookmarks::tag_matches;
+use crate::models::ShowInfo;
+
+/// A boolean expression over bookmark tags, e.g. `#twit -#skip` or `(#im OR #ai) -#maybe`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagExpr {
+    Tag(String),
+    Not(Box<TagExpr>),
+    And(Vec<TagExpr>),
+    Or(Vec<TagExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Tag(String),
+    Not,
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+impl TagExpr {
+    /// Parse an expression. Adjacent terms are ANDed; `OR`/`|` bind looser than AND,
+    /// and `-`, `!` or `NOT` negate the following term.
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = Self::tokenize(input)?;
+        if tokens.is_empty() {
+            anyhow::bail!("Empty tag expression");
+        }
+
+        let mut pos = 0;
+        let expr = Self::parse_or(&tokens, &mut pos)?;
+        if pos != tokens.len() {
+            anyhow::bail!("Unexpected {:?} in tag expression '{}'", tokens[pos], input);
+        }
+        Ok(expr)
+    }
+
+    fn tokenize(input: &str) -> Result<Vec<Token>> {
+        let mut tokens = Vec::new();
+        let mut chars = input.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            match c {
+                c if c.is_whitespace() => {
+                    chars.next();
+                }
+                '(' => {
+                    chars.next();
+                    tokens.push(Token::Open);
+                }
+                ')' => {
+                    chars.next();
+                    tokens.push(Token::Close);
+                }
+                '|' => {
+                    chars.next();
+                    tokens.push(Token::Or);
+                }
+                '&' => {
+                    chars.next();
+                    tokens.push(Token::And);
+                }
+                '-' | '!' => {
+                    chars.next();
+                    tokens.push(Token::Not);
+                }
+                _ => {
+                    let hashed = c == '#';
+                    if hashed {
+                        chars.next();
+                    }
+
+                    let mut word = String::new();
+                    if chars.peek() == Some(&'"') {
+                        // Quoted tag: #"two words"
+                        chars.next();
+                        let mut closed = false;
+                        for c in chars.by_ref() {
+                            if c == '"' {
+                                closed = true;
+                                break;
+                            }
+                            word.push(c);
+                        }
+                        if !closed {
+                            anyhow::bail!("Unterminated quote in tag expression '{}'", input);
+                        }
+                    } else {
+                        while let Some(&c) = chars.peek() {
+                            if c.is_whitespace() || matches!(c, '(' | ')' | '|' | '&') {
+                                break;
+                            }
+                            word.push(c);
+                            chars.next();
+                        }
+                    }
+
+                    if word.is_empty() {
+                        anyhow::bail!("Missing tag name in tag expression '{}'", input);
+                    }
+
+                    tokens.push(match (hashed, word.as_str()) {
+                        (false, "OR") => Token::Or,
+                        (false, "AND") => Token::And,
+                        (false, "NOT") => Token::Not,
+                        _ => Token::Tag(word),
+                    });
+                }
+            }
+        }
+
+        Ok(tokens)
+    }
+
+    fn parse_or(tokens: &[Token], pos: &mut usize) -> Result<Self> {
+        let mut terms = vec![Self::parse_and(tokens, pos)?];
+        while tokens.get(*pos) == Some(&Token::Or) {
+            *pos += 1;
+            terms.push(Self::parse_and(tokens, pos)?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            TagExpr::Or(terms)
+        })
+    }
+
+    fn parse_and(tokens: &[Token], pos: &mut usize) -> Result<Self> {
+        let mut terms = vec![Self::parse_unary(tokens, pos)?];
+        loop {
+            match tokens.get(*pos) {
+                Some(Token::And) => {
+                    *pos += 1;
+                    terms.push(Self::parse_unary(tokens, pos)?);
+                }
+                Some(Token::Tag(_)) | Some(Token::Not) | Some(Token::Open) => {
+                    terms.push(Self::parse_unary(tokens, pos)?);
+                }
+                _ => break,
+            }
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            TagExpr::And(terms)
+        })
+    }
+
+    fn parse_unary(tokens: &[Token], pos: &mut usize) -> Result<Self> {
+        match tokens.get(*pos) {
+            Some(Token::Not) => {
+                *pos += 1;
+                Ok(TagExpr::Not(Box::new(Self::parse_unary(tokens, pos)?)))
+            }
+            Some(Token::Open) => {
+                *pos += 1;
+                let expr = Self::parse_or(tokens, pos)?;
+                if tokens.get(*pos) != Some(&Token::Close) {
+                    anyhow::bail!("Missing closing parenthesis in tag expression");
+                }
+                *pos += 1;
+                Ok(expr)
+            }
+            Some(Token::Tag(tag)) => {
+                *pos += 1;
+                Ok(TagExpr::Tag(tag.clone()))
+            }
+            Some(token) => anyhow::bail!("Unexpected {:?} in tag expression", token),
+            None => anyhow::bail!("Tag expression ends unexpectedly"),
+        }
+    }
+
+    /// Whether a bookmark with `tags` satisfies the expression (case-insensitive)
+    pub fn matches(&self, tags: &[String]) -> bool {
+        match self {
+            TagExpr::Tag(wanted) => tags.iter().any(|t| tag_matches(t, wanted)),
+            TagExpr::Not(inner) => !inner.matches(tags),
+            TagExpr::And(terms) => terms.iter().all(|t| t.matches(tags)),
+            TagExpr::Or(terms) => terms.iter().any(|t| t.matches(tags)),
+        }
+    }
+
+    /// Raindrop search syntax for the expression, if it can be expressed exactly.
+    ///
+    /// Raindrop search ANDs its terms and supports `-#tag` exclusions, so only
+    /// conjunctions of (possibly negated) tags compile. Anything with OR or
+    /// nesting returns `None` and has to be filtered client-side.
+    pub fn to_raindrop_search(&self) -> Option<String> {
+        fn literal(expr: &TagExpr) -> Option<String> {
+            match expr {
+                TagExpr::Tag(tag) if tag.contains(char::is_whitespace) => {
+                    Some(format!("#\"{}\"", tag))
+                }
+                TagExpr::Tag(tag) => Some(format!("#{}", tag)),
+                TagExpr::Not(inner) => match inner.as_ref() {
+                    TagExpr::Tag(_) => literal(inner).map(|l| format!("-{}", l)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+
+        match self {
+            TagExpr::And(terms) => terms
+                .iter()
+                .map(literal)
+                .collect::<Option<Vec<_>>>()
+                .map(|l| l.join(" ")),
+            _ => literal(self),
+        }
+    }
+}
+
+/// Which bookmarks belong in a show's briefing
+#[derive(Debug, Clone)]
+pub struct BookmarkQuery {
+    pub tags: TagExpr,
+    /// Raindrop collection IDs to search; empty means all collections
+    pub collections: Vec<i64>,
+    /// Domains whose links are never included (subdomains match too)
+    pub exclude_domains: Vec<String>,
+}
+
+impl BookmarkQuery {
+    pub fn from_show(show: &ShowInfo) -> Result<Self> {
+        let tags = TagExpr::parse(&show.tag)
+            .with_context(|| format!("Invalid tag query for {}: '{}'", show.name, show.tag))?;
+
+        Ok(Self {
+            tags,
+            collections: show.collections.clone(),
+            exclude_domains: show
+                .exclude_domains
+                .iter()
+                .map(|d| d.trim().trim_start_matches('.').to_lowercase())
+                .collect(),
+        })
+    }
+
+    pub fn matches_tags(&self, tags: &[String]) -> bool {
+        self.tags.matches(tags)
+    }
+
+    pub fn allows_link(&self, link: &str) -> bool {
+        let Some(host) = url::Url::parse(link)
+            .ok()
+            .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        else {
+            return true;
+        };
+
+        !self
+            .exclude_domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+    }
+
+    pub fn allows_collection(&self, collection: Option<i64>) -> bool {
+        self.collections.is_empty() || collection.is_some_and(|id| self.collections.contains(&id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(list: &[&str]) -> Vec<String> {
+        list.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_single_tag() {
+        assert_eq!(
+            TagExpr::parse("#twit").unwrap(),
+            TagExpr::Tag("twit".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_and_with_exclusion() {
+        assert_eq!(
+            TagExpr::parse("#twit -#skip").unwrap(),
+            TagExpr::And(vec![
+                TagExpr::Tag("twit".to_string()),
+                TagExpr::Not(Box::new(TagExpr::Tag("skip".to_string()))),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_or_binds_looser_than_and() {
+        let expr = TagExpr::parse("#im #ai OR #followup").unwrap();
+        assert!(expr.matches(&tags(&["im", "ai"])));
+        assert!(expr.matches(&tags(&["followup"])));
+        assert!(!expr.matches(&tags(&["im"])));
+    }
+
+    #[test]
+    fn test_parse_parentheses_and_keywords() {
+        let expr = TagExpr::parse("(#im | #ai) AND NOT #maybe").unwrap();
+        assert!(expr.matches(&tags(&["AI"])));
+        assert!(!expr.matches(&tags(&["ai", "Maybe"])));
+        assert!(!expr.matches(&tags(&["twit"])));
+    }
+
+    #[test]
+    fn test_parse_quoted_tag() {
+        let expr = TagExpr::parse("#\"leo picks\"").unwrap();
+        assert!(expr.matches(&tags(&["Leo Picks"])));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(TagExpr::parse("").is_err());
+        assert!(TagExpr::parse("(#twit").is_err());
+        assert!(TagExpr::parse("#twit OR").is_err());
+        assert!(TagExpr::parse("#\"open").is_err());
+        assert!(TagExpr::parse("#twit )").is_err());
+    }
+
+    #[test]
+    fn test_to_raindrop_search() {
+        let compile = |q: &str| TagExpr::parse(q).unwrap().to_raindrop_search();
+
+        assert_eq!(compile("#twit").as_deref(), Some("#twit"));
+        assert_eq!(compile("#twit -#skip").as_deref(), Some("#twit -#skip"));
+        assert_eq!(
+            compile("#im #\"leo picks\"").as_deref(),
+            Some("#im #\"leo picks\"")
+        );
+        assert_eq!(compile("#im OR #ai"), None);
+        assert_eq!(compile("#twit -(#a #b)"), None);
+    }
+
+    #[test]
+    fn test_bookmark_query_from_show() {
+        let mut show = ShowInfo::new("This Week in Tech", "twit", "#twit -#skip");
+        show.collections = vec![42];
+        show.exclude_domains = vec![".YouTube.com".to_string()];
+
+        let query = BookmarkQuery::from_show(&show).unwrap();
+
+        assert!(query.matches_tags(&tags(&["TWiT"])));
+        assert!(!query.matches_tags(&tags(&["twit", "skip"])));
+        assert!(!query.allows_link("https://www.youtube.com/watch?v=1"));
+        assert!(query.allows_link("https://notyoutube.com/story"));
+        assert!(query.allows_collection(Some(42)));
+        assert!(!query.allows_collection(Some(7)));
+        assert!(!query.allows_collection(None));
+    }
+
+    #[test]
+    fn test_bookmark_query_without_collections_allows_all() {
+        let show = ShowInfo::new("MacBreak Weekly", "mbw", "#mbw");
+        let query = BookmarkQuery::from_show(&show).unwrap();
+
+        assert!(query.allows_collection(None));
+        assert!(query.allows_link("not a url"));
+    }
+
+    #[test]
+    fn test_bookmark_query_invalid_expression() {
+        let show = ShowInfo::new("MacBreak Weekly", "mbw", "#mbw (");
+        assert!(BookmarkQuery::from_show(&show).is_err());
+    }
+}
@@ -1,10 +1,17 @@
 use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
+use crate::bookmarks::{format_highlight, Bookmark, BookmarkSource};
+use crate::query::BookmarkQuery;
+use crate::raindrop_cache::RaindropCache;
+
+/// A bookmark as returned by the Raindrop.io API
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Bookmark {
+pub struct Raindrop {
     #[serde(rename = "_id")]
     pub id: i64,
     pub title: String,
@@ -13,19 +20,103 @@ pub struct Bookmark {
     pub note: Option<String>,
     pub tags: Vec<String>,
     pub created: String,
+    #[serde(rename = "lastUpdate", default)]
+    pub last_update: Option<String>,
+    #[serde(default)]
+    pub highlights: Vec<RaindropHighlight>,
+    #[serde(default)]
+    pub collection: Option<CollectionRef>,
+}
+
+impl Raindrop {
+    pub fn collection_id(&self) -> Option<i64> {
+        self.collection.as_ref().map(|c| c.id)
+    }
+}
+
+/// The collection a raindrop is filed in
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectionRef {
+    #[serde(rename = "$id")]
+    pub id: i64,
+}
+
+/// A passage highlighted in the Raindrop reader
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RaindropHighlight {
+    pub text: String,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+impl From<Raindrop> for Bookmark {
+    fn from(raindrop: Raindrop) -> Self {
+        Bookmark {
+            id: raindrop.id.to_string(),
+            source: "raindrop".to_string(),
+            title: raindrop.title,
+            link: raindrop.link,
+            excerpt: raindrop.excerpt,
+            // Leave out any summary written back by an earlier --write-summaries run
+            note: raindrop
+                .note
+                .map(|note| producer_note(&note).to_string())
+                .filter(|note| !note.is_empty()),
+            highlights: raindrop
+                .highlights
+                .into_iter()
+                .filter_map(|h| format_highlight(h.text, h.note))
+                .collect(),
+            tags: raindrop.tags,
+            created: raindrop.created,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct RaindropResponse {
-    items: Vec<Bookmark>,
+    items: Vec<Raindrop>,
     #[serde(default)]
     #[allow(dead_code)]
     count: usize,
 }
 
+/// What a sync changed in the local cache
+#[derive(Debug, Default)]
+pub struct SyncStats {
+    pub fetched: usize,
+    pub removed: usize,
+    pub cached: usize,
+}
+
+/// Fields to change on a raindrop; unset fields are left alone
+#[derive(Debug, Default, Serialize)]
+pub struct RaindropUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdateResponse {
+    item: Raindrop,
+}
+
+/// Marks the start of the generated summary inside a raindrop note
+const SUMMARY_MARKER: &str = "--- Briefing summary ---";
+
+/// Collection IDs with special meaning in the Raindrop API
+const ALL_COLLECTIONS: i64 = 0;
+const TRASH_COLLECTION: i64 = -99;
+
+const DEFAULT_BASE_URL: &str = "https://api.raindrop.io/rest/v1";
+
 pub struct RaindropClient {
     client: Client,
     api_token: String,
+    base_url: String,
+    cache: Option<RaindropCache>,
 }
 
 impl RaindropClient {
@@ -35,22 +126,107 @@ impl RaindropClient {
             .build()
             .context("Failed to create HTTP client")?;
 
-        Ok(Self { client, api_token })
+        Ok(Self {
+            client,
+            api_token,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            cache: None,
+        })
+    }
+
+    /// Talk to a different API endpoint (used for testing against a mock server)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
     }
 
-    pub async fn fetch_bookmarks(&self, tag: &str, since: DateTime<Utc>) -> Result<Vec<Bookmark>> {
-        use std::collections::HashSet;
+    /// Keep bookmarks in a local cache and only fetch changes from the API
+    pub fn with_cache(mut self, cache: RaindropCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Fetch every page of raindrops in `collection` matching `search`
+    async fn fetch_all(&self, collection: i64, search: &str) -> Result<Vec<Raindrop>> {
+        let mut all_raindrops = Vec::new();
+        let mut seen_ids = HashSet::new();
+        let mut page = 0;
+        let per_page = 50;
+
+        loop {
+            let url = format!(
+                "{}/raindrops/{}?perpage={}&page={}&search={}",
+                self.base_url,
+                collection,
+                per_page,
+                page,
+                urlencoding::encode(search)
+            );
+
+            let response = self
+                .client
+                .get(&url)
+                .header("Authorization", format!("Bearer {}", self.api_token))
+                .send()
+                .await
+                .context("Failed to fetch bookmarks from Raindrop.io")?;
+
+            let status = response.status();
+            if !status.is_success() {
+                let error_text = response
+                    .text()
+                    .await
+                    .unwrap_or_else(|_| String::from("unknown error"));
+                anyhow::bail!("Raindrop API returned error: {} - {}", status, error_text);
+            }
+
+            let raindrop_response = response
+                .json::<RaindropResponse>()
+                .await
+                .context("Failed to parse Raindrop API response")?;
+
+            // A short page is the last one, so there's no need to ask for
+            // an empty one after it
+            let last_page = raindrop_response.items.len() < per_page;
 
+            // Deduplicate by bookmark ID
+            for raindrop in raindrop_response.items {
+                if seen_ids.insert(raindrop.id) {
+                    all_raindrops.push(raindrop);
+                }
+            }
+
+            if last_page {
+                break;
+            }
+            page += 1;
+
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        }
+
+        Ok(all_raindrops)
+    }
+
+    /// Search the API directly for raindrops matching `query` created after `since`.
+    ///
+    /// Tag expressions Raindrop can't express (OR, nesting) are searched by
+    /// date alone; callers filter the results with the full query.
+    pub async fn fetch_raindrops(
+        &self,
+        query: &BookmarkQuery,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Raindrop>> {
         let date_str = since.format("%Y-%m-%d").to_string();
+        let tag_search = query.tags.to_raindrop_search().unwrap_or_default();
 
         // Search for multiple case variations to handle uppercase/lowercase tags
         // Common variations: lowercase, UPPERCASE, Titlecase
-        let tag_variations = vec![
-            tag.to_lowercase(),
-            tag.to_uppercase(),
+        let mut tag_variations = vec![
+            tag_search.to_lowercase(),
+            tag_search.to_uppercase(),
             // Titlecase (first char upper, rest lower)
             {
-                let mut chars = tag.chars();
+                let mut chars = tag_search.chars();
                 match chars.next() {
                     None => String::new(),
                     Some(first) => first
@@ -60,63 +236,682 @@ impl RaindropClient {
                 }
             },
         ];
+        tag_variations.dedup();
 
-        let mut all_bookmarks = Vec::new();
-        let mut seen_ids = HashSet::new();
+        let collections = if query.collections.is_empty() {
+            vec![ALL_COLLECTIONS]
+        } else {
+            query.collections.clone()
+        };
 
-        // Search for each tag variation
-        for tag_variant in &tag_variations {
-            let search_query = format!("{} created:>{}", tag_variant, date_str);
-            let mut page = 0;
-            let per_page = 50;
-
-            loop {
-                let url = format!(
-                    "https://api.raindrop.io/rest/v1/raindrops/0?perpage={}&page={}&search={}",
-                    per_page,
-                    page,
-                    urlencoding::encode(&search_query)
-                );
+        let mut all_raindrops = Vec::new();
+        let mut seen_ids = HashSet::new();
 
-                let response = self
-                    .client
-                    .get(&url)
-                    .header("Authorization", format!("Bearer {}", self.api_token))
-                    .send()
-                    .await
-                    .context("Failed to fetch bookmarks from Raindrop.io")?;
-
-                let status = response.status();
-                if !status.is_success() {
-                    let error_text = response
-                        .text()
-                        .await
-                        .unwrap_or_else(|_| String::from("unknown error"));
-                    anyhow::bail!("Raindrop API returned error: {} - {}", status, error_text);
+        // Search each collection for each tag variation
+        for collection in &collections {
+            for tag_variant in &tag_variations {
+                let search_query = format!("{} created:>{}", tag_variant, date_str)
+                    .trim()
+                    .to_string();
+                for raindrop in self.fetch_all(*collection, &search_query).await? {
+                    if seen_ids.insert(raindrop.id) {
+                        all_raindrops.push(raindrop);
+                    }
                 }
+            }
+        }
 
-                let raindrop_response = response
-                    .json::<RaindropResponse>()
-                    .await
-                    .context("Failed to parse Raindrop API response")?;
+        Ok(all_raindrops)
+    }
 
-                if raindrop_response.items.is_empty() {
-                    break;
-                }
+    /// Bring the local cache up to date for bookmarks created after `since`.
+    ///
+    /// Every sync lists the whole window, dropping cached raindrops it no
+    /// longer finds, since one deleted outright never shows up in the trash.
+    /// Cached raindrops from further back, kept for runs with a longer
+    /// window, are refreshed from those changed since the previous sync and
+    /// dropped once they're in the trash.
+    pub async fn sync(&self, cache: &RaindropCache, since: DateTime<Utc>) -> Result<SyncStats> {
+        let started = Utc::now();
+        let mut stats = SyncStats::default();
 
-                // Deduplicate by bookmark ID
-                for bookmark in raindrop_response.items {
-                    if seen_ids.insert(bookmark.id) {
-                        all_bookmarks.push(bookmark);
-                    }
-                }
+        let search = format!("created:>{}", since.format("%Y-%m-%d"));
+        let raindrops = self.fetch_all(ALL_COLLECTIONS, &search).await?;
+        stats.fetched += raindrops.len();
 
-                page += 1;
+        // Whatever the search should have listed but didn't was deleted.
+        // It's day-granular, so leave the first day alone.
+        let listed: HashSet<i64> = raindrops.iter().map(|r| r.id).collect();
+        let deleted: Vec<i64> = cache
+            .ids_since(since + Duration::days(1))?
+            .into_iter()
+            .filter(|id| !listed.contains(id))
+            .collect();
+        stats.removed += cache.remove(&deleted)?;
+        cache.upsert(&raindrops)?;
 
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        let covered = match cache.covered_since()? {
+            Some(covered) if covered <= since => covered,
+            _ => {
+                cache.set_covered_since(since)?;
+                since
             }
+        };
+
+        if let Some(cursor) = cache.cursor()? {
+            // Search dates are day-granular, so look back a day past the
+            // cursor; re-fetching a few unchanged raindrops is harmless
+            let search = format!(
+                "lastUpdate:>{}",
+                (cursor - Duration::days(1)).format("%Y-%m-%d")
+            );
+
+            let changed = self.fetch_all(ALL_COLLECTIONS, &search).await?;
+            stats.fetched += changed.len();
+            cache.upsert(&changed)?;
         }
 
-        Ok(all_bookmarks)
+        // Moving a raindrop to the trash doesn't always touch lastUpdate, so
+        // check everything there from the cached window
+        let search = format!("created:>{}", covered.format("%Y-%m-%d"));
+        let trashed = self.fetch_all(TRASH_COLLECTION, &search).await?;
+        let trashed_ids: Vec<i64> = trashed.iter().map(|r| r.id).collect();
+        stats.removed += cache.remove(&trashed_ids)?;
+
+        cache.set_cursor(started)?;
+        stats.cached = cache.len()?;
+
+        Ok(stats)
+    }
+
+    /// Apply `update` to a single raindrop and return the updated raindrop
+    pub async fn update_raindrop(&self, id: i64, update: &RaindropUpdate) -> Result<Raindrop> {
+        let url = format!("{}/raindrop/{}", self.base_url, id);
+
+        let response = self
+            .client
+            .put(&url)
+            .header("Authorization", format!("Bearer {}", self.api_token))
+            .json(update)
+            .send()
+            .await
+            .context("Failed to update bookmark on Raindrop.io")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| String::from("unknown error"));
+            anyhow::bail!("Raindrop API returned error: {} - {}", status, error_text);
+        }
+
+        let update_response = response
+            .json::<UpdateResponse>()
+            .await
+            .context("Failed to parse Raindrop API response")?;
+
+        Ok(update_response.item)
+    }
+
+    /// Tag a bookmark as used in a briefing and optionally store its summary in the note.
+    ///
+    /// Raindrop replaces the whole tag list on update, so the bookmark's
+    /// existing tags are sent along with the new one.
+    pub async fn mark_used(
+        &self,
+        bookmark: &Bookmark,
+        used_tag: &str,
+        summary: Option<&str>,
+    ) -> Result<()> {
+        let id: i64 = bookmark
+            .id
+            .parse()
+            .with_context(|| format!("Not a Raindrop bookmark ID: {}", bookmark.id))?;
+
+        let mut tags = bookmark.tags.clone();
+        if !tags.iter().any(|t| t == used_tag) {
+            tags.push(used_tag.to_string());
+        }
+
+        let update = RaindropUpdate {
+            tags: Some(tags),
+            note: summary.map(|s| merge_note(bookmark.note.as_deref(), s)),
+        };
+
+        self.update_raindrop(id, &update).await?;
+        Ok(())
+    }
+}
+
+/// The part of a raindrop note written by the producer rather than by us
+fn producer_note(note: &str) -> &str {
+    match note.find(SUMMARY_MARKER) {
+        Some(pos) => &note[..pos],
+        None => note,
+    }
+    .trim()
+}
+
+/// Put `summary` below the producer's own note, replacing any summary from an earlier run
+pub fn merge_note(existing: Option<&str>, summary: &str) -> String {
+    let own_note = existing.map(producer_note).unwrap_or("");
+
+    if own_note.is_empty() {
+        format!("{}\n{}", SUMMARY_MARKER, summary)
+    } else {
+        format!("{}\n\n{}\n{}", own_note, SUMMARY_MARKER, summary)
+    }
+}
+
+#[async_trait]
+impl BookmarkSource for RaindropClient {
+    fn name(&self) -> &str {
+        "Raindrop.io"
+    }
+
+    async fn fetch_bookmarks(
+        &self,
+        query: &BookmarkQuery,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Bookmark>> {
+        let raindrops = match &self.cache {
+            Some(cache) => {
+                let stats = self.sync(cache, since).await?;
+                println!(
+                    "  Synced {} new or changed bookmarks ({} removed, {} cached)",
+                    stats.fetched, stats.removed, stats.cached
+                );
+                cache
+                    .raindrops_since(since)?
+                    .into_iter()
+                    .filter(|r| query.allows_collection(r.collection_id()))
+                    .collect()
+            }
+            None => self.fetch_raindrops(query, since).await?,
+        };
+
+        Ok(raindrops
+            .into_iter()
+            .filter(|r| query.matches_tags(&r.tags) && query.allows_link(&r.link))
+            .map(Bookmark::from)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn bookmark(id: &str, tags: &[&str], note: Option<&str>) -> Bookmark {
+        Bookmark {
+            id: id.to_string(),
+            source: "raindrop".to_string(),
+            title: "Story".to_string(),
+            link: "https://example.com/story".to_string(),
+            excerpt: None,
+            note: note.map(String::from),
+            highlights: Vec::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            created: "2026-02-01T10:00:00.000Z".to_string(),
+        }
+    }
+
+    fn item_json(id: i64, tags: &[&str], note: &str) -> serde_json::Value {
+        serde_json::json!({
+            "_id": id,
+            "title": "Story",
+            "link": "https://example.com/story",
+            "excerpt": "",
+            "note": note,
+            "tags": tags,
+            "created": "2026-02-01T10:00:00.000Z",
+            "lastUpdate": "2026-02-08T10:00:00.000Z",
+            "collection": {"$id": 100}
+        })
+    }
+
+    /// Serve `items` as the only page of `search` in `collection`
+    async fn mount_search(
+        server: &MockServer,
+        collection: i64,
+        search: &str,
+        items: Vec<serde_json::Value>,
+    ) {
+        let collection_path = format!("/raindrops/{}", collection);
+        Mock::given(method("GET"))
+            .and(path(collection_path.as_str()))
+            .and(query_param("search", search))
+            .and(query_param("page", "0"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": items })),
+            )
+            .mount(server)
+            .await;
+    }
+
+    fn client(server: &MockServer) -> RaindropClient {
+        RaindropClient::new("token".to_string())
+            .unwrap()
+            .with_base_url(server.uri())
+    }
+
+    #[test]
+    fn test_merge_note_without_existing_note() {
+        assert_eq!(
+            merge_note(None, "- Point"),
+            "--- Briefing summary ---\n- Point"
+        );
+    }
+
+    #[test]
+    fn test_merge_note_keeps_producer_note() {
+        assert_eq!(
+            merge_note(Some("Lead story"), "- Point"),
+            "Lead story\n\n--- Briefing summary ---\n- Point"
+        );
+    }
+
+    #[test]
+    fn test_merge_note_replaces_previous_summary() {
+        let first = merge_note(Some("Lead story"), "- Old point");
+        assert_eq!(
+            merge_note(Some(&first), "- New point"),
+            "Lead story\n\n--- Briefing summary ---\n- New point"
+        );
+    }
+
+    #[test]
+    fn test_bookmark_from_raindrop_keeps_annotations() {
+        let raindrop: Raindrop = serde_json::from_value(serde_json::json!({
+            "_id": 5,
+            "title": "Story",
+            "link": "https://example.com/story",
+            "excerpt": "",
+            "note": "Lead story\n\n--- Briefing summary ---\n- Old point",
+            "tags": ["twit"],
+            "created": "2026-02-01T10:00:00.000Z",
+            "highlights": [
+                {"_id": "h1", "text": "First passage", "note": "", "color": "yellow"},
+                {"_id": "h2", "text": "Second passage", "note": "Ask Leo", "color": "blue"}
+            ]
+        }))
+        .unwrap();
+
+        let bookmark = Bookmark::from(raindrop);
+
+        assert_eq!(bookmark.note.as_deref(), Some("Lead story"));
+        assert_eq!(
+            bookmark.highlights,
+            vec!["First passage", "Second passage (Ask Leo)"]
+        );
+    }
+
+    #[test]
+    fn test_bookmark_from_raindrop_drops_summary_only_note() {
+        let raindrop: Raindrop =
+            serde_json::from_value(item_json(6, &["twit"], "--- Briefing summary ---\n- Point"))
+                .unwrap();
+
+        assert!(Bookmark::from(raindrop).note.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_mark_used_sends_existing_tags_and_note() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/raindrop/42"))
+            .and(header("Authorization", "Bearer token"))
+            .and(body_json(serde_json::json!({
+                "tags": ["twit", "twit-used-2026-02-08"],
+                "note": "--- Briefing summary ---\n- Point"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "result": true,
+                "item": item_json(42, &["twit", "twit-used-2026-02-08"], "")
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        client(&server)
+            .mark_used(
+                &bookmark("42", &["twit"], None),
+                "twit-used-2026-02-08",
+                Some("- Point"),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_mark_used_without_summary_leaves_note_alone() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/raindrop/7"))
+            .and(body_json(serde_json::json!({
+                "tags": ["twit-used-2026-02-08", "twit"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "result": true,
+                "item": item_json(7, &["twit-used-2026-02-08", "twit"], "Keep me")
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // Already tagged from an earlier run: the tag isn't duplicated
+        client(&server)
+            .mark_used(
+                &bookmark("7", &["twit-used-2026-02-08", "twit"], Some("Keep me")),
+                "twit-used-2026-02-08",
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_raindrop_reports_api_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/raindrop/1"))
+            .respond_with(ResponseTemplate::new(404).set_body_string("not found"))
+            .mount(&server)
+            .await;
+
+        let err = client(&server)
+            .update_raindrop(1, &RaindropUpdate::default())
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("404"));
+    }
+
+    #[tokio::test]
+    async fn test_mark_used_rejects_non_raindrop_ids() {
+        let server = MockServer::start().await;
+        let result = client(&server)
+            .mark_used(&bookmark("abc", &[], None), "used", None)
+            .await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_stops_after_short_page() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/raindrops/0"))
+            .and(query_param("page", "0"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [item_json(1, &["twit"], ""), item_json(2, &["twit"], "")],
+                "count": 2
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/raindrops/0"))
+            .and(query_param("page", "1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"items": [], "count": 2})),
+            )
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let raindrops = client(&server)
+            .fetch_all(ALL_COLLECTIONS, "#twit")
+            .await
+            .unwrap();
+
+        assert_eq!(raindrops.len(), 2);
+        assert_eq!(
+            raindrops[0].last_update.as_deref(),
+            Some("2026-02-08T10:00:00.000Z")
+        );
+        assert_eq!(raindrops[0].collection_id(), Some(100));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_pages_while_full() {
+        let server = MockServer::start().await;
+        let full_page: Vec<_> = (1..=50).map(|id| item_json(id, &["twit"], "")).collect();
+        Mock::given(method("GET"))
+            .and(path("/raindrops/0"))
+            .and(query_param("page", "0"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": full_page })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/raindrops/0"))
+            .and(query_param("page", "1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "items": [item_json(51, &["twit"], "")] })),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/raindrops/0"))
+            .and(query_param("page", "2"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({ "items": [] })),
+            )
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let raindrops = client(&server)
+            .fetch_all(ALL_COLLECTIONS, "#twit")
+            .await
+            .unwrap();
+
+        assert_eq!(raindrops.len(), 51);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bookmarks_searches_collections_and_filters() {
+        let server = MockServer::start().await;
+        // OR can't be expressed in Raindrop search, so only the date is sent
+        Mock::given(method("GET"))
+            .and(path("/raindrops/100"))
+            .and(query_param("page", "0"))
+            .and(query_param("search", "created:>2026-01-25"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "items": [
+                    item_json(1, &["IM"], ""),
+                    item_json(2, &["ai", "skip"], ""),
+                    item_json(3, &["twit"], "")
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/raindrops/100"))
+            .and(query_param("page", "1"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"items": []})),
+            )
+            .mount(&server)
+            .await;
+
+        let mut show = crate::ShowInfo::new("Intelligent Machines", "im", "(#im OR #ai) -#skip");
+        show.collections = vec![100];
+        let query = BookmarkQuery::from_show(&show).unwrap();
+        let since = "2026-01-25T00:00:00Z".parse().unwrap();
+
+        let bookmarks = client(&server)
+            .fetch_bookmarks(&query, since)
+            .await
+            .unwrap();
+
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].id, "1");
+    }
+
+    #[tokio::test]
+    async fn test_sync_backfills_then_fetches_changes() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RaindropCache::open(&dir.path().join("cache.sqlite")).unwrap();
+        let since = "2026-01-25T00:00:00Z".parse().unwrap();
+
+        let first = {
+            let mut item = item_json(1, &["twit"], "");
+            item["title"] = "First".into();
+            item
+        };
+        mount_search(
+            &server,
+            ALL_COLLECTIONS,
+            "created:>2026-01-25",
+            vec![first, item_json(2, &["twit"], "")],
+        )
+        .await;
+        mount_search(&server, TRASH_COLLECTION, "created:>2026-01-25", Vec::new()).await;
+
+        let stats = client(&server).sync(&cache, since).await.unwrap();
+        assert_eq!(stats.fetched, 2);
+        assert_eq!(stats.removed, 0);
+        assert_eq!(stats.cached, 2);
+        assert_eq!(cache.covered_since().unwrap(), Some(since));
+
+        // The next sync lists the window again and asks for what changed
+        // since the cursor
+        cache
+            .set_cursor("2026-02-08T00:00:00Z".parse().unwrap())
+            .unwrap();
+        let edited = {
+            let mut item = item_json(1, &["twit"], "");
+            item["title"] = "Edited".into();
+            item
+        };
+        mount_search(
+            &server,
+            ALL_COLLECTIONS,
+            "lastUpdate:>2026-02-07",
+            vec![edited],
+        )
+        .await;
+
+        let stats = client(&server).sync(&cache, since).await.unwrap();
+        assert_eq!(stats.fetched, 3);
+        assert_eq!(stats.cached, 2);
+        let titles: Vec<String> = cache
+            .raindrops_since(since)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.title)
+            .collect();
+        assert!(titles.contains(&"Edited".to_string()));
+    }
+
+    fn cached_raindrops(ids: &[i64]) -> Vec<Raindrop> {
+        ids.iter()
+            .map(|&id| serde_json::from_value(item_json(id, &["twit"], "")).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_full_sync_removes_deleted_raindrops() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RaindropCache::open(&dir.path().join("cache.sqlite")).unwrap();
+        let since = "2026-01-25T00:00:00Z".parse().unwrap();
+
+        // Cached by an earlier run; 2 has since been deleted outright
+        cache.upsert(&cached_raindrops(&[1, 2])).unwrap();
+
+        mount_search(
+            &server,
+            ALL_COLLECTIONS,
+            "created:>2026-01-25",
+            vec![item_json(1, &["twit"], "")],
+        )
+        .await;
+        mount_search(&server, TRASH_COLLECTION, "created:>2026-01-25", Vec::new()).await;
+
+        let stats = client(&server).sync(&cache, since).await.unwrap();
+        assert_eq!(stats.removed, 1);
+        assert_eq!(cache.ids_since(since).unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_incremental_sync_removes_deleted_raindrops() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RaindropCache::open(&dir.path().join("cache.sqlite")).unwrap();
+        let since = "2026-01-25T00:00:00Z".parse().unwrap();
+
+        cache.upsert(&cached_raindrops(&[1, 2])).unwrap();
+        cache.set_covered_since(since).unwrap();
+        cache
+            .set_cursor("2026-02-08T00:00:00Z".parse().unwrap())
+            .unwrap();
+
+        // 2 was deleted from the trash, so it's listed nowhere
+        mount_search(
+            &server,
+            ALL_COLLECTIONS,
+            "created:>2026-01-25",
+            vec![item_json(1, &["twit"], "")],
+        )
+        .await;
+        mount_search(
+            &server,
+            ALL_COLLECTIONS,
+            "lastUpdate:>2026-02-07",
+            Vec::new(),
+        )
+        .await;
+        mount_search(&server, TRASH_COLLECTION, "created:>2026-01-25", Vec::new()).await;
+
+        let stats = client(&server).sync(&cache, since).await.unwrap();
+        assert_eq!(stats.removed, 1);
+        assert_eq!(cache.ids_since(since).unwrap(), vec![1]);
+    }
+
+    #[tokio::test]
+    async fn test_sync_removes_trashed_raindrops() {
+        let server = MockServer::start().await;
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RaindropCache::open(&dir.path().join("cache.sqlite")).unwrap();
+        let covered = "2026-01-25T00:00:00Z".parse().unwrap();
+        let since = "2026-02-01T00:00:00Z".parse().unwrap();
+
+        // Cached by a run with a longer window than this one
+        cache.upsert(&cached_raindrops(&[1, 2])).unwrap();
+        cache.set_covered_since(covered).unwrap();
+        cache
+            .set_cursor("2026-02-08T00:00:00Z".parse().unwrap())
+            .unwrap();
+
+        // Nothing changed, but 2 sits in the trash
+        mount_search(&server, ALL_COLLECTIONS, "created:>2026-02-01", Vec::new()).await;
+        mount_search(
+            &server,
+            ALL_COLLECTIONS,
+            "lastUpdate:>2026-02-07",
+            Vec::new(),
+        )
+        .await;
+        mount_search(
+            &server,
+            TRASH_COLLECTION,
+            "created:>2026-01-25",
+            vec![item_json(2, &["twit"], ""), item_json(9, &["twit"], "")],
+        )
+        .await;
+
+        let stats = client(&server).sync(&cache, since).await.unwrap();
+        assert_eq!(stats.fetched, 0);
+        assert_eq!(stats.removed, 1);
+        assert_eq!(cache.ids_since(covered).unwrap(), vec![1]);
+        assert_eq!(cache.covered_since().unwrap(), Some(covered));
     }
 }
@@ -0,0 +1,307 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::cache_db;
+use crate::raindrop::Raindrop;
+
+/// Bump when `Raindrop` gains fields that cached entries need
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS raindrops (
+        id INTEGER PRIMARY KEY,
+        created TEXT NOT NULL,
+        data TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS raindrops_created ON raindrops (created);
+    CREATE TABLE IF NOT EXISTS sync_state (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+/// Local copy of Raindrop bookmarks so each run only fetches what changed
+pub struct RaindropCache {
+    conn: Mutex<Connection>,
+}
+
+impl RaindropCache {
+    /// Open (or create) the cache database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        // Entries cached by an older version lack fields we now filter on
+        let conn = cache_db::open(
+            path,
+            "bookmark cache",
+            SCHEMA_VERSION,
+            "DROP TABLE IF EXISTS raindrops; DROP TABLE IF EXISTS sync_state;",
+            SCHEMA,
+        )?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Open the cache in the default stories directory
+    pub fn open_default() -> Result<Self> {
+        let path = crate::io::get_default_stories_dir()?.join("raindrop-cache.sqlite");
+        Self::open(&path)
+    }
+
+    /// Insert or replace raindrops by `_id`
+    pub fn upsert(&self, raindrops: &[Raindrop]) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare(
+                "INSERT OR REPLACE INTO raindrops (id, created, data) VALUES (?1, ?2, ?3)",
+            )?;
+            for raindrop in raindrops {
+                let data = serde_json::to_string(raindrop)?;
+                stmt.execute(params![
+                    raindrop.id,
+                    Self::normalize_timestamp(&raindrop.created),
+                    data
+                ])?;
+            }
+        }
+        tx.commit().context("Failed to update bookmark cache")?;
+        Ok(())
+    }
+
+    /// Drop raindrops that were deleted or moved to the trash, returning how
+    /// many of them were cached
+    pub fn remove(&self, ids: &[i64]) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("DELETE FROM raindrops WHERE id = ?1")?;
+        let mut removed = 0;
+        for id in ids {
+            removed += stmt.execute([id])?;
+        }
+        Ok(removed)
+    }
+
+    /// IDs of the cached raindrops created after `since`
+    pub fn ids_since(&self, since: DateTime<Utc>) -> Result<Vec<i64>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare("SELECT id FROM raindrops WHERE created > ?1 ORDER BY id")?;
+        let ids = stmt
+            .query_map([cache_db::timestamp(since)], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<i64>>>()?;
+        Ok(ids)
+    }
+
+    /// All cached raindrops created after `since`
+    pub fn raindrops_since(&self, since: DateTime<Utc>) -> Result<Vec<Raindrop>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt =
+            conn.prepare("SELECT data FROM raindrops WHERE created > ?1 ORDER BY created")?;
+        let rows = stmt.query_map([cache_db::timestamp(since)], |row| row.get::<_, String>(0))?;
+
+        let mut raindrops = Vec::new();
+        for data in rows {
+            let raindrop: Raindrop =
+                serde_json::from_str(&data?).context("Corrupt entry in bookmark cache")?;
+            raindrops.push(raindrop);
+        }
+        Ok(raindrops)
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM raindrops", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    /// When the last successful sync started
+    pub fn cursor(&self) -> Result<Option<DateTime<Utc>>> {
+        self.get_time("cursor")
+    }
+
+    pub fn set_cursor(&self, cursor: DateTime<Utc>) -> Result<()> {
+        self.set_time("cursor", cursor)
+    }
+
+    /// The earliest creation date the cache is known to be complete from
+    pub fn covered_since(&self) -> Result<Option<DateTime<Utc>>> {
+        self.get_time("covered_since")
+    }
+
+    pub fn set_covered_since(&self, since: DateTime<Utc>) -> Result<()> {
+        self.set_time("covered_since", since)
+    }
+
+    /// Forget the sync state so the next sync starts from scratch
+    pub fn reset(&self) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute_batch("DELETE FROM raindrops; DELETE FROM sync_state;")
+            .context("Failed to reset bookmark cache")?;
+        Ok(())
+    }
+
+    fn get_time(&self, key: &str) -> Result<Option<DateTime<Utc>>> {
+        let conn = self.conn.lock().unwrap();
+        let value: Option<String> = conn
+            .query_row(
+                "SELECT value FROM sync_state WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(value
+            .and_then(|v| DateTime::parse_from_rfc3339(&v).ok())
+            .map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    fn set_time(&self, key: &str, value: DateTime<Utc>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO sync_state (key, value) VALUES (?1, ?2)",
+            params![key, value.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Store timestamps in one UTC format so SQLite can compare them as text
+    fn normalize_timestamp(timestamp: &str) -> String {
+        DateTime::parse_from_rfc3339(timestamp)
+            .map(|dt| cache_db::timestamp(dt.with_timezone(&Utc)))
+            .unwrap_or_else(|_| timestamp.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn raindrop(id: i64, created: &str) -> Raindrop {
+        Raindrop {
+            id,
+            title: format!("Story {}", id),
+            link: format!("https://example.com/{}", id),
+            excerpt: None,
+            note: None,
+            tags: vec!["twit".to_string()],
+            created: created.to_string(),
+            last_update: Some(created.to_string()),
+            highlights: Vec::new(),
+            collection: None,
+        }
+    }
+
+    fn open_temp() -> (tempfile::TempDir, RaindropCache) {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RaindropCache::open(&dir.path().join("cache.sqlite")).unwrap();
+        (dir, cache)
+    }
+
+    #[test]
+    fn test_upsert_replaces_by_id() {
+        let (_dir, cache) = open_temp();
+        cache
+            .upsert(&[raindrop(1, "2026-02-01T10:00:00.000Z")])
+            .unwrap();
+
+        let mut changed = raindrop(1, "2026-02-01T10:00:00.000Z");
+        changed.title = "Edited".to_string();
+        cache.upsert(&[changed]).unwrap();
+
+        assert_eq!(cache.len().unwrap(), 1);
+        let since = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(cache.raindrops_since(since).unwrap()[0].title, "Edited");
+    }
+
+    #[test]
+    fn test_raindrops_since_filters_by_created() {
+        let (_dir, cache) = open_temp();
+        cache
+            .upsert(&[
+                raindrop(1, "2026-01-20T10:00:00.000Z"),
+                raindrop(2, "2026-02-01T10:00:00Z"),
+                raindrop(3, "2026-02-02T01:00:00-08:00"),
+            ])
+            .unwrap();
+
+        let since = Utc.with_ymd_and_hms(2026, 1, 25, 0, 0, 0).unwrap();
+        let ids: Vec<i64> = cache
+            .raindrops_since(since)
+            .unwrap()
+            .iter()
+            .map(|r| r.id)
+            .collect();
+
+        assert_eq!(ids, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_open_discards_entries_from_older_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cache.sqlite");
+        {
+            let cache = RaindropCache::open(&path).unwrap();
+            cache
+                .upsert(&[raindrop(1, "2026-02-01T10:00:00.000Z")])
+                .unwrap();
+            let conn = cache.conn.lock().unwrap();
+            conn.execute_batch("PRAGMA user_version = 0").unwrap();
+        }
+
+        let cache = RaindropCache::open(&path).unwrap();
+        assert!(cache.is_empty().unwrap());
+
+        // Reopening at the current version keeps entries
+        cache
+            .upsert(&[raindrop(1, "2026-02-01T10:00:00.000Z")])
+            .unwrap();
+        drop(cache);
+        assert_eq!(RaindropCache::open(&path).unwrap().len().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_remove() {
+        let (_dir, cache) = open_temp();
+        cache
+            .upsert(&[
+                raindrop(1, "2026-02-01T10:00:00.000Z"),
+                raindrop(2, "2026-02-01T11:00:00.000Z"),
+            ])
+            .unwrap();
+
+        // Only raindrops that were cached count as removed
+        assert_eq!(cache.remove(&[1, 3]).unwrap(), 1);
+
+        assert_eq!(cache.len().unwrap(), 1);
+        let since = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(cache.ids_since(since).unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn test_sync_state_round_trip_and_reset() {
+        let (_dir, cache) = open_temp();
+        assert!(cache.cursor().unwrap().is_none());
+
+        let cursor = Utc.with_ymd_and_hms(2026, 2, 1, 18, 0, 0).unwrap();
+        let covered = Utc.with_ymd_and_hms(2026, 1, 25, 0, 0, 0).unwrap();
+        cache.set_cursor(cursor).unwrap();
+        cache.set_covered_since(covered).unwrap();
+        cache
+            .upsert(&[raindrop(1, "2026-02-01T10:00:00.000Z")])
+            .unwrap();
+
+        assert_eq!(cache.cursor().unwrap(), Some(cursor));
+        assert_eq!(cache.covered_since().unwrap(), Some(covered));
+
+        cache.reset().unwrap();
+        assert!(cache.cursor().unwrap().is_none());
+        assert!(cache.is_empty().unwrap());
+    }
+}
@@ -0,0 +1,448 @@
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+use crate::config::RateLimitSettings;
+use crate::politeness::parse_retry_after;
+
+/// How long to hold off after a 429 that doesn't say
+const DEFAULT_PAUSE: Duration = Duration::from_secs(15);
+
+/// Below this share of a budget, fewer calls run at once
+const LOW_BUDGET: f64 = 0.1;
+
+/// Above this share of every budget, more calls may run at once
+const HEALTHY_BUDGET: f64 = 0.5;
+
+/// A budget the API reports, refilling continuously up to its limit
+#[derive(Debug, Clone)]
+struct Bucket {
+    limit: f64,
+    level: f64,
+    per_sec: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// `remaining` of `limit` left now, back to full at `reset`
+    fn new(limit: f64, remaining: f64, refill_in: Option<Duration>, now: Instant) -> Self {
+        // Anthropic's limits are per minute; the reset time tightens that
+        // estimate when it's given
+        let per_sec = match refill_in {
+            Some(wait) if remaining < limit && !wait.is_zero() => {
+                (limit - remaining) / wait.as_secs_f64()
+            }
+            _ => limit / 60.0,
+        };
+        Self {
+            limit,
+            level: remaining.min(limit),
+            per_sec: per_sec.max(f64::MIN_POSITIVE),
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.level = (self.level + elapsed * self.per_sec).min(self.limit);
+        self.updated = now;
+    }
+
+    /// How long until `amount` is available; more than the whole limit only
+    /// waits for a full bucket
+    fn wait_for(&self, amount: f64) -> Duration {
+        let amount = amount.min(self.limit);
+        if self.level >= amount {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((amount - self.level) / self.per_sec)
+        }
+    }
+
+    fn share_left(&self) -> f64 {
+        if self.limit > 0.0 {
+            self.level / self.limit
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    concurrency: usize,
+    in_flight: usize,
+    /// Set by a 429; nothing starts before then
+    paused_until: Option<Instant>,
+    /// Unknown until the API reports them
+    requests: Option<Bucket>,
+    input_tokens: Option<Bucket>,
+}
+
+impl State {
+    /// Start a call if the budget allows, otherwise say how long to wait;
+    /// `None` means until a running call finishes
+    fn try_start(&mut self, input_tokens: u32, now: Instant) -> Result<(), Option<Duration>> {
+        if let Some(until) = self.paused_until {
+            if now < until {
+                return Err(Some(until - now));
+            }
+            self.paused_until = None;
+        }
+        if self.in_flight >= self.concurrency {
+            return Err(None);
+        }
+
+        let mut wait = Duration::ZERO;
+        for (bucket, amount) in [
+            (&mut self.requests, 1.0),
+            (&mut self.input_tokens, input_tokens as f64),
+        ] {
+            if let Some(bucket) = bucket {
+                bucket.refill(now);
+                wait = wait.max(bucket.wait_for(amount));
+            }
+        }
+        if !wait.is_zero() {
+            return Err(Some(wait));
+        }
+
+        if let Some(bucket) = &mut self.requests {
+            bucket.level -= 1.0;
+        }
+        if let Some(bucket) = &mut self.input_tokens {
+            bucket.level -= (input_tokens as f64).min(bucket.limit);
+        }
+        self.in_flight += 1;
+        Ok(())
+    }
+
+    /// Take in what a response says about the budget
+    fn observe(
+        &mut self,
+        status: u16,
+        headers: &HeaderMap,
+        max_concurrent: usize,
+        now: Instant,
+        now_utc: DateTime<Utc>,
+    ) {
+        if let Some(bucket) = header_bucket(headers, "requests", now, now_utc) {
+            self.requests = Some(bucket);
+        }
+        if let Some(bucket) = header_bucket(headers, "input-tokens", now, now_utc) {
+            self.input_tokens = Some(bucket);
+        }
+
+        if status == 429 {
+            let pause = headers
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| parse_retry_after(v, now_utc))
+                .unwrap_or(DEFAULT_PAUSE);
+            let until = now + pause;
+            self.paused_until = Some(self.paused_until.map_or(until, |u| u.max(until)));
+            self.concurrency = (self.concurrency / 2).max(1);
+            return;
+        }
+
+        // Without a reported budget there's nothing to adjust to
+        let share_left = [&self.requests, &self.input_tokens]
+            .into_iter()
+            .flatten()
+            .map(Bucket::share_left)
+            .reduce(f64::min);
+        match share_left {
+            Some(share) if share < LOW_BUDGET => {
+                self.concurrency = self.concurrency.saturating_sub(1).max(1);
+            }
+            Some(share) if share > HEALTHY_BUDGET => {
+                self.concurrency = (self.concurrency + 1).min(max_concurrent);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A budget from `anthropic-ratelimit-{name}-limit`, `-remaining` and `-reset`
+fn header_bucket(
+    headers: &HeaderMap,
+    name: &str,
+    now: Instant,
+    now_utc: DateTime<Utc>,
+) -> Option<Bucket> {
+    let header = |suffix: &str| {
+        headers
+            .get(format!("anthropic-ratelimit-{}-{}", name, suffix))
+            .and_then(|v| v.to_str().ok())
+    };
+    let limit: f64 = header("limit")?.parse().ok()?;
+    let remaining: f64 = header("remaining")?.parse().ok()?;
+    let refill_in = header("reset")
+        .and_then(|reset| DateTime::parse_from_rfc3339(reset).ok())
+        .and_then(|reset| (reset.with_timezone(&Utc) - now_utc).to_std().ok());
+    Some(Bucket::new(limit, remaining, refill_in, now))
+}
+
+/// Paces calls to one API: a token bucket each for requests and input
+/// tokens, refilled from the rate-limit headers of every response, a pause
+/// when it answers 429, and a concurrency cap that follows the budget left
+pub struct RateLimiter {
+    max_concurrent: usize,
+    state: Mutex<State>,
+    /// Woken when a call finishes or more may run at once
+    released: Notify,
+}
+
+/// Held while a call is in flight
+pub struct RateLimitPermit {
+    limiter: Arc<RateLimiter>,
+}
+
+impl Drop for RateLimitPermit {
+    fn drop(&mut self) {
+        self.limiter.state.lock().unwrap().in_flight -= 1;
+        self.limiter.released.notify_waiters();
+    }
+}
+
+impl RateLimiter {
+    pub fn new(settings: &RateLimitSettings) -> Self {
+        let max_concurrent = settings.max_concurrent.max(1);
+        Self {
+            max_concurrent,
+            state: Mutex::new(State {
+                concurrency: settings.start_concurrent.clamp(1, max_concurrent),
+                in_flight: 0,
+                paused_until: None,
+                requests: None,
+                input_tokens: None,
+            }),
+            released: Notify::new(),
+        }
+    }
+
+    /// Wait until a call sending about `input_tokens` fits the budget
+    pub async fn acquire(self: &Arc<Self>, input_tokens: u32) -> RateLimitPermit {
+        loop {
+            // Registered before checking so a release in between isn't missed
+            let released = self.released.notified();
+            tokio::pin!(released);
+            released.as_mut().enable();
+
+            let wait = self
+                .state
+                .lock()
+                .unwrap()
+                .try_start(input_tokens, Instant::now());
+            match wait {
+                Ok(()) => {
+                    return RateLimitPermit {
+                        limiter: self.clone(),
+                    }
+                }
+                Err(Some(wait)) => {
+                    tokio::select! {
+                        _ = released => {}
+                        _ = tokio::time::sleep(wait) => {}
+                    }
+                }
+                Err(None) => released.await,
+            }
+        }
+    }
+
+    /// Update the budget from a response's status and headers
+    pub fn observe(&self, status: u16, headers: &HeaderMap) {
+        self.state.lock().unwrap().observe(
+            status,
+            headers,
+            self.max_concurrent,
+            Instant::now(),
+            Utc::now(),
+        );
+        // A raised cap lets waiting calls start
+        self.released.notify_waiters();
+    }
+
+    /// How many calls may run at once right now
+    pub fn concurrency(&self) -> usize {
+        self.state.lock().unwrap().concurrency
+    }
+}
+
+/// One [`RateLimiter`] per API, so every provider calling the same API in
+/// this process draws on the same budget
+pub struct RateLimiters {
+    settings: RateLimitSettings,
+    limiters: Mutex<HashMap<String, Arc<RateLimiter>>>,
+}
+
+impl RateLimiters {
+    pub fn new(settings: RateLimitSettings) -> Self {
+        Self {
+            settings,
+            limiters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The limiter for the API at `base_url`
+    pub fn for_api(&self, base_url: &str) -> Arc<RateLimiter> {
+        let key = base_url.trim_end_matches('/').to_lowercase();
+        self.limiters
+            .lock()
+            .unwrap()
+            .entry(key)
+            .or_insert_with(|| Arc::new(RateLimiter::new(&self.settings)))
+            .clone()
+    }
+}
+
+/// Rough input token count for a request body, at about 4 bytes a token
+pub fn estimate_tokens(body: &[u8]) -> u32 {
+    (body.len() / 4).try_into().unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn state(concurrency: usize) -> State {
+        State {
+            concurrency,
+            in_flight: 0,
+            paused_until: None,
+            requests: None,
+            input_tokens: None,
+        }
+    }
+
+    fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes()).unwrap(),
+                value.parse().unwrap(),
+            );
+        }
+        headers
+    }
+
+    #[test]
+    fn test_token_budget_from_headers() {
+        let now = Instant::now();
+        let now_utc = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let mut state = state(4);
+        state.observe(
+            200,
+            &headers(&[
+                ("anthropic-ratelimit-requests-limit", "50"),
+                ("anthropic-ratelimit-requests-remaining", "49"),
+                ("anthropic-ratelimit-requests-reset", "2026-02-01T12:00:01Z"),
+                ("anthropic-ratelimit-input-tokens-limit", "50000"),
+                ("anthropic-ratelimit-input-tokens-remaining", "1000"),
+                (
+                    "anthropic-ratelimit-input-tokens-reset",
+                    "2026-02-01T12:00:49Z",
+                ),
+            ]),
+            8,
+            now,
+            now_utc,
+        );
+        // 2% of the token budget left: one fewer call at a time
+        assert_eq!(state.concurrency, 3);
+
+        assert_eq!(state.try_start(800, now), Ok(()));
+        // 200 tokens left, refilling at 1000 a second
+        let Err(Some(wait)) = state.try_start(1200, now) else {
+            panic!("expected to wait for tokens");
+        };
+        assert_eq!(wait, Duration::from_secs(1));
+        assert_eq!(state.try_start(1200, now + wait), Ok(()));
+        assert_eq!(state.in_flight, 2);
+    }
+
+    #[test]
+    fn test_pause_after_429() {
+        let now = Instant::now();
+        let now_utc = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let mut state = state(6);
+        state.observe(429, &headers(&[("retry-after", "20")]), 8, now, now_utc);
+
+        assert_eq!(state.concurrency, 3);
+        assert_eq!(state.try_start(10, now), Err(Some(Duration::from_secs(20))));
+        assert_eq!(state.try_start(10, now + Duration::from_secs(20)), Ok(()));
+
+        // No Retry-After: the default pause
+        state.observe(429, &HeaderMap::new(), 8, now, now_utc);
+        assert_eq!(state.paused_until, Some(now + DEFAULT_PAUSE));
+    }
+
+    #[test]
+    fn test_concurrency_follows_budget() {
+        let now = Instant::now();
+        let now_utc = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        let mut state = state(2);
+
+        // Servers that report no budget keep the starting concurrency
+        state.observe(200, &HeaderMap::new(), 3, now, now_utc);
+        assert_eq!(state.concurrency, 2);
+
+        let healthy = headers(&[
+            ("anthropic-ratelimit-requests-limit", "50"),
+            ("anthropic-ratelimit-requests-remaining", "45"),
+        ]);
+        for _ in 0..3 {
+            state.observe(200, &healthy, 3, now, now_utc);
+        }
+        assert_eq!(state.concurrency, 3);
+
+        assert_eq!(state.try_start(0, now), Ok(()));
+        assert_eq!(state.try_start(0, now), Ok(()));
+        assert_eq!(state.try_start(0, now), Ok(()));
+        assert_eq!(state.try_start(0, now), Err(None));
+    }
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_a_free_slot() {
+        let limiter = Arc::new(RateLimiter::new(&RateLimitSettings {
+            start_concurrent: 1,
+            max_concurrent: 1,
+        }));
+        let permit = limiter.acquire(100).await;
+
+        let waiting = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move {
+                let _permit = limiter.acquire(100).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(permit);
+        tokio::time::timeout(Duration::from_secs(1), waiting)
+            .await
+            .expect("second call should start once the first finishes")
+            .unwrap();
+    }
+
+    #[test]
+    fn test_limiters_shared_per_api() {
+        let limiters = RateLimiters::new(RateLimitSettings::default());
+        let anthropic = limiters.for_api("https://api.anthropic.com");
+        assert!(Arc::ptr_eq(
+            &anthropic,
+            &limiters.for_api("https://api.anthropic.com/")
+        ));
+        assert!(!Arc::ptr_eq(
+            &anthropic,
+            &limiters.for_api("http://localhost:11434/v1")
+        ));
+    }
+}
@@ -0,0 +1,451 @@
+use scraper::node::Node;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+
+/// Elements that never hold article text
+const REMOVED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "iframe", "object", "embed", "form", "button",
+    "input", "select", "textarea", "nav", "aside", "footer", "svg", "canvas", "dialog",
+];
+
+/// class/id words that mark boilerplate: cookie banners, sidebars, share bars, comments
+const UNLIKELY_WORDS: &[&str] = &[
+    "ad",
+    "ads",
+    "advert",
+    "agegate",
+    "banner",
+    "breadcrumb",
+    "comment",
+    "community",
+    "consent",
+    "cookie",
+    "disqus",
+    "footer",
+    "gdpr",
+    "header",
+    "masthead",
+    "menu",
+    "modal",
+    "nav",
+    "navbar",
+    "navigation",
+    "newsletter",
+    "outbrain",
+    "pagination",
+    "pager",
+    "paywall",
+    "popup",
+    "promo",
+    "recommended",
+    "related",
+    "remark",
+    "replies",
+    "share",
+    "sharing",
+    "shoutbox",
+    "sidebar",
+    "skyscraper",
+    "social",
+    "sponsor",
+    "subscribe",
+    "taboola",
+    "trending",
+    "widget",
+];
+
+/// class/id words that rescue an element despite an unlikely word, e.g. `article-header`
+const LIKELY_WORDS: &[&str] = &[
+    "article", "body", "column", "content", "entry", "hentry", "main", "post", "story", "text",
+];
+
+/// Paragraphs shorter than this don't count towards a container's score
+const MIN_PARAGRAPH_CHARS: usize = 25;
+
+/// Below this much text the extraction probably picked the wrong node
+const MIN_CONTENT_CHARS: usize = 250;
+
+/// Find the main article in a page and return it as cleaned-up HTML.
+///
+/// Follows the approach of Mozilla's Readability: paragraphs award points to
+/// their parent and grandparent, containers are weighted by their class/id and
+/// penalised by link density, and the best one (plus any sibling paragraphs
+/// that look like part of the same article) is kept with boilerplate stripped.
+/// Returns `None` when no container stands out, so callers can fall back to
+/// converting the whole page.
+pub fn extract_main_content(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let paragraphs = Selector::parse("p, pre, blockquote, td, div").ok()?;
+
+    let mut scores: HashMap<_, (ElementRef, f64)> = HashMap::new();
+
+    for element in document.select(&paragraphs) {
+        // A div only counts as a paragraph when it holds text rather than blocks
+        if element.value().name() == "div" && has_block_children(element) {
+            continue;
+        }
+        if is_removed(element)
+            || element
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(is_removed)
+        {
+            continue;
+        }
+
+        let text = inner_text(element);
+        let length = text.chars().count();
+        if length < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+
+        let score = 1.0 + text.matches(',').count() as f64 + (length as f64 / 100.0).min(3.0);
+
+        let ancestors = element.ancestors().filter_map(ElementRef::wrap).take(2);
+        for (level, ancestor) in ancestors.enumerate() {
+            if matches!(ancestor.value().name(), "html" | "body") {
+                break;
+            }
+            let entry = scores
+                .entry(ancestor.id())
+                .or_insert_with(|| (ancestor, initial_score(ancestor)));
+            entry.1 += if level == 0 { score } else { score / 2.0 };
+        }
+    }
+
+    let (top, top_score) = scores
+        .values()
+        .map(|(element, score)| (*element, score * (1.0 - link_density(*element))))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    // Pull in siblings that belong to the same article, e.g. paragraphs the
+    // page split across several wrappers
+    let threshold = (top_score * 0.2).max(10.0);
+    let mut content = String::from("<div>");
+    let siblings: Vec<ElementRef> = match top.parent().and_then(ElementRef::wrap) {
+        Some(parent) => parent.children().filter_map(ElementRef::wrap).collect(),
+        None => vec![top],
+    };
+
+    for sibling in siblings {
+        let include = sibling.id() == top.id()
+            || scores
+                .get(&sibling.id())
+                .is_some_and(|(el, score)| score * (1.0 - link_density(*el)) >= threshold)
+            || (sibling.value().name() == "p" && is_prose(sibling));
+
+        if include {
+            render(sibling, &mut content);
+        }
+    }
+    content.push_str("</div>");
+
+    let kept = Html::parse_fragment(&content);
+    if inner_text(kept.root_element()).chars().count() < MIN_CONTENT_CHARS {
+        return None;
+    }
+
+    Some(content)
+}
+
+/// Boilerplate that should be dropped along with everything inside it
+fn is_removed(element: ElementRef) -> bool {
+    let el = element.value();
+    if REMOVED_TAGS.contains(&el.name()) {
+        return true;
+    }
+    if matches!(el.name(), "html" | "body" | "article" | "main") {
+        return false;
+    }
+    if el.attr("hidden").is_some() || el.attr("aria-hidden") == Some("true") {
+        return true;
+    }
+    if let Some(style) = el.attr("style") {
+        let style = style.replace(' ', "").to_lowercase();
+        if style.contains("display:none") || style.contains("visibility:hidden") {
+            return true;
+        }
+    }
+    if matches!(
+        el.attr("role"),
+        Some("navigation" | "banner" | "complementary" | "dialog")
+    ) {
+        return true;
+    }
+
+    has_word(element, UNLIKELY_WORDS) && !has_word(element, LIKELY_WORDS)
+}
+
+/// Base score for a container before its paragraphs are counted
+fn initial_score(element: ElementRef) -> f64 {
+    let tag_score = match element.value().name() {
+        "article" | "main" => 10.0,
+        "div" | "section" => 5.0,
+        "pre" | "td" | "blockquote" => 3.0,
+        "ol" | "ul" | "dl" | "dd" | "dt" | "li" | "form" | "address" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    tag_score + class_weight(element)
+}
+
+/// +25 for each of class/id that sounds like content, -25 for boilerplate
+fn class_weight(element: ElementRef) -> f64 {
+    let mut weight = 0.0;
+    for attr in ["class", "id"] {
+        if let Some(value) = element.value().attr(attr) {
+            if words_match(value, UNLIKELY_WORDS) {
+                weight -= 25.0;
+            }
+            if words_match(value, LIKELY_WORDS) {
+                weight += 25.0;
+            }
+        }
+    }
+    weight
+}
+
+fn has_word(element: ElementRef, words: &[&str]) -> bool {
+    ["class", "id"]
+        .iter()
+        .filter_map(|attr| element.value().attr(attr))
+        .any(|value| words_match(value, words))
+}
+
+/// Match class/id tokens like `site-nav` or `cookieBanner` against a word
+/// list. Short words must match a whole token so `ad` doesn't hit `address`.
+fn words_match(value: &str, words: &[&str]) -> bool {
+    let lower = value.to_lowercase();
+    lower
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .any(|token| {
+            words
+                .iter()
+                .any(|word| token == *word || (word.len() >= 4 && token.starts_with(word)))
+        })
+}
+
+fn has_block_children(element: ElementRef) -> bool {
+    element
+        .children()
+        .filter_map(ElementRef::wrap)
+        .any(|child| {
+            matches!(
+                child.value().name(),
+                "p" | "div"
+                    | "section"
+                    | "article"
+                    | "blockquote"
+                    | "pre"
+                    | "table"
+                    | "ul"
+                    | "ol"
+                    | "dl"
+                    | "h1"
+                    | "h2"
+                    | "h3"
+                    | "h4"
+                    | "h5"
+                    | "h6"
+            )
+        })
+}
+
+/// A stray paragraph long enough, and light enough on links, to be article text
+fn is_prose(element: ElementRef) -> bool {
+    let text = inner_text(element);
+    let length = text.chars().count();
+    let density = link_density(element);
+
+    (length > 80 && density < 0.25) || (length > 0 && density == 0.0 && text.ends_with('.'))
+}
+
+/// Share of an element's text that sits inside links
+fn link_density(element: ElementRef) -> f64 {
+    let total = inner_text(element).chars().count();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let links = Selector::parse("a").unwrap();
+    let linked: usize = element
+        .select(&links)
+        .map(|a| inner_text(a).chars().count())
+        .sum();
+    linked as f64 / total as f64
+}
+
+/// Visible text with whitespace collapsed
+fn inner_text(element: ElementRef) -> String {
+    element
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Write `element` back out as HTML, leaving out boilerplate descendants
+fn render(element: ElementRef, out: &mut String) {
+    if is_removed(element) || is_link_list(element) {
+        return;
+    }
+
+    let el = element.value();
+    out.push('<');
+    out.push_str(el.name());
+    for attr in ["href", "src", "alt"] {
+        if let Some(value) = el.attr(attr) {
+            out.push_str(&format!(
+                " {}=\"{}\"",
+                attr,
+                escape(value).replace('"', "&quot;")
+            ));
+        }
+    }
+    out.push('>');
+
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => out.push_str(&escape(text)),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    render(child, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if !is_void(el.name()) {
+        out.push_str(&format!("</{}>", el.name()));
+    }
+}
+
+/// Lists and panels that are mostly links, e.g. "More stories" or tag clouds
+fn is_link_list(element: ElementRef) -> bool {
+    matches!(
+        element.value().name(),
+        "ul" | "ol" | "div" | "section" | "table"
+    ) && inner_text(element).chars().count() > 0
+        && link_density(element) > 0.5
+}
+
+fn is_void(name: &str) -> bool {
+    matches!(
+        name,
+        "br" | "hr" | "img" | "wbr" | "source" | "track" | "area" | "col" | "meta" | "link"
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let path = format!(
+            "{}/tests/fixtures/readability/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+    }
+
+    fn extract_text(name: &str) -> String {
+        let html = extract_main_content(&fixture(name))
+            .unwrap_or_else(|| panic!("no main content found in {}", name));
+        // Collapse wrapping so phrases can be matched across line breaks
+        html2text::from_read(html.as_bytes(), 100)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn assert_boilerplate_removed(text: &str, boilerplate: &[&str]) {
+        for phrase in boilerplate {
+            assert!(
+                !text.contains(phrase),
+                "expected '{}' to be stripped from:\n{}",
+                phrase,
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_news_article() {
+        let text = extract_text("news_article.html");
+
+        assert!(text.contains("regulators in Brussels"));
+        assert!(text.contains("The company said it would appeal"));
+        assert_boilerplate_removed(
+            &text,
+            &[
+                "We use cookies",
+                "Sign up for our newsletter",
+                "More from Tech",
+                "Privacy Policy",
+                "Subscribe now",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_blog_post_with_comments() {
+        let text = extract_text("blog_post.html");
+
+        assert!(text.contains("the M4 MacBook Air"));
+        assert!(text.contains("battery life held up"));
+        assert_boilerplate_removed(
+            &text,
+            &["Leave a Reply", "Great review", "Recent Posts", "Share on"],
+        );
+    }
+
+    #[test]
+    fn test_div_soup_without_semantic_markup() {
+        let text = extract_text("div_soup.html");
+
+        assert!(text.contains("open-source model"));
+        assert!(text.contains("benchmarks published on Tuesday"));
+        assert_boilerplate_removed(&text, &["Trending Now", "Home | World", "Copyright"]);
+    }
+
+    #[test]
+    fn test_article_split_across_sibling_wrappers() {
+        let text = extract_text("split_article.html");
+
+        assert!(text.contains("first half of the story"));
+        assert!(text.contains("second half of the story"));
+        assert_boilerplate_removed(&text, &["Advertisement", "You might also like"]);
+    }
+
+    #[test]
+    fn test_hidden_and_script_content_removed() {
+        let text = extract_text("news_article.html");
+
+        assert_boilerplate_removed(&text, &["window.dataLayer", "Please enable JavaScript"]);
+    }
+
+    #[test]
+    fn test_page_without_article_returns_none() {
+        assert!(extract_main_content(&fixture("link_index.html")).is_none());
+    }
+
+    #[test]
+    fn test_words_match() {
+        assert!(words_match("site-nav", UNLIKELY_WORDS));
+        assert!(words_match("cookieBanner", &["cookie"]));
+        assert!(words_match("ad", UNLIKELY_WORDS));
+        assert!(!words_match("address", UNLIKELY_WORDS));
+        assert!(!words_match("", UNLIKELY_WORDS));
+    }
+}
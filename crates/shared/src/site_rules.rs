@@ -0,0 +1,377 @@
+use anyhow::{Context, Result};
+use scraper::{ElementRef, Html, Selector};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::metadata;
+
+/// Hand-written extraction rules for a site the generic heuristics get wrong.
+/// Every field is optional; anything left unset falls back to generic extraction.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SiteRule {
+    /// Article body; every match is kept, in page order
+    pub body: Option<String>,
+    pub title: Option<String>,
+    /// Read from the element's `datetime` or `content` attribute, else its text
+    pub date: Option<String>,
+    pub author: Option<String>,
+    /// Elements dropped before any extraction, e.g. inline ads or newsletter boxes
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Extra request headers, e.g. a `Referer` some sites insist on
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+}
+
+/// What a site rule found in a page
+#[derive(Debug, Default, PartialEq)]
+pub struct SiteExtraction {
+    /// Body HTML built from the `body` matches
+    pub body: Option<String>,
+    pub title: Option<String>,
+    pub published_date: Option<String>,
+    pub author: Option<String>,
+}
+
+impl SiteRule {
+    fn selectors(&self) -> impl Iterator<Item = (&'static str, &String)> {
+        [
+            ("body", self.body.as_ref()),
+            ("title", self.title.as_ref()),
+            ("date", self.date.as_ref()),
+            ("author", self.author.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(field, selector)| selector.map(|s| (field, s)))
+        .chain(self.remove.iter().map(|s| ("remove", s)))
+    }
+
+    fn validate(&self, domain: &str) -> Result<()> {
+        for (field, selector) in self.selectors() {
+            if let Err(e) = Selector::parse(selector) {
+                anyhow::bail!(
+                    "Invalid {} selector '{}' for {}: {}",
+                    field,
+                    selector,
+                    domain,
+                    e
+                );
+            }
+        }
+
+        for (name, value) in &self.headers {
+            reqwest::header::HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid header name '{}' for {}", name, domain))?;
+            reqwest::header::HeaderValue::from_str(value)
+                .with_context(|| format!("Invalid value for header '{}' for {}", name, domain))?;
+        }
+
+        Ok(())
+    }
+
+    /// A hash of everything in the rule, stored with cached articles so that
+    /// editing the rule refetches them
+    pub fn fingerprint(&self) -> String {
+        let mut hasher = Sha256::new();
+        for (field, selector) in self.selectors() {
+            hasher.update(format!("{}={}\n", field, selector));
+        }
+        let mut headers: Vec<_> = self.headers.iter().collect();
+        headers.sort();
+        for (name, value) in headers {
+            hasher.update(format!("header {}={}\n", name, value));
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// The page with everything matching `remove` cut out
+    pub fn strip(&self, html: &str) -> String {
+        if self.remove.is_empty() {
+            return html.to_string();
+        }
+
+        let mut document = Html::parse_document(html);
+        let ids: Vec<_> = self
+            .remove
+            .iter()
+            .filter_map(|s| Selector::parse(s).ok())
+            .flat_map(|selector| {
+                document
+                    .select(&selector)
+                    .map(|element| element.id())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        for id in ids {
+            if let Some(mut node) = document.tree.get_mut(id) {
+                node.detach();
+            }
+        }
+
+        document.html()
+    }
+
+    /// Pull out whatever the rule has selectors for
+    pub fn extract(&self, html: &str) -> SiteExtraction {
+        let document = Html::parse_document(html);
+        let select = |selector: &Option<String>| -> Vec<ElementRef> {
+            selector
+                .as_deref()
+                .and_then(|s| Selector::parse(s).ok())
+                .map(|selector| document.select(&selector).collect())
+                .unwrap_or_default()
+        };
+
+        let body = {
+            let matches = select(&self.body);
+            let ids: HashSet<_> = matches.iter().map(|element| element.id()).collect();
+            // Nested matches are already part of their ancestor's HTML
+            let outer: Vec<String> = matches
+                .iter()
+                .filter(|element| !element.ancestors().any(|a| ids.contains(&a.id())))
+                .filter(|element| !text_of(**element).is_empty())
+                .map(|element| element.html())
+                .collect();
+            (!outer.is_empty()).then(|| format!("<div>{}</div>", outer.concat()))
+        };
+
+        let title = select(&self.title)
+            .into_iter()
+            .map(|element| {
+                element
+                    .value()
+                    .attr("content")
+                    .map(|c| c.trim().to_string())
+                    .unwrap_or_else(|| text_of(element))
+            })
+            .find(|title| !title.is_empty());
+
+        let published_date = select(&self.date).into_iter().find_map(|element| {
+            let el = element.value();
+            el.attr("datetime")
+                .or_else(|| el.attr("content"))
+                .and_then(metadata::normalize_date)
+                .or_else(|| metadata::normalize_date(&text_of(element)))
+        });
+
+        let mut authors: Vec<String> = Vec::new();
+        for element in select(&self.author) {
+            let name = text_of(element);
+            if !name.is_empty() && !authors.contains(&name) {
+                authors.push(name);
+            }
+        }
+        let author = (!authors.is_empty()).then(|| authors.join(", "));
+
+        SiteExtraction {
+            body,
+            title,
+            published_date,
+            author,
+        }
+    }
+}
+
+fn text_of(element: ElementRef) -> String {
+    element
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Site rules keyed by domain, loaded from `site-rules.toml`:
+///
+/// ```toml
+/// ["9to5mac.com"]
+/// body = ".container.med.post-content"
+/// remove = [".ad-disclaimer-container", ".post-meta"]
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SiteRules {
+    rules: HashMap<String, SiteRule>,
+}
+
+impl SiteRules {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("podcast-briefing").join("site-rules.toml"))
+    }
+
+    /// Load the rules file if there is one, otherwise use no rules
+    pub fn load() -> Result<Self> {
+        match Self::default_path() {
+            Some(path) if path.exists() => Self::load_from(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read site rules: {}", path.display()))?;
+        Self::parse(&content)
+            .with_context(|| format!("Failed to parse site rules: {}", path.display()))
+    }
+
+    /// Parse a rules file, checking every selector and header up front so a
+    /// typo fails the run instead of silently skipping the rule
+    pub fn parse(content: &str) -> Result<Self> {
+        let raw: HashMap<String, SiteRule> = toml::from_str(content)?;
+
+        let mut rules = HashMap::new();
+        for (domain, rule) in raw {
+            rule.validate(&domain)?;
+            let domain = domain.trim().trim_start_matches('.').to_lowercase();
+            let domain = domain.strip_prefix("www.").unwrap_or(&domain).to_string();
+            rules.insert(domain, rule);
+        }
+
+        Ok(Self { rules })
+    }
+
+    /// The rule for `url`'s site; the most specific matching domain wins
+    pub fn rule_for(&self, url: &str) -> Option<&SiteRule> {
+        let host = crate::politeness::Politeness::host_key(url)?;
+        self.rules
+            .iter()
+            .filter(|(domain, _)| host == **domain || host.ends_with(&format!(".{}", domain)))
+            .max_by_key(|(domain, _)| domain.len())
+            .map(|(_, rule)| rule)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+        ["9to5mac.com"]
+        body = ".post-content"
+        title = "h1.post-title"
+        date = "time.published"
+        author = ".author-name"
+        remove = [".ad-disclaimer", ".newsletter"]
+
+        ["bloomberg.com"]
+        body = "article .body-copy"
+        headers = { Referer = "https://www.google.com/" }
+
+        ["news.bloomberg.com"]
+        body = ".news-body"
+    "#;
+
+    const PAGE: &str = r#"<html><body>
+        <h1 class="post-title">Apple ships  a thing</h1>
+        <time class="published" datetime="2026-02-01T09:30:00Z">Feb 1</time>
+        <span class="author-name">Jane Doe</span>
+        <span class="author-name">John Roe</span>
+        <span class="author-name">Jane Doe</span>
+        <div class="post-content">
+            <p>First paragraph.</p>
+            <div class="ad-disclaimer">Affiliate links may earn us money.</div>
+            <p>Second paragraph.</p>
+        </div>
+        <div class="newsletter">Sign up!</div>
+    </body></html>"#;
+
+    #[test]
+    fn test_parse_and_match_domains() {
+        let rules = SiteRules::parse(RULES).unwrap();
+        assert_eq!(rules.len(), 3);
+
+        let rule = rules
+            .rule_for("https://9to5mac.com/2026/02/01/story")
+            .unwrap();
+        assert_eq!(rule.body.as_deref(), Some(".post-content"));
+
+        let rule = rules.rule_for("https://www.bloomberg.com/news/a").unwrap();
+        assert_eq!(rule.headers["Referer"], "https://www.google.com/");
+
+        // The more specific subdomain rule wins
+        let rule = rules.rule_for("https://news.bloomberg.com/a").unwrap();
+        assert_eq!(rule.body.as_deref(), Some(".news-body"));
+
+        assert!(rules.rule_for("https://notbloomberg.com/a").is_none());
+        assert!(rules.rule_for("not a url").is_none());
+    }
+
+    #[test]
+    fn test_invalid_rules_rejected() {
+        let err = SiteRules::parse("[\"example.com\"]\nbody = \"div[\"\n").unwrap_err();
+        assert!(err.to_string().contains("Invalid body selector"));
+
+        let err = SiteRules::parse("[\"example.com\"]\nheaders = { \"Bad Name\" = \"x\" }\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid header name"));
+
+        assert!(SiteRules::parse("[\"example.com\"]\nbdy = \"article\"\n").is_err());
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let rules = SiteRules::parse(RULES).unwrap();
+        let rule = rules.rule_for("https://9to5mac.com/story").unwrap();
+        assert_eq!(rule.fingerprint(), rule.clone().fingerprint());
+
+        let mut edited = rule.clone();
+        edited.body = Some(".entry-content".to_string());
+        assert_ne!(rule.fingerprint(), edited.fingerprint());
+
+        let bloomberg = rules.rule_for("https://bloomberg.com/a").unwrap();
+        let mut edited = bloomberg.clone();
+        edited.headers.insert(
+            "Referer".to_string(),
+            "https://news.google.com/".to_string(),
+        );
+        assert_ne!(bloomberg.fingerprint(), edited.fingerprint());
+    }
+
+    #[test]
+    fn test_strip_and_extract() {
+        let rules = SiteRules::parse(RULES).unwrap();
+        let rule = rules.rule_for("https://9to5mac.com/story").unwrap();
+
+        let html = rule.strip(PAGE);
+        assert!(!html.contains("Affiliate links"));
+        assert!(!html.contains("Sign up!"));
+
+        let extraction = rule.extract(&html);
+        let body = extraction.body.unwrap();
+        assert!(body.contains("First paragraph."));
+        assert!(body.contains("Second paragraph."));
+        assert!(!body.contains("Affiliate links"));
+        assert_eq!(extraction.title.as_deref(), Some("Apple ships a thing"));
+        assert_eq!(
+            extraction.published_date.as_deref(),
+            Some("2026-02-01T09:30:00+00:00")
+        );
+        assert_eq!(extraction.author.as_deref(), Some("Jane Doe, John Roe"));
+    }
+
+    #[test]
+    fn test_extract_without_matches() {
+        let rule = SiteRule {
+            body: Some(".missing".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(rule.extract(PAGE), SiteExtraction::default());
+        assert_eq!(rule.strip(PAGE), PAGE);
+    }
+}
@@ -1,97 +1,448 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use futures::stream::{self, StreamExt};
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
-use tokio::sync::Semaphore;
+
+use crate::config::SummarySettings;
+use crate::llm::{self, JsonTool, LlmProvider};
+use crate::summary_cache::{self, SummaryCache};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Summary {
     Success {
         points: Vec<String>,
+        /// The quote's words, without quotation marks
         quote: Option<String>,
+        /// Who said the quote
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        speaker: Option<String>,
     },
     Insufficient,
     Failed(String),
 }
 
-#[derive(Serialize)]
-struct ClaudeRequest {
-    model: String,
-    max_tokens: u32,
-    messages: Vec<Message>,
+/// A quote as one line: `"words" -- Speaker`
+pub fn quote_line(quote: &str, speaker: Option<&str>) -> String {
+    // Quotes saved before the speaker was split out already carry their marks
+    let quote = if quote.starts_with(['"', '\u{201c}']) {
+        quote.to_string()
+    } else {
+        format!("\"{}\"", quote)
+    };
+    match speaker {
+        Some(speaker) => format!("{} -- {}", quote, speaker),
+        None => quote,
+    }
 }
 
-#[derive(Serialize, Deserialize)]
-struct Message {
-    role: String,
-    content: String,
+/// Split a line written by [`quote_line`] back into the quote and speaker
+pub fn split_quote_line(line: &str) -> (String, Option<String>) {
+    let line = line.trim();
+    let Some(rest) = line.strip_prefix(['"', '\u{201c}']) else {
+        return (line.to_string(), None);
+    };
+    let Some(end) = rest.rfind(['"', '\u{201d}']) else {
+        return (line.to_string(), None);
+    };
+
+    let (quote, after) = rest.split_at(end);
+    let mark_len = after.chars().next().map_or(0, char::len_utf8);
+    let speaker = after[mark_len..]
+        .trim_start_matches(|c: char| {
+            matches!(c, '-' | '\u{2013}' | '\u{2014}') || c.is_whitespace()
+        })
+        .trim();
+    (
+        quote.trim().to_string(),
+        (!speaker.is_empty()).then(|| speaker.to_string()),
+    )
 }
 
+const SUMMARY_TOOL_NAME: &str = "record_summary";
+
+/// Bump whenever the prompt, tool schema or validation changes, so cached
+/// summaries are made again
+const PROMPT_VERSION: u32 = 2;
+
+/// Points longer than this are rejected, leaving a little slack over the 20
+/// words asked for
+const MAX_POINT_WORDS: usize = 25;
+
+fn summary_tool() -> JsonTool {
+    JsonTool {
+        name: SUMMARY_TOOL_NAME,
+        description: "Record the key points and most important quote of an article",
+        schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "insufficient_content": {
+                    "type": "boolean",
+                    "description": "True if the article has fewer than 5 points worth making",
+                },
+                "points": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Exactly 5 key points from the article, each under 20 words",
+                },
+                "quote": {
+                    "type": "string",
+                    "description": "The most important direct quote, without quotation marks",
+                },
+                "speaker": {
+                    "type": "string",
+                    "description": "Who said the quote, as named in the article",
+                },
+            },
+            "required": ["insufficient_content", "points"],
+        }),
+    }
+}
+
+/// The summary tool's arguments
 #[derive(Deserialize)]
-struct ClaudeResponse {
-    content: Vec<Content>,
+struct SummaryArgs {
+    #[serde(default)]
+    insufficient_content: bool,
+    #[serde(default)]
+    points: Vec<String>,
+    quote: Option<String>,
+    speaker: Option<String>,
 }
 
+/// Turn the model's answer into a summary, or say what's wrong with it
+fn validate_summary(reply: Value) -> Result<Summary, String> {
+    let args: SummaryArgs =
+        serde_json::from_value(reply).map_err(|e| format!("Invalid summary fields: {}", e))?;
+    if args.insufficient_content {
+        return Ok(Summary::Insufficient);
+    }
+
+    let points: Vec<String> = args.points.iter().map(|p| p.trim().to_string()).collect();
+    if points.len() != 5 {
+        return Err(format!("Expected 5 points, got {}", points.len()));
+    }
+    if points.iter().any(|p| p.is_empty()) {
+        return Err("Empty point".to_string());
+    }
+    if let Some(point) = points
+        .iter()
+        .find(|p| p.split_whitespace().count() > MAX_POINT_WORDS)
+    {
+        return Err(format!("Point over {} words: '{}'", MAX_POINT_WORDS, point));
+    }
+
+    let clean = |s: Option<String>| {
+        s.map(|s| {
+            s.trim()
+                .trim_matches(['"', '\u{201c}', '\u{201d}'])
+                .trim()
+                .to_string()
+        })
+        .filter(|s| !s.is_empty())
+    };
+    let (quote, speaker) = match (clean(args.quote), clean(args.speaker)) {
+        (Some(_), None) => return Err("Quote has no speaker".to_string()),
+        (None, Some(_)) => return Err("Speaker given without a quote".to_string()),
+        pair => pair,
+    };
+
+    Ok(Summary::Success {
+        points,
+        quote,
+        speaker,
+    })
+}
+
+fn summary_prompt(content: &str) -> String {
+    format!(
+        r#"You are a text summarization specialist. Extract exactly 5 key points from the article below, and if there are any direct quotes, extract the most important one with attribution.
+
+RULES:
+1. Each point must be under 20 words
+2. Use ONLY text from the article - no external knowledge
+3. Each point must be supported by specific article content
+4. If fewer than 5 valid points exist, set insufficient_content to true and leave points empty
+5. Use only factual statements from the article text
+6. If there are direct quotes in the article, select the most important one (often the first quote, but use your judgment)
+7. Give the quote's words without quotation marks, and the speaker's name separately
+8. If there are no quotes, or no clear speaker attribution in the article, omit quote and speaker
+
+Article:
+{}
+
+Record your answer with the {} tool."#,
+        content, SUMMARY_TOOL_NAME
+    )
+}
+
+const NOTES_TOOL_NAME: &str = "record_notes";
+
+fn notes_tool() -> JsonTool {
+    JsonTool {
+        name: NOTES_TOOL_NAME,
+        description: "Record the key facts and quotes from one part of a long article",
+        schema: serde_json::json!({
+            "type": "object",
+            "properties": {
+                "facts": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "The most important facts in this part, each under 25 words",
+                },
+                "quotes": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "quote": { "type": "string" },
+                            "speaker": { "type": "string" },
+                        },
+                        "required": ["quote", "speaker"],
+                    },
+                    "description": "Direct quotes from this part, without quotation marks",
+                },
+            },
+            "required": ["facts"],
+        }),
+    }
+}
+
+/// The notes tool's arguments
 #[derive(Deserialize)]
-struct Content {
-    text: String,
+struct NotesArgs {
+    #[serde(default)]
+    facts: Vec<String>,
+    #[serde(default)]
+    quotes: Vec<NoteQuote>,
 }
 
-pub struct ClaudeSummarizer {
-    client: Client,
-    api_key: String,
-    semaphore: Arc<Semaphore>,
+#[derive(Deserialize)]
+struct NoteQuote {
+    quote: String,
+    speaker: String,
 }
 
-impl ClaudeSummarizer {
-    pub fn new(api_key: String) -> Result<Self> {
-        let client = Client::builder()
-            .timeout(std::time::Duration::from_secs(60))
-            .build()
-            .context("Failed to create HTTP client")?;
+fn notes_prompt(chunk: &str, part: usize, parts: usize) -> String {
+    format!(
+        r#"You are taking notes on part {part} of {parts} of a long article. The notes on every part will be summarized together afterwards.
 
-        // Reduce concurrency to avoid rate limits (50k tokens/min)
-        let semaphore = Arc::new(Semaphore::new(2));
+RULES:
+1. List up to 8 of the most important facts in this part, each under 25 words
+2. Use ONLY text from this part - no external knowledge
+3. Keep names, numbers, dates and outcomes exactly as the text gives them
+4. Copy up to 2 direct quotes from this part word for word, without quotation marks, each with the speaker's name
+5. Leave out quotes without a clear speaker
 
-        Ok(Self {
-            client,
-            api_key,
-            semaphore,
-        })
+Part {part} of {parts}:
+{chunk}
+
+Record your notes with the {tool} tool."#,
+        tool = NOTES_TOOL_NAME
+    )
+}
+
+/// Notes on every part, in order, standing in for the article text
+fn merged_notes(notes: &[NotesArgs]) -> String {
+    let mut text = String::from(
+        "(This article was too long to read at once. These are notes on each of its parts, \
+         in order; treat them as the article text and the quoted lines as its quotes.)\n",
+    );
+    for (index, part) in notes.iter().enumerate() {
+        text.push_str(&format!("\nPart {} of {}:\n", index + 1, notes.len()));
+        for fact in &part.facts {
+            text.push_str(&format!("- {}\n", fact.trim()));
+        }
+        for quote in &part.quotes {
+            text.push_str(&format!(
+                "- {}\n",
+                quote_line(quote.quote.trim(), Some(quote.speaker.trim()))
+            ));
+        }
+    }
+    text
+}
+
+/// Split `text` into overlapping chunks of about `chunk_chars`, breaking after
+/// a paragraph, sentence or word where possible. Texts that would need more
+/// than `max_chunks` get bigger chunks instead, so the end is never dropped.
+fn split_into_chunks<'a>(text: &'a str, settings: &SummarySettings) -> Vec<&'a str> {
+    let max_chunks = settings.max_chunks.max(1);
+    let overlap = settings.chunk_overlap_chars;
+    let needed = (text.len() + (max_chunks - 1) * overlap).div_ceil(max_chunks);
+    let chunk_len = settings.chunk_chars.max(needed).max(4);
+    // Keeps every chunk moving past the start of the one before
+    let overlap = overlap.min(chunk_len / 4);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while chunks.len() + 1 < max_chunks && text.len() - start > chunk_len {
+        let end = break_before(text, start + chunk_len, start + chunk_len / 2);
+        chunks.push(&text[start..end]);
+        start = break_before(
+            text,
+            end - overlap,
+            (end - overlap).saturating_sub(overlap).max(start + 1),
+        );
+    }
+    chunks.push(&text[start..]);
+    chunks
+}
+
+/// The best place to break `text` between `min` and `pos`: after a
+/// paragraph, then a line, a sentence or a word, else at `pos` itself
+fn break_before(text: &str, pos: usize, min: usize) -> usize {
+    let floor = |mut index: usize| {
+        while !text.is_char_boundary(index) {
+            index -= 1;
+        }
+        index
+    };
+    let pos = floor(pos);
+    let min = floor(min.min(pos));
+
+    let window = &text[min..pos];
+    for separator in ["\n\n", "\n", ". ", " "] {
+        if let Some(index) = window.rfind(separator) {
+            return min + index + separator.len();
+        }
+    }
+    pos
+}
+
+/// Articles summarized at once; how many calls actually run at once is up to
+/// the provider's rate limiter
+const ARTICLES_IN_FLIGHT: usize = 16;
+
+/// Summarizes articles with whichever model is configured for the task
+pub struct Summarizer {
+    provider: Arc<dyn LlmProvider>,
+    chunking: SummarySettings,
+    cache: Option<SummaryCache>,
+    /// Summarize again even when the cache has an answer, replacing it
+    refresh_cache: bool,
+    cache_hits: AtomicUsize,
+}
+
+impl Summarizer {
+    pub fn new(provider: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            provider,
+            chunking: SummarySettings::default(),
+            cache: None,
+            refresh_cache: false,
+            cache_hits: AtomicUsize::new(0),
+        }
+    }
+
+    /// Summarize long articles in chunks as `settings` describes
+    pub fn with_chunking(mut self, settings: SummarySettings) -> Self {
+        self.chunking = settings;
+        self
+    }
+
+    /// Reuse summaries of identical text from earlier runs; with `refresh`,
+    /// summarize everything again and overwrite what's cached
+    pub fn with_cache(mut self, cache: SummaryCache, refresh: bool) -> Self {
+        self.cache = Some(cache);
+        self.refresh_cache = refresh;
+        self
+    }
+
+    /// Provider and model, for progress output
+    pub fn provider_name(&self) -> String {
+        self.provider.name()
+    }
+
+    /// How many summaries came from the cache so far
+    pub fn cache_hits(&self) -> usize {
+        self.cache_hits.load(Ordering::Relaxed)
+    }
+
+    /// Too long to summarize in a single call
+    fn is_long(&self, content: &str) -> bool {
+        content.len() > self.chunking.long_article_chars
+    }
+
+    /// `content` cut at a break before `max_article_chars`, so a runaway
+    /// page can't turn into dozens of calls
+    fn capped<'a>(&self, content: &'a str) -> &'a str {
+        let max = self.chunking.max_article_chars;
+        if content.len() <= max {
+            return content;
+        }
+        let end = break_before(content, max, max - max / 10);
+        eprintln!(
+            "Article too long ({} chars), summarizing the first {}",
+            content.len(),
+            end
+        );
+        &content[..end]
+    }
+
+    fn cache_key(&self, content: &str) -> String {
+        let mut model = self.provider.name();
+        // How a long article is split changes what its summary says
+        if self.is_long(content) {
+            let settings = &self.chunking;
+            model.push_str(&format!(
+                " chunks {}/{}/{}",
+                settings.chunk_chars, settings.chunk_overlap_chars, settings.max_chunks
+            ));
+        }
+        summary_cache::cache_key(content, &model, PROMPT_VERSION)
+    }
+
+    fn cached(&self, content: &str) -> Option<Summary> {
+        if self.refresh_cache {
+            return None;
+        }
+        // A broken cache just means summarizing again
+        let summary = self.cache.as_ref()?.get(&self.cache_key(content)).ok()??;
+        self.cache_hits.fetch_add(1, Ordering::Relaxed);
+        Some(summary)
+    }
+
+    fn remember(&self, content: &str, summary: &Summary) {
+        if let Some(cache) = &self.cache {
+            if let Err(e) = cache.put(&self.cache_key(content), summary, chrono::Utc::now()) {
+                eprintln!("Failed to cache summary: {}", e);
+            }
+        }
     }
 
     pub async fn summarize_article(&self, content: &str) -> Result<Summary> {
-        let _permit = self.semaphore.acquire().await?;
+        let content = self.capped(content);
+        if let Some(summary) = self.cached(content) {
+            return Ok(summary);
+        }
 
+        // Notes on a long article's chunks outlive a failed attempt, so a
+        // retry picks up at the chunk that failed
+        let mut notes = Vec::new();
         for attempt in 0..5 {
-            match self.try_summarize(content).await {
+            match self.try_summarize(content, &mut notes).await {
                 Ok(summary) => {
-                    // Add small delay after successful request to spread load
-                    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+                    self.remember(content, &summary);
                     return Ok(summary);
                 }
                 Err(e) => {
-                    let error_msg = e.to_string();
-                    let is_rate_limit = error_msg.contains("rate_limit");
-
                     if attempt == 4 {
                         eprintln!("Failed to summarize: {}", e);
                         return Ok(Summary::Failed(e.to_string()));
                     }
 
-                    // Longer backoff for rate limits
-                    let backoff = if is_rate_limit {
-                        std::time::Duration::from_secs(15 * (attempt + 1) as u64)
-                    } else {
-                        std::time::Duration::from_millis(1000 * (2_u64.pow(attempt as u32)))
-                    };
-
-                    if is_rate_limit {
-                        eprintln!("Rate limit hit, waiting {:?} before retry...", backoff);
+                    // The rate limiter already holds the retry until the
+                    // API's Retry-After has passed
+                    if llm::is_rate_limit(&e) {
+                        eprintln!("Rate limit hit, retrying when the API allows...");
+                        continue;
                     }
 
+                    let backoff =
+                        std::time::Duration::from_millis(1000 * (2_u64.pow(attempt as u32)));
                     tokio::time::sleep(backoff).await;
                 }
             }
@@ -100,172 +451,144 @@ impl ClaudeSummarizer {
         Ok(Summary::Failed("Max retries reached".to_string()))
     }
 
-    async fn try_summarize(&self, content: &str) -> Result<Summary> {
-        // Truncate content to 10000 chars, respecting UTF-8 boundaries
-        let truncated_content = if content.len() > 10000 {
-            let mut end = 10000;
-            while end > 0 && !content.is_char_boundary(end) {
-                end -= 1;
-            }
-            &content[..end]
+    async fn try_summarize(&self, content: &str, notes: &mut Vec<NotesArgs>) -> Result<Summary> {
+        let prompt = if self.is_long(content) {
+            self.take_notes(content, notes).await?;
+            summary_prompt(&merged_notes(notes))
         } else {
-            content
+            summary_prompt(content)
         };
 
-        let prompt = format!(
-            r#"You are a text summarization specialist. Extract exactly 5 key points from the article below, and if there are any direct quotes, extract the most important one with attribution.
-
-RULES:
-1. Each point must be under 20 words
-2. Use ONLY text from the article - no external knowledge
-3. Each point must be supported by specific article content
-4. If fewer than 5 valid points exist, respond with: "Insufficient content for summary"
-5. Format: Bullet points using dashes (-)
-6. Use only factual statements from the article text
-7. If there are direct quotes in the article, select the most important one (often the first quote, but use your judgment)
-8. The quote should be on a line starting with "QUOTE: " followed by the quote text in quotation marks and attribution
-9. Format for quotes: QUOTE: "quote text" -- Speaker Name
-
-Article:
-{}
+        let tool = summary_tool();
+        let reply = self.provider.complete_json(&prompt, &tool).await?;
+        let problem = match validate_summary(reply.clone()) {
+            Ok(summary) => return Ok(summary),
+            Err(problem) => problem,
+        };
 
-Format your response as:
-QUOTE: "the most important quote if one exists" -- Speaker Name
-- First key point
-- Second key point
-- Third key point
-- Fourth key point
-- Fifth key point
-
-If there are no quotes in the article, omit the QUOTE line entirely.
-If there's a quote but no clear speaker attribution in the article, omit the QUOTE line."#,
-            truncated_content
+        // One chance to fix it, telling the model what was wrong
+        let repair_prompt = format!(
+            "{}\n\nYour previous answer was rejected: {}.\nPrevious answer: {}\n\n\
+             Call the {} tool again with a corrected answer.",
+            prompt, problem, reply, SUMMARY_TOOL_NAME
         );
+        let reply = self.provider.complete_json(&repair_prompt, &tool).await?;
+        Ok(validate_summary(reply).unwrap_or_else(Summary::Failed))
+    }
 
-        let request = ClaudeRequest {
-            model: "claude-3-5-haiku-20241022".to_string(),
-            max_tokens: 512,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-        };
-
-        let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send request to Claude API")?;
-
-        if !response.status().is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| String::from("unknown error"));
-            anyhow::bail!("Claude API error: {}", error_text);
+    /// Notes on each chunk of a long article not already in `notes`
+    async fn take_notes(&self, content: &str, notes: &mut Vec<NotesArgs>) -> Result<()> {
+        let chunks = split_into_chunks(content, &self.chunking);
+        let tool = notes_tool();
+        for (index, chunk) in chunks.iter().enumerate().skip(notes.len()) {
+            let prompt = notes_prompt(chunk, index + 1, chunks.len());
+            let reply = self.provider.complete_json(&prompt, &tool).await?;
+            let part: NotesArgs = serde_json::from_value(reply)
+                .map_err(|e| anyhow::anyhow!("Invalid notes on part {}: {}", index + 1, e))?;
+            notes.push(part);
         }
+        Ok(())
+    }
 
-        let claude_response = response
-            .json::<ClaudeResponse>()
-            .await
-            .context("Failed to parse Claude API response")?;
-
-        let summary_text = claude_response
-            .content
-            .first()
-            .map(|c| c.text.as_str())
-            .unwrap_or("");
-
-        if summary_text.contains("Insufficient content for summary") {
-            return Ok(Summary::Insufficient);
+    /// Summarize everything as one batch job, for unattended runs where
+    /// latency doesn't matter. Articles the batch doesn't summarize go
+    /// through [`Summarizer::summarize_articles_parallel`] instead, as do
+    /// long articles, whose chunks depend on one another.
+    pub async fn summarize_articles_batch(
+        &self,
+        articles: Vec<(String, String)>,
+    ) -> Vec<(String, Summary)> {
+        if !self.provider.supports_batch() {
+            eprintln!(
+                "  {} has no batch API, summarizing one at a time",
+                self.provider.name()
+            );
+            return self.summarize_articles_parallel(articles).await;
         }
 
-        let (quote, bullets) = self.parse_summary_with_quote(summary_text);
-
-        if bullets.len() == 5 {
-            Ok(Summary::Success {
-                points: bullets,
-                quote,
-            })
-        } else {
-            Ok(Summary::Failed(format!(
-                "Expected 5 bullets, got {}",
-                bullets.len()
-            )))
+        let mut summaries = Vec::new();
+        let mut articles_to_send = Vec::new();
+        let mut long_articles = Vec::new();
+        for (url, content) in articles {
+            match self.cached(&content) {
+                Some(summary) => summaries.push((url, summary)),
+                None if self.is_long(&content) => long_articles.push((url, content)),
+                None => articles_to_send.push((url, content)),
+            }
         }
+        // Long articles don't go in the batch, so their parts are
+        // summarized while the batch is being polled
+        let (batched, long) = tokio::join!(
+            self.summarize_batch(articles_to_send),
+            self.summarize_long_articles(long_articles)
+        );
+        summaries.extend(batched);
+        summaries.extend(long);
+        summaries
     }
 
-    fn parse_summary_with_quote(&self, text: &str) -> (Option<String>, Vec<String>) {
-        let mut quote = None;
-        let mut bullets = Vec::new();
-
-        for line in text.lines() {
-            let trimmed = line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
+    async fn summarize_batch(&self, articles: Vec<(String, String)>) -> Vec<(String, Summary)> {
+        if articles.is_empty() {
+            return Vec::new();
+        }
 
-            // Check for quote line
-            if trimmed.starts_with("QUOTE:") {
-                let quote_text = trimmed.strip_prefix("QUOTE:").unwrap().trim();
-                // Keep the quote as-is (it already includes quotes and attribution)
-                if !quote_text.is_empty() {
-                    quote = Some(quote_text.to_string());
-                }
-                continue;
-            }
+        // Batch ids can't hold a URL, so requests are numbered instead
+        let id = |index: usize| format!("article-{}", index);
+        let requests: Vec<(String, String)> = articles
+            .iter()
+            .enumerate()
+            .map(|(index, (_, content))| (id(index), summary_prompt(content)))
+            .collect();
 
-            // Check for bullet points
-            if let Some(stripped) = trimmed.strip_prefix(|c: char| c.is_numeric()) {
-                let stripped = stripped
-                    .trim_start_matches(|c: char| c == '.' || c == ')' || c.is_whitespace());
-                if !stripped.is_empty() {
-                    bullets.push(stripped.to_string());
-                }
-                continue;
+        let mut results = match self
+            .provider
+            .complete_json_batch(&requests, &summary_tool())
+            .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                eprintln!("  Batch failed ({}), summarizing one at a time", e);
+                return self.summarize_articles_parallel(articles).await;
             }
+        };
 
-            if trimmed.starts_with('-') || trimmed.starts_with('*') || trimmed.starts_with('•') {
-                let stripped = trimmed[1..].trim();
-                if !stripped.is_empty() {
-                    bullets.push(stripped.to_string());
+        let total = articles.len();
+        let mut summaries = Vec::new();
+        let mut leftovers = Vec::new();
+        for (index, (url, content)) in articles.into_iter().enumerate() {
+            let summary = match results.remove(&id(index)) {
+                Some(Ok(reply)) => validate_summary(reply).ok(),
+                _ => None,
+            };
+            match summary {
+                Some(summary) => {
+                    self.remember(&content, &summary);
+                    summaries.push((url, summary));
                 }
+                None => leftovers.push((url, content)),
             }
         }
 
-        (quote, bullets)
+        if !leftovers.is_empty() {
+            eprintln!(
+                "  {} of {} articles weren't summarized by the batch, retrying one at a time",
+                leftovers.len(),
+                total
+            );
+            summaries.extend(self.summarize_articles_parallel(leftovers).await);
+        }
+        summaries
     }
 
-    #[allow(dead_code)]
-    fn parse_bullet_points(&self, text: &str) -> Vec<String> {
-        text.lines()
-            .filter_map(|line| {
-                let trimmed = line.trim();
-                if trimmed.is_empty() {
-                    return None;
-                }
-                if let Some(stripped) = trimmed.strip_prefix(|c: char| c.is_numeric()) {
-                    let stripped = stripped
-                        .trim_start_matches(|c: char| c == '.' || c == ')' || c.is_whitespace());
-                    if !stripped.is_empty() {
-                        return Some(stripped.to_string());
-                    }
-                }
-                if trimmed.starts_with('-') || trimmed.starts_with('*') || trimmed.starts_with('•')
-                {
-                    let stripped = trimmed[1..].trim();
-                    if !stripped.is_empty() {
-                        return Some(stripped.to_string());
-                    }
-                }
-                None
-            })
-            .collect()
+    async fn summarize_long_articles(
+        &self,
+        articles: Vec<(String, String)>,
+    ) -> Vec<(String, Summary)> {
+        if articles.is_empty() {
+            return Vec::new();
+        }
+        eprintln!("  Summarizing {} long articles in parts", articles.len());
+        self.summarize_articles_parallel(articles).await
     }
 
     pub async fn summarize_articles_parallel(
@@ -286,10 +609,439 @@ If there's a quote but no clear speaker attribution in the article, omit the QUO
                     (url_clone, summary)
                 }
             })
-            .buffer_unordered(2) // Reduced to 2 to avoid rate limits
+            .buffer_unordered(ARTICLES_IN_FLIGHT)
             .collect()
             .await;
         eprintln!(); // Newline after dots
         results
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::FakeProvider;
+
+    fn reply(points: usize, quote: Option<&str>, speaker: Option<&str>) -> String {
+        serde_json::json!({
+            "insufficient_content": false,
+            "points": (1..=points).map(|n| format!("Point {}", n)).collect::<Vec<_>>(),
+            "quote": quote,
+            "speaker": speaker,
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_summarize_with_quote() {
+        let provider = Arc::new(FakeProvider::replying(reply(
+            5,
+            Some("We shipped it"),
+            Some("Jane Doe"),
+        )));
+        let summarizer = Summarizer::new(provider.clone());
+
+        let summary = summarizer.summarize_article("Article text").await.unwrap();
+        let Summary::Success {
+            points,
+            quote,
+            speaker,
+        } = summary
+        else {
+            panic!("expected a summary, got {:?}", summary);
+        };
+        assert_eq!(points.len(), 5);
+        assert_eq!(points[0], "Point 1");
+        assert_eq!(quote.as_deref(), Some("We shipped it"));
+        assert_eq!(speaker.as_deref(), Some("Jane Doe"));
+
+        let prompts = provider.prompts();
+        assert_eq!(prompts.len(), 1);
+        assert!(prompts[0].contains("Article text"));
+    }
+
+    #[tokio::test]
+    async fn test_summarize_insufficient() {
+        let summarizer = Summarizer::new(Arc::new(FakeProvider::replying(
+            r#"{"insufficient_content": true, "points": []}"#,
+        )));
+        assert!(matches!(
+            summarizer.summarize_article("Too short").await.unwrap(),
+            Summary::Insufficient
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_summarize_repairs_once() {
+        let provider = Arc::new(FakeProvider::new(|prompt| {
+            Ok(if prompt.contains("previous answer was rejected") {
+                reply(5, None, None)
+            } else {
+                reply(4, None, None)
+            })
+        }));
+        let summarizer = Summarizer::new(provider.clone());
+
+        let summary = summarizer.summarize_article("Article").await.unwrap();
+        assert!(matches!(summary, Summary::Success { .. }));
+        let prompts = provider.prompts();
+        assert_eq!(prompts.len(), 2);
+        assert!(prompts[1].contains("Expected 5 points, got 4"));
+    }
+
+    #[tokio::test]
+    async fn test_summarize_gives_up_after_repair() {
+        let provider = Arc::new(FakeProvider::replying(reply(5, Some("Words"), None)));
+        let summarizer = Summarizer::new(provider.clone());
+
+        let summary = summarizer.summarize_article("Article").await.unwrap();
+        assert!(matches!(summary, Summary::Failed(ref e) if e == "Quote has no speaker"));
+        assert_eq!(provider.prompts().len(), 2);
+    }
+
+    fn tool_use(points: usize) -> serde_json::Value {
+        serde_json::json!({
+            "content": [{
+                "type": "tool_use",
+                "id": "toolu_01",
+                "name": SUMMARY_TOOL_NAME,
+                "input": serde_json::from_str::<Value>(&reply(points, None, None)).unwrap(),
+            }],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_summarize_batch_with_fallback() {
+        use crate::llm::{AnthropicProvider, ModelConfig};
+        use std::time::Duration;
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/batches"))
+            .and(body_partial_json(serde_json::json!({
+                "requests": [{ "custom_id": "article-0" }, { "custom_id": "article-1" }],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msgbatch_01",
+                "processing_status": "in_progress",
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_01"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msgbatch_01",
+                "processing_status": "ended",
+                "results_url": format!("{}/v1/messages/batches/msgbatch_01/results", server.uri()),
+            })))
+            .mount(&server)
+            .await;
+        let results = [
+            serde_json::json!({
+                "custom_id": "article-0",
+                "result": { "type": "succeeded", "message": tool_use(5) },
+            }),
+            serde_json::json!({
+                "custom_id": "article-1",
+                "result": { "type": "errored", "error": { "type": "overloaded_error" } },
+            }),
+        ]
+        .map(|line| line.to_string())
+        .join("\n");
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_01/results"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(results))
+            .mount(&server)
+            .await;
+        // The errored request is summarized again the regular way
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(tool_use(5)))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let model = ModelConfig {
+            base_url: server.uri(),
+            model: "test-model".to_string(),
+            max_tokens: 512,
+            temperature: None,
+            timeout: Duration::from_secs(5),
+        };
+        let provider = AnthropicProvider::new("secret".to_string(), model)
+            .unwrap()
+            .with_batch_polling(Duration::from_millis(10), Duration::from_secs(5));
+        let summarizer = Summarizer::new(Arc::new(provider));
+
+        let mut summaries = summarizer
+            .summarize_articles_batch(vec![
+                ("https://example.com/a".to_string(), "First".to_string()),
+                ("https://example.com/b".to_string(), "Second".to_string()),
+            ])
+            .await;
+        summaries.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].0, "https://example.com/a");
+        assert!(summaries
+            .iter()
+            .all(|(_, summary)| matches!(summary, Summary::Success { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_summarize_batch_without_batch_api() {
+        let provider = Arc::new(FakeProvider::replying(reply(5, None, None)));
+        let summarizer = Summarizer::new(provider.clone());
+
+        let summaries = summarizer
+            .summarize_articles_batch(vec![(
+                "https://example.com/a".to_string(),
+                "First".to_string(),
+            )])
+            .await;
+        assert!(matches!(summaries[0].1, Summary::Success { .. }));
+        assert_eq!(provider.prompts().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_summary_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("summaries.sqlite");
+        let provider = Arc::new(FakeProvider::replying(reply(5, None, None)));
+
+        let summarizer =
+            Summarizer::new(provider.clone()).with_cache(SummaryCache::open(&path).unwrap(), false);
+        summarizer.summarize_article("Article").await.unwrap();
+        let summary = summarizer.summarize_article("Article").await.unwrap();
+        assert!(matches!(summary, Summary::Success { .. }));
+        assert_eq!(provider.prompts().len(), 1);
+        assert_eq!(summarizer.cache_hits(), 1);
+
+        // Different text is a different entry, even in a batch
+        let summaries = summarizer
+            .summarize_articles_batch(vec![
+                ("https://example.com/a".to_string(), "Article".to_string()),
+                ("https://example.com/b".to_string(), "Other".to_string()),
+            ])
+            .await;
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(provider.prompts().len(), 2);
+        assert_eq!(summarizer.cache_hits(), 2);
+
+        // Refreshing asks the model again
+        let summarizer =
+            Summarizer::new(provider.clone()).with_cache(SummaryCache::open(&path).unwrap(), true);
+        summarizer.summarize_article("Article").await.unwrap();
+        assert_eq!(provider.prompts().len(), 3);
+        assert_eq!(summarizer.cache_hits(), 0);
+    }
+
+    #[test]
+    fn test_split_into_chunks() {
+        let paragraph = "A sentence of filler text. ".repeat(20);
+        let text = format!("{}\n\n", paragraph.trim()).repeat(10);
+        let settings = SummarySettings {
+            long_article_chars: 1_000,
+            chunk_chars: 1_200,
+            chunk_overlap_chars: 100,
+            max_chunks: 8,
+            max_article_chars: 100_000,
+        };
+
+        let chunks = split_into_chunks(&text, &settings);
+        assert!(chunks.len() > 1 && chunks.len() <= 8);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 1_200));
+        assert!(text.starts_with(chunks[0]));
+        assert!(text.ends_with(chunks[chunks.len() - 1]));
+        // Breaks fall between sentences, and each chunk repeats a little of
+        // the one before
+        for pair in chunks.windows(2) {
+            assert!(pair[0].ends_with("\n\n") || pair[0].ends_with(". "));
+            let start = text.find(pair[1]).unwrap();
+            let end = text.find(pair[0]).unwrap() + pair[0].len();
+            assert!(start < end);
+        }
+
+        // Too long for the chunk count: bigger chunks, nothing dropped
+        let settings = SummarySettings {
+            max_chunks: 2,
+            ..settings
+        };
+        let chunks = split_into_chunks(&text, &settings);
+        assert_eq!(chunks.len(), 2);
+        assert!(text.ends_with(chunks[1]));
+
+        // Multi-byte text splits on character boundaries
+        let text = "\u{00e9}".repeat(5_000);
+        let chunks = split_into_chunks(&text, &settings);
+        assert_eq!(chunks.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_summarize_long_article_in_parts() {
+        let provider = Arc::new(FakeProvider::new(|prompt| {
+            Ok(if prompt.contains("You are taking notes on part") {
+                serde_json::json!({
+                    "facts": ["A fact from this part"],
+                    "quotes": [{ "quote": "It was late", "speaker": "The judge" }],
+                })
+                .to_string()
+            } else {
+                reply(5, Some("It was late"), Some("The judge"))
+            })
+        }));
+        let settings = SummarySettings {
+            long_article_chars: 1_000,
+            chunk_chars: 800,
+            chunk_overlap_chars: 100,
+            max_chunks: 8,
+            max_article_chars: 100_000,
+        };
+        let summarizer = Summarizer::new(provider.clone()).with_chunking(settings);
+
+        let article = format!("{}The verdict came last.", "Background text. ".repeat(150));
+        let summary = summarizer.summarize_article(&article).await.unwrap();
+        assert!(matches!(summary, Summary::Success { .. }));
+
+        let prompts = provider.prompts();
+        let parts = prompts.len() - 1;
+        assert!(parts >= 3);
+        assert!(prompts[parts - 1].contains("The verdict came last."));
+        let merge = &prompts[parts];
+        assert!(merge.contains(&format!("Part {} of {}:", parts, parts)));
+        assert!(merge.contains("- \"It was late\" -- The judge"));
+        assert!(!merge.contains("Background text."));
+
+        // Short articles still take a single call
+        summarizer.summarize_article("Short article").await.unwrap();
+        assert_eq!(provider.prompts().len(), prompts.len() + 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_keeps_notes_on_earlier_parts() {
+        let limited = std::sync::atomic::AtomicBool::new(false);
+        let provider = Arc::new(FakeProvider::new(move |prompt| {
+            if prompt.contains("You are taking notes on part 2")
+                && !limited.swap(true, Ordering::SeqCst)
+            {
+                return Err(crate::llm::ApiError {
+                    provider: "fake".to_string(),
+                    status: 429,
+                    body: "rate limited".to_string(),
+                }
+                .into());
+            }
+            Ok(if prompt.contains("You are taking notes on part") {
+                serde_json::json!({ "facts": ["A fact"], "quotes": [] }).to_string()
+            } else {
+                reply(5, None, None)
+            })
+        }));
+        let settings = SummarySettings {
+            long_article_chars: 1_000,
+            chunk_chars: 800,
+            chunk_overlap_chars: 100,
+            max_chunks: 8,
+            max_article_chars: 100_000,
+        };
+        let summarizer = Summarizer::new(provider.clone()).with_chunking(settings);
+
+        let article = "Background text. ".repeat(150);
+        let summary = summarizer.summarize_article(&article).await.unwrap();
+        assert!(matches!(summary, Summary::Success { .. }));
+
+        let prompts = provider.prompts();
+        let count = |part: &str| prompts.iter().filter(|p| p.contains(part)).count();
+        assert_eq!(count("You are taking notes on part 1 of"), 1);
+        assert_eq!(count("You are taking notes on part 2 of"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_long_article_capped() {
+        let provider = Arc::new(FakeProvider::new(|prompt| {
+            Ok(if prompt.contains("You are taking notes on part") {
+                serde_json::json!({ "facts": ["A fact"], "quotes": [] }).to_string()
+            } else {
+                reply(5, None, None)
+            })
+        }));
+        let settings = SummarySettings {
+            long_article_chars: 1_000,
+            chunk_chars: 800,
+            chunk_overlap_chars: 100,
+            max_chunks: 8,
+            max_article_chars: 2_000,
+        };
+        let summarizer = Summarizer::new(provider.clone()).with_chunking(settings);
+
+        let article = format!("{}The runaway tail.", "Background text. ".repeat(1_000));
+        summarizer.summarize_article(&article).await.unwrap();
+
+        let prompts = provider.prompts();
+        assert!(prompts.len() <= 4);
+        assert!(prompts.iter().all(|p| !p.contains("The runaway tail.")));
+    }
+
+    #[test]
+    fn test_validate_summary() {
+        let long_point = "word ".repeat(30);
+        let reply = serde_json::json!({
+            "insufficient_content": false,
+            "points": ["A", "B", "C", "D", long_point],
+        });
+        assert!(validate_summary(reply)
+            .unwrap_err()
+            .starts_with("Point over 25 words"));
+
+        // Quotation marks the model adds anyway are dropped
+        let reply = serde_json::json!({
+            "insufficient_content": false,
+            "points": ["A", "B", "C", "D", "E"],
+            "quote": "\u{201c}It works\u{201d}",
+            "speaker": " Tim Cook ",
+        });
+        let Ok(Summary::Success { quote, speaker, .. }) = validate_summary(reply) else {
+            panic!("expected a summary");
+        };
+        assert_eq!(quote.as_deref(), Some("It works"));
+        assert_eq!(speaker.as_deref(), Some("Tim Cook"));
+
+        assert!(validate_summary(serde_json::json!({ "points": "A" })).is_err());
+    }
+
+    #[test]
+    fn test_quote_line_round_trip() {
+        let line = quote_line("It works", Some("Tim Cook"));
+        assert_eq!(line, "\"It works\" -- Tim Cook");
+        assert_eq!(
+            split_quote_line(&line),
+            ("It works".to_string(), Some("Tim Cook".to_string()))
+        );
+
+        assert_eq!(
+            split_quote_line("\"This is a quote\" - Author Name"),
+            (
+                "This is a quote".to_string(),
+                Some("Author Name".to_string())
+            )
+        );
+        assert_eq!(
+            split_quote_line("\u{201c}Said \"this\"\u{201d} \u{2014} Jane"),
+            ("Said \"this\"".to_string(), Some("Jane".to_string()))
+        );
+        assert_eq!(
+            split_quote_line("\"No speaker\""),
+            ("No speaker".to_string(), None)
+        );
+        assert_eq!(split_quote_line("No marks"), ("No marks".to_string(), None));
+
+        // Quotes saved before the speaker was split out keep their marks
+        assert_eq!(
+            quote_line("\"Old style\" -- Someone", None),
+            "\"Old style\" -- Someone"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::cache_db;
+use crate::summarizer::Summary;
+
+/// Bump when `Summary` changes enough to invalidate old entries
+const SCHEMA_VERSION: i64 = 1;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS summaries (
+        key TEXT PRIMARY KEY,
+        summarized_at TEXT NOT NULL,
+        summary TEXT NOT NULL
+    );
+";
+
+/// The cache key for summarizing `text` with `model` and version
+/// `prompt_version` of the prompt, so a change to any of them misses
+pub fn cache_key(text: &str, model: &str, prompt_version: u32) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(prompt_version.to_le_bytes());
+    hasher.update(model.as_bytes());
+    hasher.update([0]);
+    hasher.update(text.as_bytes());
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Summaries keyed by a hash of the article text, model and prompt, so the
+/// same article isn't summarized again for another show or another night
+pub struct SummaryCache {
+    conn: Mutex<Connection>,
+}
+
+impl SummaryCache {
+    /// Open (or create) the cache database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn = cache_db::open(
+            path,
+            "summary cache",
+            SCHEMA_VERSION,
+            "DROP TABLE IF EXISTS summaries;",
+            SCHEMA,
+        )?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Open the cache in the default stories directory, shared by every show
+    pub fn open_default() -> Result<Self> {
+        let path = crate::io::get_default_stories_dir()?.join("summary-cache.sqlite");
+        Self::open(&path)
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<Summary>> {
+        let conn = self.conn.lock().unwrap();
+        let summary: Option<String> = conn
+            .query_row(
+                "SELECT summary FROM summaries WHERE key = ?1",
+                [key],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        // Treat unreadable entries as missing; the next summary replaces them
+        Ok(summary.and_then(|summary| serde_json::from_str(&summary).ok()))
+    }
+
+    /// Store a summary; failures aren't cached so they're tried again next run
+    pub fn put(&self, key: &str, summary: &Summary, now: DateTime<Utc>) -> Result<()> {
+        if matches!(summary, Summary::Failed(_)) {
+            return Ok(());
+        }
+
+        let summary = serde_json::to_string(summary)?;
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR REPLACE INTO summaries (key, summarized_at, summary) VALUES (?1, ?2, ?3)",
+            params![key, cache_db::timestamp(now), summary],
+        )
+        .context("Failed to update summary cache")?;
+        Ok(())
+    }
+
+    /// Drop entries summarized before `before`, returning how many were removed
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let removed = conn.execute(
+            "DELETE FROM summaries WHERE summarized_at < ?1",
+            [cache_db::timestamp(before)],
+        )?;
+        Ok(removed)
+    }
+
+    pub fn len(&self) -> Result<usize> {
+        let conn = self.conn.lock().unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM summaries", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    pub fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn summary(point: &str) -> Summary {
+        Summary::Success {
+            points: vec![point.to_string()],
+            quote: Some("It works".to_string()),
+            speaker: Some("Jane Doe".to_string()),
+        }
+    }
+
+    fn open_temp() -> (tempfile::TempDir, SummaryCache) {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SummaryCache::open(&dir.path().join("summaries.sqlite")).unwrap();
+        (dir, cache)
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let key = cache_key("Article text", "claude", 1);
+        assert_eq!(key.len(), 64);
+        assert_eq!(key, cache_key("Article text", "claude", 1));
+        assert_ne!(key, cache_key("Article text!", "claude", 1));
+        assert_ne!(key, cache_key("Article text", "llama", 1));
+        assert_ne!(key, cache_key("Article text", "claude", 2));
+    }
+
+    #[test]
+    fn test_put_and_get_round_trip() {
+        let (_dir, cache) = open_temp();
+        let now = Utc.with_ymd_and_hms(2026, 2, 1, 12, 0, 0).unwrap();
+        cache.put("abc", &summary("Point"), now).unwrap();
+        cache
+            .put("failed", &Summary::Failed("x".into()), now)
+            .unwrap();
+        cache.put("short", &Summary::Insufficient, now).unwrap();
+
+        let Some(Summary::Success {
+            points, speaker, ..
+        }) = cache.get("abc").unwrap()
+        else {
+            panic!("expected a cached summary");
+        };
+        assert_eq!(points, vec!["Point"]);
+        assert_eq!(speaker.as_deref(), Some("Jane Doe"));
+        assert!(matches!(
+            cache.get("short").unwrap(),
+            Some(Summary::Insufficient)
+        ));
+        assert!(cache.get("failed").unwrap().is_none());
+        assert!(cache.get("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_prune() {
+        let (_dir, cache) = open_temp();
+        let old = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+        let recent = Utc.with_ymd_and_hms(2026, 2, 1, 0, 0, 0).unwrap();
+        cache.put("old", &summary("Old"), old).unwrap();
+        cache.put("new", &summary("New"), recent).unwrap();
+
+        let removed = cache
+            .prune(Utc.with_ymd_and_hms(2026, 1, 15, 0, 0, 0).unwrap())
+            .unwrap();
+
+        assert_eq!(removed, 1);
+        assert_eq!(cache.len().unwrap(), 1);
+    }
+}
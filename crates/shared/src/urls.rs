@@ -0,0 +1,494 @@
+use anyhow::{Context, Result};
+use futures::stream::{self, StreamExt};
+use reqwest::Client;
+use scraper::{Html, Selector};
+use std::collections::HashMap;
+use std::time::Duration;
+use url::Url;
+
+/// Query parameters that only identify where a click came from
+const TRACKING_PARAMS: &[&str] = &[
+    "fbclid",
+    "gclid",
+    "gclsrc",
+    "dclid",
+    "msclkid",
+    "yclid",
+    "twclid",
+    "igshid",
+    "mc_cid",
+    "mc_eid",
+    "_hsenc",
+    "_hsmi",
+    "mkt_tok",
+    "guccounter",
+    "guce_referrer",
+    "guce_referrer_sig",
+    "ref_src",
+    "ref_url",
+    "smid",
+    "smtyp",
+    "cmpid",
+    "ocid",
+    "sr_share",
+    "vero_id",
+    "oly_anon_id",
+    "oly_enc_id",
+    "wt_mc",
+    "__twitter_impression",
+    "at_medium",
+    "at_campaign",
+    "ito",
+    "ncid",
+];
+
+/// Query parameter prefixes with the same purpose, e.g. `utm_source`
+const TRACKING_PREFIXES: &[&str] = &["utm_", "pk_", "mtm_", "hsa_", "__hs"];
+
+/// Link shorteners whose redirects are worth following before extraction
+const SHORTENERS: &[&str] = &[
+    "t.co",
+    "bit.ly",
+    "bitly.com",
+    "buff.ly",
+    "ow.ly",
+    "tinyurl.com",
+    "goo.gl",
+    "dlvr.it",
+    "trib.al",
+    "lnkd.in",
+    "fb.me",
+    "apple.co",
+    "amzn.to",
+    "flip.it",
+    "ift.tt",
+    "wp.me",
+    "shorturl.at",
+    "rebrand.ly",
+    "tiny.cc",
+    "is.gd",
+    "bbc.in",
+    "nyti.ms",
+    "wapo.st",
+    "reut.rs",
+    "on.wsj.com",
+    "econ.st",
+    "cnb.cx",
+    "engt.co",
+];
+
+/// Public suffixes with more than one label, and hosts that give each user a
+/// subdomain, so the registrable domain keeps one more label under them
+const MULTI_LABEL_SUFFIXES: &[&str] = &[
+    "co.uk",
+    "org.uk",
+    "ac.uk",
+    "gov.uk",
+    "com.au",
+    "net.au",
+    "org.au",
+    "co.nz",
+    "co.jp",
+    "co.in",
+    "co.za",
+    "com.br",
+    "com.cn",
+    "com.mx",
+    "github.io",
+    "blogspot.com",
+    "substack.com",
+    "medium.com",
+    "wordpress.com",
+];
+
+/// Drop tracking parameters from a URL, leaving everything else as written
+pub fn strip_tracking(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+
+    if let Some(query) = parsed.query() {
+        let kept: Vec<&str> = query
+            .split('&')
+            .filter(|pair| {
+                let key = pair.split('=').next().unwrap_or_default().to_lowercase();
+                !key.is_empty()
+                    && !TRACKING_PARAMS.contains(&key.as_str())
+                    && !TRACKING_PREFIXES
+                        .iter()
+                        .any(|prefix| key.starts_with(prefix))
+            })
+            .collect();
+        let kept = kept.join("&");
+        parsed.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    }
+
+    // Scroll-to-text fragments are per-click too
+    if parsed
+        .fragment()
+        .is_some_and(|fragment| fragment.is_empty() || fragment.starts_with(":~:"))
+    {
+        parsed.set_fragment(None);
+    }
+
+    parsed.to_string()
+}
+
+/// Rewrite Google AMP cache and AMP viewer links to the publisher's own URL,
+/// and drop parameters that ask for the AMP version of a page.
+///
+/// Publisher-hosted AMP pages (`/amp/`, `amp.` subdomains) vary too much to
+/// rewrite blindly; they're resolved from their `rel=canonical` link instead.
+pub fn de_amp(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    let host = parsed.host_str().unwrap_or_default().to_lowercase();
+    let segments: Vec<&str> = parsed
+        .path_segments()
+        .map(|s| s.collect())
+        .unwrap_or_default();
+
+    // {domain}.cdn.ampproject.org/c/s/{domain}/{path} and google.com/amp/s/{domain}/{path}
+    let inner = if host.ends_with("cdn.ampproject.org") {
+        segments.get(1..)
+    } else if host.starts_with("www.google.") || host.starts_with("google.") {
+        match segments.first() {
+            Some(&"amp") => segments.get(1..),
+            _ => None,
+        }
+    } else {
+        None
+    };
+    if let Some(inner) = inner {
+        let (scheme, rest) = match inner {
+            ["s", rest @ ..] => ("https", rest),
+            rest => ("http", rest),
+        };
+        if let Ok(target) = Url::parse(&format!("{}://{}", scheme, rest.join("/"))) {
+            if target.host_str().is_some_and(|h| h.contains('.')) {
+                return target.to_string();
+            }
+        }
+    }
+
+    let Some(query) = parsed.query() else {
+        return url.to_string();
+    };
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| {
+            let pair = pair.to_lowercase();
+            !matches!(
+                pair.as_str(),
+                "amp" | "amp=1" | "amp=true" | "outputtype=amp" | "_gsa=1"
+            )
+        })
+        .collect();
+    let kept = kept.join("&");
+    parsed.set_query((!kept.is_empty()).then_some(kept.as_str()));
+    parsed.to_string()
+}
+
+/// The URL with AMP wrappers and tracking parameters removed; no network access
+pub fn normalize(url: &str) -> String {
+    strip_tracking(&de_amp(url.trim()))
+}
+
+pub fn is_shortener(url: &str) -> bool {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_lowercase()))
+        .is_some_and(|host| {
+            let host = host.strip_prefix("www.").unwrap_or(&host);
+            SHORTENERS.contains(&host)
+        })
+}
+
+/// The part of `host` its owner registered, e.g. `example.co.uk` for
+/// `news.example.co.uk`
+fn registrable_domain(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_lowercase();
+    if host.parse::<std::net::IpAddr>().is_ok() || host.starts_with('[') {
+        return host;
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let suffix_labels = MULTI_LABEL_SUFFIXES
+        .iter()
+        .filter(|suffix| host.ends_with(&format!(".{}", suffix)))
+        .map(|suffix| suffix.split('.').count())
+        .max()
+        .unwrap_or(1);
+    let keep = (suffix_labels + 1).min(labels.len());
+    labels[labels.len() - keep..].join(".")
+}
+
+/// The URL a story should be listed under once its page has been fetched:
+/// the page's declared canonical URL when it's usable, otherwise the URL it
+/// was fetched from, cleaned either way
+pub fn canonical_url(page_url: &str, declared: Option<&str>) -> String {
+    let page = Url::parse(page_url).ok();
+    let declared = declared
+        .zip(page.as_ref())
+        .and_then(|(declared, page)| page.join(declared.trim()).ok())
+        .filter(|declared| matches!(declared.scheme(), "http" | "https"))
+        // Some sites point every page's canonical at the home page
+        .filter(|declared| {
+            declared.path() != "/" || page.as_ref().is_some_and(|page| page.path() == "/")
+        })
+        // A page can't speak for another site's URL
+        .filter(|declared| {
+            let domain = |url: &Url| url.host_str().map(registrable_domain);
+            page.as_ref()
+                .is_some_and(|page| domain(declared) == domain(page))
+        });
+
+    match declared {
+        Some(declared) => normalize(declared.as_str()),
+        None => normalize(page_url),
+    }
+}
+
+/// Follows short links to where they point before extraction
+pub struct UrlResolver {
+    client: Client,
+}
+
+impl UrlResolver {
+    pub fn new() -> Result<Self> {
+        // Shorteners serve browsers an HTML interstitial but send
+        // everything else a plain redirect
+        let client = Client::builder()
+            .timeout(Duration::from_secs(10))
+            .user_agent("collect-stories")
+            .build()
+            .context("Failed to create HTTP client")?;
+        Ok(Self { client })
+    }
+
+    /// Normalize `url`, expanding it first if it's a short link. Falls back
+    /// to the normalized original if the shortener can't be reached.
+    pub async fn resolve(&self, url: &str) -> String {
+        let url = normalize(url);
+        if !is_shortener(&url) {
+            return url;
+        }
+        match self.follow_redirects(&url).await {
+            Some(target) => normalize(&target),
+            None => url,
+        }
+    }
+
+    /// Resolve many URLs at once, returning original -> resolved
+    pub async fn resolve_all(&self, urls: Vec<String>) -> HashMap<String, String> {
+        stream::iter(urls)
+            .map(|url| async move {
+                let resolved = self.resolve(&url).await;
+                (url, resolved)
+            })
+            .buffer_unordered(10)
+            .collect()
+            .await
+    }
+
+    /// Where `url` ends up after HTTP redirects and any `<meta refresh>` hop
+    pub async fn follow_redirects(&self, url: &str) -> Option<String> {
+        let response = self.client.get(url).send().await.ok()?;
+        let final_url = response.url().to_string();
+        if final_url != url && !is_shortener(&final_url) {
+            return Some(final_url);
+        }
+
+        // Still on the shortener: look for an interstitial's refresh target
+        let body = response.text().await.ok()?;
+        let document = Html::parse_document(&body);
+        let selector = Selector::parse(r#"meta[http-equiv="refresh" i]"#).ok()?;
+        let content = document
+            .select(&selector)
+            .find_map(|meta| meta.value().attr("content"))?;
+        let target = content.split_once(';').map(|(_, target)| target.trim())?;
+        let target = match target.get(..4) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("url=") => &target[4..],
+            _ => target,
+        };
+        let target = target.trim().trim_matches(|c| c == '\'' || c == '"');
+        let target = Url::parse(&final_url).ok()?.join(target).ok()?;
+        Some(target.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_strip_tracking() {
+        assert_eq!(
+            strip_tracking(
+                "https://www.theverge.com/story?utm_source=twitter&utm_medium=social&id=7&fbclid=abc"
+            ),
+            "https://www.theverge.com/story?id=7"
+        );
+        assert_eq!(
+            strip_tracking("https://news.yahoo.com/a.html?guccounter=1&guce_referrer=aHR0"),
+            "https://news.yahoo.com/a.html"
+        );
+        assert_eq!(
+            strip_tracking("https://example.com/a#:~:text=quote"),
+            "https://example.com/a"
+        );
+        // Encoding of what's kept is left alone
+        assert_eq!(
+            strip_tracking("https://example.com/search?q=a%2Fb+c&UTM_CAMPAIGN=x#section"),
+            "https://example.com/search?q=a%2Fb+c#section"
+        );
+        assert_eq!(strip_tracking("not a url"), "not a url");
+    }
+
+    #[test]
+    fn test_de_amp() {
+        assert_eq!(
+            de_amp(
+                "https://www-theverge-com.cdn.ampproject.org/c/s/www.theverge.com/2026/2/1/story"
+            ),
+            "https://www.theverge.com/2026/2/1/story"
+        );
+        assert_eq!(
+            de_amp("https://www.google.com/amp/s/arstechnica.com/gadgets/2026/02/story/"),
+            "https://arstechnica.com/gadgets/2026/02/story/"
+        );
+        assert_eq!(
+            de_amp("https://www.cnbc.com/2026/02/01/story.html?outputType=amp"),
+            "https://www.cnbc.com/2026/02/01/story.html"
+        );
+        assert_eq!(
+            de_amp("https://www.google.com/search?q=amp"),
+            "https://www.google.com/search?q=amp"
+        );
+    }
+
+    #[test]
+    fn test_canonical_url() {
+        let page = "https://amp.example.com/news/story?utm_source=rss";
+        assert_eq!(
+            canonical_url(
+                page,
+                Some("https://www.example.com/news/story?utm_medium=amp")
+            ),
+            "https://www.example.com/news/story"
+        );
+        assert_eq!(
+            canonical_url("https://example.com/a/b", Some("/a/b-full")),
+            "https://example.com/a/b-full"
+        );
+        // Home page canonicals are a site bug, not the story's address
+        assert_eq!(
+            canonical_url(
+                "https://example.com/a?fbclid=1",
+                Some("https://example.com/")
+            ),
+            "https://example.com/a"
+        );
+        assert_eq!(
+            canonical_url("https://example.com/a", None),
+            "https://example.com/a"
+        );
+        // Only the page's own site can name its canonical URL
+        assert_eq!(
+            canonical_url(
+                "https://example.com/a",
+                Some("https://attacker.example.net/a")
+            ),
+            "https://example.com/a"
+        );
+        assert_eq!(
+            canonical_url(
+                "https://news.example.co.uk/a",
+                Some("https://www.example.co.uk/a")
+            ),
+            "https://www.example.co.uk/a"
+        );
+        assert_eq!(
+            canonical_url(
+                "https://alice.substack.com/p/a",
+                Some("https://bob.substack.com/p/a")
+            ),
+            "https://alice.substack.com/p/a"
+        );
+    }
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(registrable_domain("www.theverge.com"), "theverge.com");
+        assert_eq!(registrable_domain("news.bbc.co.uk"), "bbc.co.uk");
+        assert_eq!(registrable_domain("co.uk"), "co.uk");
+        assert_eq!(registrable_domain("localhost"), "localhost");
+        assert_eq!(registrable_domain("127.0.0.1"), "127.0.0.1");
+    }
+
+    #[test]
+    fn test_is_shortener() {
+        assert!(is_shortener("https://t.co/abc123"));
+        assert!(is_shortener("https://www.bit.ly/x"));
+        assert!(!is_shortener("https://www.theverge.com/x"));
+    }
+
+    #[tokio::test]
+    async fn test_follow_redirects() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/short"))
+            .respond_with(
+                ResponseTemplate::new(301)
+                    .insert_header("Location", format!("{}/story", server.uri())),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/story"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let resolver = UrlResolver::new().unwrap();
+        assert_eq!(
+            resolver
+                .follow_redirects(&format!("{}/short", server.uri()))
+                .await,
+            Some(format!("{}/story", server.uri()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_follow_meta_refresh() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<head><meta http-equiv="refresh" content="0;URL=https://www.example.com/story"></head>"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let resolver = UrlResolver::new().unwrap();
+        assert_eq!(
+            resolver
+                .follow_redirects(&format!("{}/abc", server.uri()))
+                .await
+                .as_deref(),
+            Some("https://www.example.com/story")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_leaves_regular_links_offline() {
+        let resolver = UrlResolver::new().unwrap();
+        assert_eq!(
+            resolver
+                .resolve("https://www.theverge.com/story?utm_source=rss")
+                .await,
+            "https://www.theverge.com/story"
+        );
+    }
+}
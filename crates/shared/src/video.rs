@@ -0,0 +1,687 @@
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+
+use crate::extractor::{ArticleContent, ExtractionError};
+use crate::metadata::{self, ArticleMetadata};
+use crate::politeness::Politeness;
+
+/// Start a new transcript paragraph after this many seconds of captions
+const PARAGRAPH_SECONDS: f64 = 60.0;
+
+/// Where video pages and APIs are fetched from. Overridable so tests can
+/// point them at a local stub.
+#[derive(Debug, Clone)]
+pub struct VideoEndpoints {
+    pub youtube: String,
+    pub vimeo: String,
+    pub vimeo_player: String,
+}
+
+impl Default for VideoEndpoints {
+    fn default() -> Self {
+        Self {
+            youtube: "https://www.youtube.com".to_string(),
+            vimeo: "https://vimeo.com".to_string(),
+            vimeo_player: "https://player.vimeo.com".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VideoPlatform {
+    YouTube,
+    Vimeo,
+}
+
+impl fmt::Display for VideoPlatform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VideoPlatform::YouTube => write!(f, "YouTube"),
+            VideoPlatform::Vimeo => write!(f, "Vimeo"),
+        }
+    }
+}
+
+/// A link to a single YouTube or Vimeo video
+#[derive(Debug, Clone, PartialEq)]
+pub enum VideoLink {
+    YouTube { id: String },
+    Vimeo { id: String },
+}
+
+impl VideoLink {
+    pub fn parse(url: &str) -> Option<Self> {
+        let parsed = url::Url::parse(url).ok()?;
+        let host = parsed.host_str()?.to_lowercase();
+        let host = host.strip_prefix("www.").unwrap_or(&host);
+        let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+
+        match host {
+            "youtube.com" | "m.youtube.com" | "music.youtube.com" => {
+                let id = match segments.as_slice() {
+                    ["watch"] => parsed
+                        .query_pairs()
+                        .find(|(key, _)| key == "v")
+                        .map(|(_, v)| v.to_string()),
+                    ["shorts" | "live" | "embed", id, ..] => Some(id.to_string()),
+                    _ => None,
+                }?;
+                is_youtube_id(&id).then_some(VideoLink::YouTube { id })
+            }
+            "youtu.be" => {
+                let id = segments.first()?.to_string();
+                is_youtube_id(&id).then_some(VideoLink::YouTube { id })
+            }
+            "vimeo.com" | "player.vimeo.com" => {
+                // vimeo.com/123, vimeo.com/channels/staffpicks/123,
+                // player.vimeo.com/video/123, vimeo.com/showcase/45/video/123
+                let is_id = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+                let after_video = segments
+                    .iter()
+                    .position(|s| *s == "video")
+                    .and_then(|i| segments.get(i + 1))
+                    .filter(|s| is_id(s));
+                let id = after_video.or_else(|| segments.iter().rev().find(|s| is_id(s)))?;
+                Some(VideoLink::Vimeo { id: id.to_string() })
+            }
+            _ => None,
+        }
+    }
+}
+
+fn is_youtube_id(id: &str) -> bool {
+    id.len() == 11
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// A video's details plus its captions, if it has any
+#[derive(Debug, Clone, PartialEq)]
+pub struct Video {
+    pub platform: VideoPlatform,
+    pub title: String,
+    pub channel: Option<String>,
+    /// RFC 3339
+    pub published: Option<String>,
+    pub description: Option<String>,
+    pub transcript: Option<String>,
+}
+
+impl Video {
+    /// The transcript as article text, or the description for videos without captions
+    pub fn into_article(self, url: &str) -> ArticleContent {
+        let source = match &self.channel {
+            Some(channel) => format!("{} on {}", channel, self.platform),
+            None => self.platform.to_string(),
+        };
+        let text = match (&self.transcript, &self.description) {
+            (Some(transcript), _) => {
+                format!(
+                    "Transcript of \"{}\" ({}):\n\n{}",
+                    self.title, source, transcript
+                )
+            }
+            (None, Some(description)) => format!(
+                "\"{}\" ({}). No captions available; video description:\n\n{}",
+                self.title, source, description
+            ),
+            (None, None) => format!("\"{}\" ({}). No captions available.", self.title, source),
+        };
+
+        ArticleContent {
+            text,
+            published_date: self.published,
+            metadata: ArticleMetadata {
+                author: self.channel,
+                publisher: Some(self.platform.to_string()),
+                canonical_url: Some(url.to_string()),
+                headline: Some(self.title),
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Fetch a video's details and captions
+pub async fn fetch_video(
+    client: &Client,
+    politeness: &Politeness,
+    endpoints: &VideoEndpoints,
+    link: &VideoLink,
+) -> Result<Video, ExtractionError> {
+    match link {
+        VideoLink::YouTube { id } => fetch_youtube(client, politeness, endpoints, id).await,
+        VideoLink::Vimeo { id } => fetch_vimeo(client, politeness, endpoints, id).await,
+    }
+}
+
+async fn get(
+    client: &Client,
+    politeness: &Politeness,
+    url: &str,
+) -> Result<reqwest::Response, ExtractionError> {
+    let response = politeness
+        .send(client.get(url))
+        .await
+        .map_err(ExtractionError::from_request)?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(ExtractionError::HttpStatus {
+            status: status.as_u16(),
+        });
+    }
+    Ok(response)
+}
+
+async fn get_text(
+    client: &Client,
+    politeness: &Politeness,
+    url: &str,
+) -> Result<String, ExtractionError> {
+    get(client, politeness, url)
+        .await?
+        .text()
+        .await
+        .map_err(ExtractionError::from_request)
+}
+
+/// Captions are a bonus; a failed download leaves the video without a transcript
+async fn fetch_captions(client: &Client, politeness: &Politeness, url: &str) -> Option<String> {
+    let vtt = get_text(client, politeness, url).await.ok()?;
+    let transcript = parse_vtt(&vtt);
+    (!transcript.is_empty()).then_some(transcript)
+}
+
+async fn fetch_youtube(
+    client: &Client,
+    politeness: &Politeness,
+    endpoints: &VideoEndpoints,
+    id: &str,
+) -> Result<Video, ExtractionError> {
+    let base = endpoints.youtube.trim_end_matches('/');
+    let page = get_text(client, politeness, &format!("{}/watch?v={}", base, id)).await?;
+
+    // The watch page embeds everything the player needs as a JSON blob
+    let player = page
+        .split_once("ytInitialPlayerResponse = ")
+        .and_then(|(_, rest)| {
+            serde_json::Deserializer::from_str(rest)
+                .into_iter::<Value>()
+                .next()?
+                .ok()
+        })
+        .ok_or_else(|| ExtractionError::Other {
+            message: "YouTube page had no player data".to_string(),
+        })?;
+
+    let details = &player["videoDetails"];
+    let Some(title) = details["title"].as_str() else {
+        let reason = player["playabilityStatus"]["reason"]
+            .as_str()
+            .unwrap_or("Video unavailable");
+        return Err(ExtractionError::Other {
+            message: reason.to_string(),
+        });
+    };
+
+    let microformat = &player["microformat"]["playerMicroformatRenderer"];
+    let published = microformat["publishDate"]
+        .as_str()
+        .or_else(|| microformat["uploadDate"].as_str())
+        .and_then(metadata::normalize_date);
+
+    // Prefer English captions a person wrote, then English auto-captions
+    let tracks = player["captions"]["playerCaptionsTracklistRenderer"]["captionTracks"]
+        .as_array()
+        .cloned()
+        .unwrap_or_default();
+    let rank = |track: &Value| {
+        let english = track["languageCode"]
+            .as_str()
+            .is_some_and(|lang| lang.starts_with("en"));
+        let generated = track["kind"] == "asr";
+        match (english, generated) {
+            (true, false) => 0,
+            (true, true) => 1,
+            (false, false) => 2,
+            (false, true) => 3,
+        }
+    };
+    let track_url = tracks
+        .iter()
+        .min_by_key(|track| rank(track))
+        .and_then(|track| track["baseUrl"].as_str());
+
+    let transcript = match track_url.and_then(|url| vtt_url(base, url)) {
+        Some(url) => fetch_captions(client, politeness, &url).await,
+        None => None,
+    };
+
+    Ok(Video {
+        platform: VideoPlatform::YouTube,
+        title: title.to_string(),
+        channel: details["author"]
+            .as_str()
+            .or_else(|| microformat["ownerChannelName"].as_str())
+            .map(String::from),
+        published,
+        description: details["shortDescription"]
+            .as_str()
+            .filter(|d| !d.trim().is_empty())
+            .map(String::from),
+        transcript,
+    })
+}
+
+/// A caption track's URL, relative ones under `base`, asking for WebVTT in
+/// place of whatever format it named
+fn vtt_url(base: &str, track_url: &str) -> Option<String> {
+    let url = if track_url.starts_with('/') {
+        format!("{}{}", base, track_url)
+    } else {
+        track_url.to_string()
+    };
+    let mut url = url::Url::parse(&url).ok()?;
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| key != "fmt")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair("fmt", "vtt");
+    Some(url.to_string())
+}
+
+#[derive(Debug, Deserialize)]
+struct VimeoOembed {
+    title: String,
+    #[serde(default)]
+    author_name: Option<String>,
+    #[serde(default)]
+    upload_date: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+async fn fetch_vimeo(
+    client: &Client,
+    politeness: &Politeness,
+    endpoints: &VideoEndpoints,
+    id: &str,
+) -> Result<Video, ExtractionError> {
+    let video_url = format!("https://vimeo.com/{}", id);
+    let oembed_url = url::Url::parse_with_params(
+        &format!("{}/api/oembed.json", endpoints.vimeo.trim_end_matches('/')),
+        &[("url", video_url.as_str())],
+    )
+    .map_err(|e| ExtractionError::Other {
+        message: e.to_string(),
+    })?;
+    let oembed: VimeoOembed = get(client, politeness, oembed_url.as_str())
+        .await?
+        .json()
+        .await
+        .map_err(|e| ExtractionError::Other {
+            message: format!("Unexpected Vimeo response: {}", e),
+        })?;
+
+    // Text tracks are listed in the player config; private videos won't have one
+    let player = endpoints.vimeo_player.trim_end_matches('/');
+    let config: Option<Value> = match get(
+        client,
+        politeness,
+        &format!("{}/video/{}/config", player, id),
+    )
+    .await
+    {
+        Ok(response) => response.json().await.ok(),
+        Err(_) => None,
+    };
+    let tracks = config
+        .as_ref()
+        .and_then(|config| config["request"]["text_tracks"].as_array())
+        .cloned()
+        .unwrap_or_default();
+    let track_url = tracks
+        .iter()
+        .min_by_key(|track| {
+            !track["lang"]
+                .as_str()
+                .is_some_and(|lang| lang.starts_with("en"))
+        })
+        .and_then(|track| track["url"].as_str());
+
+    let transcript = match track_url {
+        Some(url) if url.starts_with('/') => {
+            fetch_captions(client, politeness, &format!("{}{}", player, url)).await
+        }
+        Some(url) => fetch_captions(client, politeness, url).await,
+        None => None,
+    };
+
+    Ok(Video {
+        platform: VideoPlatform::Vimeo,
+        title: oembed.title,
+        channel: oembed.author_name,
+        published: oembed
+            .upload_date
+            .as_deref()
+            .and_then(|date| metadata::normalize_date(&date.replace(' ', "T"))),
+        description: oembed.description.filter(|d| !d.trim().is_empty()),
+        transcript,
+    })
+}
+
+/// Turn a WebVTT caption file into plain transcript text.
+///
+/// Drops the header, notes, cue settings and inline tags, merges the
+/// repeated lines that auto-generated "rolling" captions produce, and starts a
+/// new paragraph roughly every minute.
+pub fn parse_vtt(vtt: &str) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut paragraph_start: Option<f64> = None;
+    let mut last_line = String::new();
+
+    for block in vtt.replace("\r\n", "\n").split("\n\n") {
+        let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+        let Some(timing) = lines.next() else {
+            // Header, NOTE, STYLE and REGION blocks have no timing line
+            continue;
+        };
+        let start = timing
+            .split("-->")
+            .next()
+            .and_then(|t| parse_timestamp(t.trim()));
+
+        if let (Some(start), Some(paragraph)) = (start, paragraph_start) {
+            if start - paragraph >= PARAGRAPH_SECONDS && !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+                paragraph_start = None;
+            }
+        }
+
+        for line in lines {
+            let line = strip_tags(line);
+            let line = line.trim();
+            if line.is_empty() || line == last_line {
+                continue;
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(line);
+            last_line = line.to_string();
+            if paragraph_start.is_none() {
+                paragraph_start = start;
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs.join("\n\n")
+}
+
+/// `HH:MM:SS.mmm` or `MM:SS.mmm` in seconds
+fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let mut seconds = 0.0;
+    for part in timestamp.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(seconds)
+}
+
+/// Remove `<c>`, `<v Speaker>`, `<00:00:01.000>` and similar cue tags, and
+/// decode the entities captions use
+fn strip_tags(line: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in line.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&nbsp;", " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn politeness() -> Politeness {
+        Politeness::new(crate::config::PolitenessSettings {
+            host_delay_ms: 0,
+            ..Default::default()
+        })
+    }
+
+    const VTT: &str = "WEBVTT\nKind: captions\nLanguage: en\n\nNOTE generated\n\n\
+        00:00:00.000 --> 00:00:02.500 align:start position:0%\n\
+        <v Tim>Good morning, and welcome\n\n\
+        00:00:02.500 --> 00:00:05.000\n\
+        Good morning, and welcome\n\
+        to Apple Park &amp; the<00:00:03.100><c> keynote.</c>\n\n\
+        01:05.000 --> 01:07.000\n\
+        Let's talk about iPhone.\n";
+
+    #[test]
+    fn test_parse_links() {
+        let youtube = |id: &str| Some(VideoLink::YouTube { id: id.to_string() });
+        assert_eq!(
+            VideoLink::parse("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42s"),
+            youtube("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            VideoLink::parse("https://youtu.be/dQw4w9WgXcQ?si=abc"),
+            youtube("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            VideoLink::parse("https://m.youtube.com/shorts/dQw4w9WgXcQ"),
+            youtube("dQw4w9WgXcQ")
+        );
+        assert_eq!(
+            VideoLink::parse("https://vimeo.com/channels/staffpicks/76979871"),
+            Some(VideoLink::Vimeo {
+                id: "76979871".to_string()
+            })
+        );
+        assert!(VideoLink::parse("https://player.vimeo.com/video/76979871").is_some());
+        assert_eq!(
+            VideoLink::parse("https://vimeo.com/showcase/123/video/456"),
+            Some(VideoLink::Vimeo {
+                id: "456".to_string()
+            })
+        );
+        assert_eq!(
+            VideoLink::parse("https://vimeo.com/groups/7/videos/76979871"),
+            Some(VideoLink::Vimeo {
+                id: "76979871".to_string()
+            })
+        );
+
+        assert!(VideoLink::parse("https://www.youtube.com/@apple").is_none());
+        assert!(VideoLink::parse("https://www.youtube.com/watch?v=short").is_none());
+        assert!(VideoLink::parse("https://vimeo.com/about").is_none());
+    }
+
+    #[test]
+    fn test_vtt_url() {
+        assert_eq!(
+            vtt_url(
+                "https://www.youtube.com",
+                "/api/timedtext?v=abc&fmt=srv3&lang=en"
+            )
+            .as_deref(),
+            Some("https://www.youtube.com/api/timedtext?v=abc&lang=en&fmt=vtt")
+        );
+        assert_eq!(
+            vtt_url(
+                "https://www.youtube.com",
+                "https://example.com/api/timedtext?v=abc"
+            )
+            .as_deref(),
+            Some("https://example.com/api/timedtext?v=abc&fmt=vtt")
+        );
+    }
+
+    #[test]
+    fn test_parse_vtt() {
+        assert_eq!(
+            parse_vtt(VTT),
+            "Good morning, and welcome to Apple Park & the keynote.\n\nLet's talk about iPhone."
+        );
+        assert_eq!(parse_vtt("WEBVTT\n\n"), "");
+    }
+
+    #[tokio::test]
+    async fn test_youtube_with_captions() {
+        let server = MockServer::start().await;
+        let player = serde_json::json!({
+            "videoDetails": {
+                "title": "Apple Event - February 2026",
+                "author": "Apple",
+                "shortDescription": "Watch the special Apple Event."
+            },
+            "microformat": {"playerMicroformatRenderer": {"publishDate": "2026-02-01T10:00:00-08:00"}},
+            "captions": {"playerCaptionsTracklistRenderer": {"captionTracks": [
+                {"baseUrl": format!("{}/api/timedtext?v=abc&lang=de", server.uri()), "languageCode": "de"},
+                {"baseUrl": format!("{}/api/timedtext?v=abc&lang=en&kind=asr", server.uri()), "languageCode": "en", "kind": "asr"},
+                {"baseUrl": format!("{}/api/timedtext?v=abc&lang=en", server.uri()), "languageCode": "en"}
+            ]}}
+        });
+        Mock::given(method("GET"))
+            .and(path("/watch"))
+            .and(query_param("v", "dQw4w9WgXcQ"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                "<html><script>var ytInitialPlayerResponse = {};var meta = {{}};</script></html>",
+                player
+            )))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/timedtext"))
+            .and(query_param("lang", "en"))
+            .and(query_param("fmt", "vtt"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(VTT))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let endpoints = VideoEndpoints {
+            youtube: server.uri(),
+            ..Default::default()
+        };
+        let link = VideoLink::parse("https://youtu.be/dQw4w9WgXcQ").unwrap();
+        let video = fetch_video(&Client::new(), &politeness(), &endpoints, &link)
+            .await
+            .unwrap();
+
+        assert_eq!(video.title, "Apple Event - February 2026");
+        assert_eq!(video.channel.as_deref(), Some("Apple"));
+        assert_eq!(
+            video.published.as_deref(),
+            Some("2026-02-01T18:00:00+00:00")
+        );
+        assert!(video
+            .transcript
+            .as_deref()
+            .unwrap()
+            .starts_with("Good morning, and welcome"));
+
+        let article = video.into_article("https://youtu.be/dQw4w9WgXcQ");
+        assert!(article
+            .text
+            .starts_with("Transcript of \"Apple Event - February 2026\" (Apple on YouTube):"));
+        assert_eq!(
+            article.metadata.attribution().as_deref(),
+            Some("YouTube's Apple")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_youtube_unavailable() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"<script>var ytInitialPlayerResponse = {"playabilityStatus": {"status": "ERROR", "reason": "This video is private"}};</script>"#,
+            ))
+            .mount(&server)
+            .await;
+
+        let endpoints = VideoEndpoints {
+            youtube: server.uri(),
+            ..Default::default()
+        };
+        let link = VideoLink::YouTube {
+            id: "dQw4w9WgXcQ".to_string(),
+        };
+        assert_eq!(
+            fetch_video(&Client::new(), &politeness(), &endpoints, &link)
+                .await
+                .unwrap_err(),
+            ExtractionError::Other {
+                message: "This video is private".to_string()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_vimeo_without_captions() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/oembed.json"))
+            .and(query_param("url", "https://vimeo.com/76979871"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "title": "The New Vimeo Player",
+                "author_name": "Vimeo Staff",
+                "upload_date": "2013-10-15 14:08:29",
+                "description": "Introducing the new player."
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/video/76979871/config"))
+            .respond_with(ResponseTemplate::new(403))
+            .mount(&server)
+            .await;
+
+        let endpoints = VideoEndpoints {
+            vimeo: server.uri(),
+            vimeo_player: server.uri(),
+            ..Default::default()
+        };
+        let link = VideoLink::parse("https://vimeo.com/76979871").unwrap();
+        let video = fetch_video(&Client::new(), &politeness(), &endpoints, &link)
+            .await
+            .unwrap();
+
+        assert_eq!(video.channel.as_deref(), Some("Vimeo Staff"));
+        assert_eq!(
+            video.published.as_deref(),
+            Some("2013-10-15T14:08:29+00:00")
+        );
+        assert_eq!(video.transcript, None);
+        assert!(video
+            .into_article("https://vimeo.com/76979871")
+            .text
+            .ends_with("video description:\n\nIntroducing the new player."));
+    }
+}